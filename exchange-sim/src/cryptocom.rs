@@ -0,0 +1,147 @@
+use serde_json::{json, Value};
+
+pub fn heartbeat(id: i64) -> String {
+    json!({"id": id, "method": "public/heartbeat", "code": 0}).to_string()
+}
+
+/// Ticker of instrument in exchange format e.g. BTC_USD, bid and ask are (price, quantity)
+pub fn ticker(instrument: &str, bid: (&str, &str), ask: (&str, &str), timestamp: i64) -> String {
+    let data: Value = json!({
+        "h": ask.0,
+        "l": bid.0,
+        "a": bid.0,
+        "c": "0",
+        "b": bid.0,
+        "bs": bid.1,
+        "k": ask.0,
+        "ks": ask.1,
+        "i": instrument,
+        "v": "0",
+        "vv": "0",
+        "oi": "0",
+        "t": timestamp
+    });
+    subscription(instrument, "ticker", data)
+}
+
+pub fn trade(
+    instrument: &str,
+    id: &str,
+    price: &str,
+    quantity: &str,
+    side: &str,
+    timestamp: i64,
+) -> String {
+    let data: Value = json!({
+        "d": id,
+        "t": timestamp,
+        "p": price,
+        "q": quantity,
+        "s": side.to_uppercase(),
+        "i": instrument,
+        "m": id
+    });
+    subscription(instrument, "trade", data)
+}
+
+/// Full book with update id `sequence`, levels are (price, quantity)
+pub fn book_snapshot(
+    instrument: &str,
+    asks: &[(&str, &str)],
+    bids: &[(&str, &str)],
+    timestamp: i64,
+    sequence: i64,
+) -> String {
+    let data: Value = json!({
+        "asks": levels(asks),
+        "bids": levels(bids),
+        "t": timestamp,
+        "u": sequence
+    });
+    subscription(instrument, "book", data)
+}
+
+/// Delta with update id `sequence` following `previous`, a gap when previous was not sent.
+/// Zero quantity removes the level.
+pub fn book_update(
+    instrument: &str,
+    asks: &[(&str, &str)],
+    bids: &[(&str, &str)],
+    timestamp: i64,
+    sequence: i64,
+    previous: i64,
+) -> String {
+    let data: Value = json!({
+        "update": {"asks": levels(asks), "bids": levels(bids)},
+        "t": timestamp,
+        "u": sequence,
+        "pu": previous
+    });
+    subscription(instrument, "book.update", data)
+}
+
+/// Response of a rejected request
+pub fn error(id: i64, method: &str, code: i64, message: &str) -> String {
+    json!({"id": id, "method": method, "code": code, "message": message}).to_string()
+}
+
+/// Body of public/get-instruments with spot pairs e.g. ("BTC", "USD")
+pub fn instruments(pairs: &[(&str, &str)]) -> String {
+    let data: Vec<Value> = pairs
+        .iter()
+        .map(|(base, quote)| {
+            json!({
+                "symbol": format!("{}_{}", base, quote),
+                "inst_type": "CCY_PAIR",
+                "display_name": format!("{}/{}", base, quote),
+                "base_ccy": base,
+                "quote_ccy": quote,
+                "quote_decimals": 2,
+                "quantity_decimals": 5,
+                "price_tick_size": "0.01",
+                "qty_tick_size": "0.00001",
+                "max_leverage": "50",
+                "tradable": true,
+                "expiry_timestamp_ms": 0,
+                "beta_product": false,
+                "margin_buy_enabled": true,
+                "margin_sell_enabled": true
+            })
+        })
+        .collect();
+
+    json!({
+        "id": -1,
+        "method": "public/get-instruments",
+        "code": 0,
+        "result": {"data": data}
+    })
+    .to_string()
+}
+
+/// Body of failed REST request
+pub fn rest_error(code: i64, message: &str) -> String {
+    json!({"code": code, "message": message}).to_string()
+}
+
+fn subscription(instrument: &str, channel: &str, data: Value) -> String {
+    json!({
+        "id": -1,
+        "method": "subscribe",
+        "code": 0,
+        "result": {
+            "instrument_name": instrument,
+            "subscription": format!("{}.{}", channel, instrument),
+            "channel": channel,
+            "data": [data]
+        }
+    })
+    .to_string()
+}
+
+fn levels(levels: &[(&str, &str)]) -> Vec<Value> {
+    levels
+        .iter()
+        .map(|(price, quantity)| json!([price, quantity, "1"]))
+        .collect()
+}
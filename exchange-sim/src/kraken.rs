@@ -0,0 +1,65 @@
+use serde_json::{json, Value};
+
+/// Body of AssetPairs endpoint with online spot pairs e.g. ("XBT", "USD")
+pub fn asset_pairs(pairs: &[(&str, &str)]) -> String {
+    let result: serde_json::Map<String, Value> = pairs
+        .iter()
+        .map(|(base, quote)| {
+            let pair: Value = json!({
+                "altname": format!("{}{}", base, quote),
+                "wsname": format!("{}/{}", base, quote),
+                "base": base,
+                "quote": quote,
+                "pair_decimals": 1,
+                "cost_decimals": 5,
+                "lot_decimals": 8,
+                "ordermin": "0.0001",
+                "costmin": "0.5",
+                "tick_size": "0.1",
+                "status": "online"
+            });
+            (format!("{}{}", base, quote), pair)
+        })
+        .collect();
+
+    json!({"error": [], "result": result}).to_string()
+}
+
+/// Body of futures instruments endpoint with perpetual contracts e.g. PF_XBTUSD
+pub fn futures_instruments(symbols: &[&str]) -> String {
+    let instruments: Vec<Value> = symbols
+        .iter()
+        .map(|symbol| {
+            json!({
+                "symbol": symbol,
+                "type": "flexible_futures",
+                "tradeable": true,
+                "tickSize": 0.5,
+                "contractSize": 1,
+                "contractValueTradePrecision": 4,
+                "postOnly": false
+            })
+        })
+        .collect();
+
+    json!({"result": "success", "instruments": instruments}).to_string()
+}
+
+/// Body of failed REST request
+pub fn rest_error(message: &str) -> String {
+    json!({"error": [message], "result": {}}).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kraken::asset_pairs;
+    use serde_json::Value;
+
+    #[test]
+    fn asset_pairs_should_key_pairs_by_altname() {
+        let body: Value = serde_json::from_str(&asset_pairs(&[("XBT", "USD")])).unwrap();
+
+        assert_eq!(body["result"]["XBTUSD"]["wsname"], "XBT/USD");
+        assert_eq!(body["error"].as_array().map(Vec::len), Some(0));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod cryptocom;
+pub mod kraken;
+pub mod nats;
+pub mod scenario;
+pub mod server;
@@ -0,0 +1,516 @@
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use protocol::client::NatsConfig;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::{self, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
+
+const NO_RESPONDERS: &[u8] = b"NATS/1.0 503\r\n\r\n";
+const NATS_SERVER: &str = "nats-server";
+const NATS_SERVER_ENV: &str = "NATS_SERVER_BIN";
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// NATS server listening on a random local port. Runs `nats-server -js` taken from
+/// NATS_SERVER_BIN or PATH, so JetStream key value buckets (leader election, sharding
+/// membership, shared listing) work. Without the binary falls back to in-process server
+/// with core protocol only (pub/sub, headers, queue groups, request no responders).
+pub struct EmbeddedNats {
+    address: SocketAddr,
+    server: Running,
+}
+
+enum Running {
+    Process { child: Child, store: PathBuf },
+    InProcess(JoinHandle<()>),
+}
+
+impl EmbeddedNats {
+    pub async fn start() -> Result<EmbeddedNats> {
+        match binary() {
+            Some(binary) => EmbeddedNats::process(&binary).await,
+            None => {
+                warn!("{} not found, NATS runs without JetStream", NATS_SERVER);
+                EmbeddedNats::in_process().await
+            }
+        }
+    }
+
+    /// Real server with JetStream, fails when nats-server is not installed
+    pub async fn jetstream() -> Result<EmbeddedNats> {
+        let binary: PathBuf = binary()
+            .ok_or_else(|| anyhow!("{} not found in {} or PATH", NATS_SERVER, NATS_SERVER_ENV))?;
+
+        EmbeddedNats::process(&binary).await
+    }
+
+    pub fn has_jetstream(&self) -> bool {
+        matches!(self.server, Running::Process { .. })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.address.port()
+    }
+
+    pub fn config(&self) -> NatsConfig {
+        NatsConfig {
+            host: self.address.ip().to_string(),
+            port: self.port(),
+            max_reconnects: 0,
+        }
+    }
+
+    async fn process(binary: &Path) -> Result<EmbeddedNats> {
+        let address: SocketAddr = TcpListener::bind("127.0.0.1:0").await?.local_addr()?;
+        let store: PathBuf = env::temp_dir().join(format!(
+            "embedded-nats-{}-{}",
+            process::id(),
+            address.port()
+        ));
+
+        let child: Child = Command::new(binary)
+            .args([
+                "-js",
+                "-a",
+                "127.0.0.1",
+                "-p",
+                &address.port().to_string(),
+                "-sd",
+            ])
+            .arg(&store)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let nats: EmbeddedNats = EmbeddedNats {
+            address,
+            server: Running::Process { child, store },
+        };
+
+        timeout(STARTUP_TIMEOUT, async {
+            while TcpStream::connect(address).await.is_err() {
+                sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .map_err(|_| anyhow!("{} not listening within {:?}", NATS_SERVER, STARTUP_TIMEOUT))?;
+
+        Ok(nats)
+    }
+
+    async fn in_process() -> Result<EmbeddedNats> {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await?;
+        let address: SocketAddr = listener.local_addr()?;
+        let server: Arc<Server> = Arc::new(Server::default());
+
+        let task: JoinHandle<()> = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(server.clone(), stream, address.port()));
+            }
+        });
+
+        Ok(EmbeddedNats {
+            address,
+            server: Running::InProcess(task),
+        })
+    }
+}
+
+impl Drop for EmbeddedNats {
+    fn drop(&mut self) {
+        match &mut self.server {
+            Running::Process { child, store } => {
+                child.start_kill().unwrap_or_default();
+                fs::remove_dir_all(store).unwrap_or_default();
+            }
+            Running::InProcess(task) => task.abort(),
+        }
+    }
+}
+
+/// nats-server binary from NATS_SERVER_BIN or PATH
+fn binary() -> Option<PathBuf> {
+    if let Some(binary) = env::var_os(NATS_SERVER_ENV) {
+        return Some(PathBuf::from(binary));
+    }
+
+    env::var_os("PATH").and_then(|paths| {
+        env::split_paths(&paths)
+            .map(|path| path.join(NATS_SERVER))
+            .find(|path| path.is_file())
+    })
+}
+
+#[derive(Default)]
+struct Server {
+    ids: AtomicU64,
+    deliveries: AtomicU64,
+    clients: Mutex<HashMap<u64, Client>>,
+}
+
+struct Client {
+    out: UnboundedSender<Vec<u8>>,
+    headers: bool,
+    no_responders: bool,
+    subscriptions: HashMap<String, Subscription>,
+}
+
+struct Subscription {
+    subject: String,
+    queue: Option<String>,
+    remaining: Option<u64>,
+}
+
+struct Publish<'a> {
+    subject: &'a str,
+    reply: Option<&'a str>,
+    headers: Option<&'a [u8]>,
+    payload: &'a [u8],
+}
+
+async fn serve(server: Arc<Server>, stream: TcpStream, port: u16) {
+    let id: u64 = server.ids.fetch_add(1, Ordering::Relaxed) + 1;
+    let (reader, mut writer) = stream.into_split();
+    let (out, mut out_receiver): (UnboundedSender<Vec<u8>>, UnboundedReceiver<Vec<u8>>) =
+        unbounded_channel();
+
+    let writer_task: JoinHandle<()> = tokio::spawn(async move {
+        while let Some(bytes) = out_receiver.recv().await {
+            if writer.write_all(&bytes).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let info: Value = json!({
+        "server_id": "EMBEDDED",
+        "server_name": "exchange-sim",
+        "version": "2.10.0",
+        "proto": 1,
+        "host": "127.0.0.1",
+        "port": port,
+        "headers": true,
+        "max_payload": 8388608,
+        "client_id": id,
+    });
+    out.send(format!("INFO {}\r\n", info).into_bytes())
+        .unwrap_or_default();
+
+    server.clients.lock().expect("nats clients").insert(
+        id,
+        Client {
+            out,
+            headers: false,
+            no_responders: false,
+            subscriptions: HashMap::new(),
+        },
+    );
+
+    if let Err(error) = read(&server, id, BufReader::new(reader)).await {
+        debug!("Nats client {} disconnected: {}", id, error);
+    }
+
+    server.clients.lock().expect("nats clients").remove(&id);
+    writer_task.abort();
+}
+
+async fn read(server: &Server, id: u64, mut reader: BufReader<OwnedReadHalf>) -> Result<()> {
+    let mut line: String = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(());
+        }
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let Some(operation) = parts.first().map(|operation| operation.to_uppercase()) else {
+            continue;
+        };
+
+        match (operation.as_str(), parts.as_slice()) {
+            ("CONNECT", _) => {
+                let options: Value = serde_json::from_str(line.trim()["CONNECT".len()..].trim())?;
+                server.client(id, |client| {
+                    client.headers = options["headers"].as_bool().unwrap_or_default();
+                    client.no_responders = options["no_responders"].as_bool().unwrap_or_default();
+                    if options["verbose"].as_bool().unwrap_or_default() {
+                        client.send(b"+OK\r\n".to_vec());
+                    }
+                });
+            }
+            ("PING", _) => server.client(id, |client| client.send(b"PONG\r\n".to_vec())),
+            ("PONG", _) => {}
+            ("SUB", [_, subject, sid]) => server.subscribe(id, subject, None, sid),
+            ("SUB", [_, subject, queue, sid]) => server.subscribe(id, subject, Some(queue), sid),
+            ("UNSUB", [_, sid]) => server.unsubscribe(id, sid, None),
+            ("UNSUB", [_, sid, max]) => server.unsubscribe(id, sid, Some(max.parse()?)),
+            ("PUB", [_, subject, reply @ .., size]) => {
+                let payload: Vec<u8> = payload(&mut reader, size.parse()?).await?;
+                server.route(
+                    id,
+                    Publish {
+                        subject,
+                        reply: reply.first().copied(),
+                        headers: None,
+                        payload: &payload,
+                    },
+                );
+            }
+            ("HPUB", [_, subject, reply @ .., headers_size, size]) => {
+                let headers_size: usize = headers_size.parse()?;
+                let bytes: Vec<u8> = payload(&mut reader, size.parse()?).await?;
+                let (headers, payload) = bytes.split_at(headers_size.min(bytes.len()));
+                server.route(
+                    id,
+                    Publish {
+                        subject,
+                        reply: reply.first().copied(),
+                        headers: Some(headers),
+                        payload,
+                    },
+                );
+            }
+            _ => {
+                warn!("Unknown nats operation {}", line.trim());
+                server.client(id, |client| {
+                    client.send(b"-ERR 'Unknown Protocol Operation'\r\n".to_vec())
+                });
+            }
+        }
+    }
+}
+
+/// Message body followed by CRLF
+async fn payload(reader: &mut BufReader<OwnedReadHalf>, size: usize) -> Result<Vec<u8>> {
+    let mut bytes: Vec<u8> = vec![0; size + 2];
+    reader.read_exact(&mut bytes).await?;
+
+    if !bytes.ends_with(b"\r\n") {
+        return Err(anyhow!("Payload without CRLF"));
+    }
+
+    bytes.truncate(size);
+    Ok(bytes)
+}
+
+impl Server {
+    fn client<F: FnOnce(&mut Client)>(&self, id: u64, action: F) {
+        if let Some(client) = self.clients.lock().expect("nats clients").get_mut(&id) {
+            action(client);
+        }
+    }
+
+    fn subscribe(&self, id: u64, subject: &str, queue: Option<&str>, sid: &str) {
+        self.client(id, |client| {
+            client.subscriptions.insert(
+                sid.to_string(),
+                Subscription {
+                    subject: subject.to_string(),
+                    queue: queue.map(str::to_string),
+                    remaining: None,
+                },
+            );
+        });
+    }
+
+    fn unsubscribe(&self, id: u64, sid: &str, max: Option<u64>) {
+        self.client(id, |client| match max {
+            Some(max) => {
+                if let Some(subscription) = client.subscriptions.get_mut(sid) {
+                    subscription.remaining = Some(max);
+                }
+            }
+            None => {
+                client.subscriptions.remove(sid);
+            }
+        });
+    }
+
+    /// Delivers to every plain subscription and to one member of each queue group,
+    /// requests without any subscriber get 503 status when the sender supports it
+    fn route(&self, sender: u64, message: Publish) {
+        let mut clients: MutexGuard<HashMap<u64, Client>> =
+            self.clients.lock().expect("nats clients");
+        let mut targets: Vec<(u64, String)> = Vec::new();
+        let mut groups: HashMap<String, Vec<(u64, String)>> = HashMap::new();
+
+        for (id, client) in clients.iter() {
+            for (sid, subscription) in &client.subscriptions {
+                if !matches(&subscription.subject, message.subject) {
+                    continue;
+                }
+
+                match &subscription.queue {
+                    Some(queue) => groups
+                        .entry(queue.clone())
+                        .or_default()
+                        .push((*id, sid.clone())),
+                    None => targets.push((*id, sid.clone())),
+                }
+            }
+        }
+
+        let turn: usize = self.deliveries.fetch_add(1, Ordering::Relaxed) as usize;
+        targets.extend(
+            groups
+                .into_values()
+                .map(|mut members| members.swap_remove(turn % members.len())),
+        );
+
+        if targets.is_empty() {
+            let no_responders: bool = clients
+                .get(&sender)
+                .is_some_and(|client| client.headers && client.no_responders);
+
+            if let (Some(reply), true) = (message.reply, no_responders) {
+                let status: Publish = Publish {
+                    subject: reply,
+                    reply: None,
+                    headers: Some(NO_RESPONDERS),
+                    payload: &[],
+                };
+                for client in clients.values_mut() {
+                    let sids: Vec<String> = client.matching(reply);
+                    for sid in sids {
+                        client.deliver(&sid, &status);
+                    }
+                }
+            }
+            return;
+        }
+
+        for (id, sid) in targets {
+            if let Some(client) = clients.get_mut(&id) {
+                client.deliver(&sid, &message);
+            }
+        }
+    }
+}
+
+impl Client {
+    fn send(&self, bytes: Vec<u8>) {
+        self.out.send(bytes).unwrap_or_default();
+    }
+
+    fn matching(&self, subject: &str) -> Vec<String> {
+        self.subscriptions
+            .iter()
+            .filter(|(_, subscription)| matches(&subscription.subject, subject))
+            .map(|(sid, _)| sid.clone())
+            .collect()
+    }
+
+    fn deliver(&mut self, sid: &str, message: &Publish) {
+        let reply: String = message
+            .reply
+            .map(|reply| format!("{} ", reply))
+            .unwrap_or_default();
+
+        let mut bytes: Vec<u8> = match message.headers {
+            Some(_) if !self.headers => return,
+            Some(headers) => {
+                let total: usize = headers.len() + message.payload.len();
+                let mut bytes: Vec<u8> = format!(
+                    "HMSG {} {} {}{} {}\r\n",
+                    message.subject,
+                    sid,
+                    reply,
+                    headers.len(),
+                    total
+                )
+                .into_bytes();
+                bytes.extend_from_slice(headers);
+                bytes
+            }
+            None => format!(
+                "MSG {} {} {}{}\r\n",
+                message.subject,
+                sid,
+                reply,
+                message.payload.len()
+            )
+            .into_bytes(),
+        };
+        bytes.extend_from_slice(message.payload);
+        bytes.extend_from_slice(b"\r\n");
+        self.send(bytes);
+
+        if let Some(subscription) = self.subscriptions.get_mut(sid) {
+            match subscription.remaining.as_mut() {
+                Some(remaining) if *remaining <= 1 => {
+                    self.subscriptions.remove(sid);
+                }
+                Some(remaining) => *remaining -= 1,
+                None => {}
+            }
+        }
+    }
+}
+
+/// Subject matching with `*` for a single token and `>` for one or more trailing tokens
+fn matches(pattern: &str, subject: &str) -> bool {
+    let mut subject_tokens = subject.split('.');
+
+    for token in pattern.split('.') {
+        match (token, subject_tokens.next()) {
+            (">", Some(_)) => return true,
+            ("*", Some(_)) => {}
+            (token, Some(subject_token)) if token == subject_token => {}
+            _ => return false,
+        }
+    }
+
+    subject_tokens.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nats::{matches, EmbeddedNats};
+    use async_nats::{Client, Message, Subscriber};
+    use futures::StreamExt;
+
+    #[test]
+    fn matches_should_support_wildcards() {
+        assert!(matches(
+            "cryptocom.ticker.btc_usd",
+            "cryptocom.ticker.btc_usd"
+        ));
+        assert!(matches("cryptocom.*.btc_usd", "cryptocom.ticker.btc_usd"));
+        assert!(matches("cryptocom.>", "cryptocom.ticker.btc_usd"));
+        assert!(!matches("cryptocom.>", "cryptocom"));
+        assert!(!matches("cryptocom.*", "cryptocom.ticker.btc_usd"));
+        assert!(!matches("cryptocom.ticker", "cryptocom.ticker.btc_usd"));
+    }
+
+    #[tokio::test]
+    async fn embedded_nats_should_route_messages_and_requests() {
+        let nats: EmbeddedNats = EmbeddedNats::start().await.unwrap();
+        let client: Client = async_nats::connect(nats.config().address()).await.unwrap();
+
+        let mut subscriber: Subscriber = client.subscribe("cryptocom.>").await.unwrap();
+        client
+            .publish("cryptocom.ticker.btc_usd", "tick".into())
+            .await
+            .unwrap();
+        let message: Message = subscriber.next().await.unwrap();
+
+        assert_eq!(message.subject.as_str(), "cryptocom.ticker.btc_usd");
+        assert_eq!(message.payload, "tick");
+        assert!(client.request("kraken.markets", "".into()).await.is_err());
+    }
+}
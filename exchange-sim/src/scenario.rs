@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// Text frame sent to the client
+    Send(String),
+    /// Waits for a client frame containing the text
+    Expect(String),
+    Wait(Duration),
+    /// Close frame, the client is expected to reconnect
+    Close,
+    /// Drops the socket without close frame
+    Disconnect,
+}
+
+/// Script of websocket steps per accepted connection, in order of connections.
+/// Connections without script stay open and only record client frames.
+#[derive(Debug, Clone, Default)]
+pub struct Scenario {
+    connections: Vec<Vec<Step>>,
+}
+
+impl Scenario {
+    pub fn step(mut self, step: Step) -> Self {
+        match self.connections.last_mut() {
+            Some(steps) => steps.push(step),
+            None => self.connections.push(vec![step]),
+        }
+        self
+    }
+
+    pub fn send<S: Into<String>>(self, frame: S) -> Self {
+        self.step(Step::Send(frame.into()))
+    }
+
+    pub fn expect<S: Into<String>>(self, text: S) -> Self {
+        self.step(Step::Expect(text.into()))
+    }
+
+    pub fn wait(self, duration: Duration) -> Self {
+        self.step(Step::Wait(duration))
+    }
+
+    pub fn close(self) -> Self {
+        self.step(Step::Close)
+    }
+
+    pub fn disconnect(self) -> Self {
+        self.step(Step::Disconnect)
+    }
+
+    /// Following steps are played on the next connection
+    pub fn reconnect(mut self) -> Self {
+        if self.connections.is_empty() {
+            self.connections.push(vec![]);
+        }
+        self.connections.push(vec![]);
+        self
+    }
+
+    pub fn connection(&self, index: usize) -> &[Step] {
+        self.connections
+            .get(index)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scenario::{Scenario, Step};
+
+    #[test]
+    fn reconnect_should_split_steps_per_connection() {
+        let scenario: Scenario = Scenario::default()
+            .expect("subscribe")
+            .disconnect()
+            .reconnect()
+            .send("frame");
+
+        assert_eq!(
+            scenario.connection(0),
+            &[Step::Expect("subscribe".to_string()), Step::Disconnect]
+        );
+        assert_eq!(scenario.connection(1), &[Step::Send("frame".to_string())]);
+        assert!(scenario.connection(2).is_empty());
+    }
+}
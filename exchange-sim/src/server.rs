@@ -0,0 +1,296 @@
+use crate::scenario::{Scenario, Step};
+use anyhow::{anyhow, Result};
+use axum::extract::State;
+use axum::http::{header, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use futures::stream::{SplitSink, SplitStream};
+use futures::{SinkExt, StreamExt};
+use log::{debug, warn};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{accept_async, WebSocketStream};
+
+type WsSink = SplitSink<WebSocketStream<TcpStream>, Message>;
+type WsStream = SplitStream<WebSocketStream<TcpStream>>;
+
+/// Canned REST response
+#[derive(Debug, Clone)]
+pub struct Reply {
+    status: u16,
+    body: String,
+}
+
+impl Reply {
+    pub fn ok<S: Into<String>>(body: S) -> Self {
+        Reply::status(200, body)
+    }
+
+    pub fn status<S: Into<String>>(status: u16, body: S) -> Self {
+        Reply {
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// Local exchange with scripted websocket and canned REST endpoints
+#[derive(Default)]
+pub struct ExchangeSim {
+    scenario: Scenario,
+    routes: HashMap<String, Reply>,
+}
+
+impl ExchangeSim {
+    pub fn ws(mut self, scenario: Scenario) -> Self {
+        self.scenario = scenario;
+        self
+    }
+
+    pub fn rest(mut self, path: &str, reply: Reply) -> Self {
+        self.routes.insert(path.to_string(), reply);
+        self
+    }
+
+    pub async fn start(self) -> Result<SimServer> {
+        let ws_listener: TcpListener = TcpListener::bind("127.0.0.1:0").await?;
+        let rest_listener: TcpListener = TcpListener::bind("127.0.0.1:0").await?;
+        let ws_address: SocketAddr = ws_listener.local_addr()?;
+        let rest_address: SocketAddr = rest_listener.local_addr()?;
+
+        let (frames, _): (watch::Sender<Vec<String>>, _) = watch::channel(vec![]);
+        let (connections, _): (watch::Sender<usize>, _) = watch::channel(0);
+
+        let ws_task: JoinHandle<()> = tokio::spawn(accept(
+            ws_listener,
+            self.scenario,
+            frames.clone(),
+            connections.clone(),
+        ));
+
+        let router: Router = Router::new()
+            .fallback(rest)
+            .with_state(Arc::new(self.routes));
+        let rest_task: JoinHandle<()> = tokio::spawn(async move {
+            if let Err(error) = axum::serve(rest_listener, router).await {
+                warn!("Simulated rest server stopped: {}", error);
+            }
+        });
+
+        Ok(SimServer {
+            ws_address,
+            rest_address,
+            frames,
+            connections,
+            tasks: vec![ws_task, rest_task],
+        })
+    }
+}
+
+/// Running simulator, stopped on drop
+pub struct SimServer {
+    ws_address: SocketAddr,
+    rest_address: SocketAddr,
+    frames: watch::Sender<Vec<String>>,
+    connections: watch::Sender<usize>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl SimServer {
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}/ws", self.ws_address)
+    }
+
+    pub fn rest_url(&self, path: &str) -> String {
+        format!("http://{}{}", self.rest_address, path)
+    }
+
+    /// Text frames sent by clients over all connections
+    pub fn received(&self) -> Vec<String> {
+        self.frames.borrow().clone()
+    }
+
+    pub fn count(&self, text: &str) -> usize {
+        self.frames
+            .borrow()
+            .iter()
+            .filter(|frame| frame.contains(text))
+            .count()
+    }
+
+    /// First client frame containing the text
+    pub async fn wait_for(&self, text: &str, duration: Duration) -> Result<String> {
+        let mut frames: watch::Receiver<Vec<String>> = self.frames.subscribe();
+        let frames = timeout(
+            duration,
+            frames.wait_for(|frames| frames.iter().any(|frame| frame.contains(text))),
+        )
+        .await
+        .map_err(|_| anyhow!("No frame containing {} within {:?}", text, duration))??;
+
+        frames
+            .iter()
+            .find(|frame| frame.contains(text))
+            .cloned()
+            .ok_or_else(|| anyhow!("No frame containing {}", text))
+    }
+
+    pub fn connections(&self) -> usize {
+        *self.connections.borrow()
+    }
+
+    pub async fn wait_for_connections(&self, count: usize, duration: Duration) -> Result<()> {
+        let mut connections: watch::Receiver<usize> = self.connections.subscribe();
+        timeout(
+            duration,
+            connections.wait_for(|connections| *connections >= count),
+        )
+        .await
+        .map_err(|_| anyhow!("Less than {} connections within {:?}", count, duration))??;
+
+        Ok(())
+    }
+}
+
+impl Drop for SimServer {
+    fn drop(&mut self) {
+        self.tasks.iter().for_each(JoinHandle::abort);
+    }
+}
+
+async fn accept(
+    listener: TcpListener,
+    scenario: Scenario,
+    frames: watch::Sender<Vec<String>>,
+    connections: watch::Sender<usize>,
+) {
+    while let Ok((stream, _)) = listener.accept().await {
+        let index: usize = *connections.borrow();
+        connections.send_modify(|connections| *connections += 1);
+
+        let steps: Vec<Step> = scenario.connection(index).to_vec();
+        let frames: watch::Sender<Vec<String>> = frames.clone();
+        tokio::spawn(async move {
+            if let Err(error) = play(stream, steps, frames).await {
+                debug!("Simulated connection {} finished: {}", index, error);
+            }
+        });
+    }
+}
+
+async fn play(
+    stream: TcpStream,
+    steps: Vec<Step>,
+    frames: watch::Sender<Vec<String>>,
+) -> Result<()> {
+    let (mut sink, stream): (WsSink, WsStream) = accept_async(stream).await?.split();
+    let (frames_in, mut frames_out): (UnboundedSender<String>, UnboundedReceiver<String>) =
+        unbounded_channel();
+    let reader: JoinHandle<()> = tokio::spawn(read(stream, frames, frames_in));
+
+    for step in steps {
+        match step {
+            Step::Send(frame) => sink.send(Message::Text(frame)).await?,
+            Step::Expect(text) => loop {
+                match frames_out.recv().await {
+                    Some(frame) if frame.contains(&text) => break,
+                    Some(_) => {}
+                    None => return Err(anyhow!("Client left before {}", text)),
+                }
+            },
+            Step::Wait(duration) => sleep(duration).await,
+            Step::Close => {
+                sink.send(Message::Close(None)).await?;
+                break;
+            }
+            Step::Disconnect => {
+                reader.abort();
+                return Ok(());
+            }
+        }
+    }
+
+    reader.await?;
+    Ok(())
+}
+
+async fn read(
+    mut stream: WsStream,
+    frames: watch::Sender<Vec<String>>,
+    frames_in: UnboundedSender<String>,
+) {
+    while let Some(Ok(message)) = stream.next().await {
+        match message {
+            Message::Text(frame) => {
+                frames.send_modify(|frames| frames.push(frame.clone()));
+                frames_in.send(frame).unwrap_or_default();
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+}
+
+async fn rest(State(routes): State<Arc<HashMap<String, Reply>>>, uri: Uri) -> Response {
+    match routes.get(uri.path()) {
+        Some(reply) => (
+            StatusCode::from_u16(reply.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            [(header::CONTENT_TYPE, "application/json")],
+            reply.body.clone(),
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scenario::Scenario;
+    use crate::server::{ExchangeSim, Reply, SimServer};
+    use futures::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio_tungstenite::connect_async;
+    use tokio_tungstenite::tungstenite::Message;
+
+    #[tokio::test]
+    async fn sim_should_play_scenario_per_connection() {
+        let scenario: Scenario = Scenario::default()
+            .expect("subscribe")
+            .send("first")
+            .disconnect()
+            .reconnect()
+            .send("second");
+        let sim: SimServer = ExchangeSim::default()
+            .ws(scenario)
+            .rest("/markets", Reply::status(500, "{}"))
+            .start()
+            .await
+            .unwrap();
+
+        let (mut first, _) = connect_async(sim.ws_url()).await.unwrap();
+        first.send(Message::text("subscribe")).await.unwrap();
+
+        assert_eq!(first.next().await.unwrap().unwrap(), Message::text("first"));
+        assert!(!matches!(first.next().await, Some(Ok(Message::Text(_)))));
+
+        let (mut second, _) = connect_async(sim.ws_url()).await.unwrap();
+
+        assert_eq!(
+            second.next().await.unwrap().unwrap(),
+            Message::text("second")
+        );
+        assert_eq!(sim.connections(), 2);
+        assert!(sim
+            .wait_for("subscribe", Duration::from_secs(1))
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,180 @@
+use protocol::public::book::{Book, Offer, OrderBookMessage};
+use protocol::public::types::MessageType;
+use rust_decimal::Decimal;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Order book rebuilt from the snapshot and following updates, offers with zero size are removed
+#[derive(Default)]
+pub struct LocalBook {
+    asks: BTreeMap<Decimal, Offer>,
+    bids: BTreeMap<Reverse<Decimal>, Offer>,
+    timestamp: i64,
+    sequence: i64,
+    epoch: u64,
+    synced: bool,
+}
+
+/// Result of applying a stream message to the local book
+#[derive(Debug, PartialEq)]
+pub enum Applied {
+    Synced,
+    /// Update received before a snapshot, dropped
+    NotSynced,
+    /// Update missed, book is dropped until the next snapshot
+    Gap,
+}
+
+impl LocalBook {
+    pub fn apply(&mut self, message: &OrderBookMessage) -> Applied {
+        let snapshot: bool = message.r#type == MessageType::Snapshot as i32;
+
+        if !snapshot && !self.synced {
+            return Applied::NotSynced;
+        }
+
+        if !snapshot && (message.epoch != self.epoch || message.sequence != self.sequence + 1) {
+            self.synced = false;
+            return Applied::Gap;
+        }
+
+        if snapshot {
+            self.asks.clear();
+            self.bids.clear();
+            self.synced = true;
+        }
+
+        self.sequence = message.sequence;
+        self.epoch = message.epoch;
+
+        if let Some(book) = &message.book {
+            self.timestamp = book.timestamp;
+
+            for offer in &book.asks {
+                update(&mut self.asks, offer, |rate| rate);
+            }
+
+            for offer in &book.bids {
+                update(&mut self.bids, offer, Reverse);
+            }
+        }
+
+        Applied::Synced
+    }
+
+    /// Best levels of both sides, asks ascending and bids descending
+    pub fn top(&self, depth: usize) -> Book {
+        Book {
+            asks: self.asks.values().take(depth).cloned().collect(),
+            bids: self.bids.values().take(depth).cloned().collect(),
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+fn update<K: Ord>(side: &mut BTreeMap<K, Offer>, offer: &Offer, key: fn(Decimal) -> K) {
+    let (Ok(rate), Ok(size)) = (
+        Decimal::from_str(&offer.rate),
+        Decimal::from_str(&offer.size),
+    ) else {
+        return;
+    };
+
+    if size > Decimal::ZERO {
+        side.insert(key(rate), offer.clone());
+    } else {
+        side.remove(&key(rate));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::book::{Applied, LocalBook};
+    use protocol::public::book::{Book, Offer, OrderBookMessage};
+    use protocol::public::types::MessageType;
+
+    fn offer(rate: &str, size: &str) -> Offer {
+        Offer {
+            rate: rate.to_string(),
+            size: size.to_string(),
+        }
+    }
+
+    fn message(
+        r#type: MessageType,
+        sequence: i64,
+        asks: Vec<Offer>,
+        bids: Vec<Offer>,
+    ) -> OrderBookMessage {
+        OrderBookMessage {
+            r#type: r#type as i32,
+            sequence,
+            book: Some(Book {
+                asks,
+                bids,
+                timestamp: 1736286461888,
+            }),
+            ..OrderBookMessage::default()
+        }
+    }
+
+    #[test]
+    fn apply_should_merge_updates_into_snapshot() {
+        let mut book: LocalBook = LocalBook::default();
+
+        assert_eq!(
+            book.apply(&message(MessageType::Update, 1, vec![], vec![])),
+            Applied::NotSynced
+        );
+
+        book.apply(&message(
+            MessageType::Snapshot,
+            0,
+            vec![offer("101", "1"), offer("102", "2")],
+            vec![offer("99", "1"), offer("98", "3")],
+        ));
+        book.apply(&message(
+            MessageType::Update,
+            1,
+            vec![offer("101", "0")],
+            vec![offer("100", "0.5")],
+        ));
+
+        let top: Book = book.top(1);
+
+        assert_eq!(top.asks, vec![offer("102", "2")]);
+        assert_eq!(top.bids, vec![offer("100", "0.5")]);
+    }
+
+    #[test]
+    fn apply_should_drop_book_after_skipped_sequence_until_snapshot() {
+        let mut book: LocalBook = LocalBook::default();
+
+        book.apply(&message(
+            MessageType::Snapshot,
+            0,
+            vec![offer("101", "1")],
+            vec![offer("99", "1")],
+        ));
+
+        let gap: Applied = book.apply(&message(
+            MessageType::Update,
+            2,
+            vec![offer("101", "0")],
+            vec![],
+        ));
+        let dropped: Applied = book.apply(&message(MessageType::Update, 3, vec![], vec![]));
+        let resynced: Applied = book.apply(&message(
+            MessageType::Snapshot,
+            3,
+            vec![offer("102", "1")],
+            vec![offer("99", "1")],
+        ));
+
+        assert_eq!(gap, Applied::Gap);
+        assert_eq!(dropped, Applied::NotSynced);
+        assert_eq!(resynced, Applied::Synced);
+        assert_eq!(book.top(1).asks, vec![offer("102", "1")]);
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use connector::config::load_file;
+use connector::whitelist::list;
+use log::info;
+use protocol::client::NatsConfig;
+use serde::Deserialize;
+use std::env;
+
+const ENV_PATH: &str = "CONFIGURATION_PATH";
+const DEFAULT_PATH: &str = "exporter/resources";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExporterConfig {
+    /// Exchange name e.g. cryptocom, kraken
+    pub exchange: String,
+    /// Markets in nats format e.g. btc_usd
+    #[serde(deserialize_with = "list")]
+    pub markets: Vec<String>,
+    /// Exported streams: ticker, trades, book
+    #[serde(deserialize_with = "list")]
+    pub channels: Vec<String>,
+    pub directory: String,
+    /// Writes csv file next to every parquet file
+    #[serde(default)]
+    pub csv: bool,
+    /// Number of price levels per side exported from the order book
+    pub book_depth: usize,
+    /// Rows buffered before writing a file
+    pub max_rows: usize,
+    /// Max seconds between writes of buffered rows
+    pub flush_interval: u64,
+}
+
+pub fn load_config() -> Result<(NatsConfig, ExporterConfig)> {
+    let path: String = env::var(ENV_PATH).unwrap_or(DEFAULT_PATH.to_string());
+
+    let nats: NatsConfig = load_file(&path, "nats")?;
+    let exporter: ExporterConfig = load_file(&path, "exporter")?;
+
+    info!("Exporter config loaded successfully!");
+
+    Ok((nats, exporter))
+}
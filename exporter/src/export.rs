@@ -0,0 +1,94 @@
+use crate::book::{Applied, LocalBook};
+use crate::rows::{published, BookRow, Row, Source, TickerRow, TradeRow};
+use crate::sink::Sink;
+use anyhow::Result;
+use connectors_sdk::subscription::NatsStream;
+use futures::StreamExt;
+use log::{info, warn};
+use prost::Message;
+use protocol::public::book::OrderBookMessage;
+use protocol::public::ticker::TickerMessage;
+use protocol::public::trade::TradesMessage;
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::watch;
+use tokio::time::{interval, Interval};
+
+/// Writes rows of the stream until it ends or shutdown is requested, buffered rows are
+/// flushed every interval and on exit
+pub async fn export<M, R, F>(
+    mut stream: NatsStream<M>,
+    mut sink: Sink<R>,
+    flush_interval: Duration,
+    mut shutdown: watch::Receiver<bool>,
+    mut rows: F,
+) -> Result<()>
+where
+    M: Message + Default + 'static,
+    R: Row,
+    F: FnMut(M) -> Vec<R>,
+{
+    let mut flush: Interval = interval(flush_interval);
+
+    loop {
+        select! {
+            message = stream.next() => match message {
+                Some(message) => {
+                    for row in rows(message) {
+                        sink.push(row)?;
+                    }
+                }
+                None => {
+                    warn!("{} stream finished", R::CHANNEL);
+                    break;
+                }
+            },
+            _ = flush.tick() => {
+                sink.flush()?;
+            }
+            _ = shutdown.changed() => break,
+        }
+    }
+
+    sink.flush()?;
+    info!("{} export stopped", R::CHANNEL);
+    Ok(())
+}
+
+pub fn ticker_rows(source: Source) -> impl FnMut(TickerMessage) -> Vec<TickerRow> {
+    move |message| TickerRow::from_message(&source, &message)
+}
+
+pub fn trade_rows(source: Source) -> impl FnMut(TradesMessage) -> Vec<TradeRow> {
+    move |message| TradeRow::from_message(&source, &message)
+}
+
+/// Top of the local book after every message, nothing before the first snapshot.
+/// On a sequence gap rows are dropped and a fresh snapshot is requested through resync.
+pub fn book_rows(
+    source: Source,
+    depth: usize,
+    resync: UnboundedSender<()>,
+) -> impl FnMut(OrderBookMessage) -> Vec<BookRow> {
+    let mut book: LocalBook = LocalBook::default();
+
+    move |message| {
+        match book.apply(&message) {
+            Applied::Synced => {}
+            Applied::NotSynced => return vec![],
+            Applied::Gap => {
+                warn!("{} book sequence gap, requesting snapshot", source.market);
+                resync.send(()).unwrap_or_default();
+                return vec![];
+            }
+        }
+
+        BookRow::from_book(
+            &source,
+            message.sequence,
+            published(&message),
+            &book.top(depth),
+        )
+    }
+}
@@ -0,0 +1,6 @@
+pub mod book;
+pub mod config;
+pub mod export;
+pub mod model;
+pub mod rows;
+pub mod sink;
@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Result};
+use connector::shutdown;
+use connector::utils::tracing;
+use connectors_sdk::connector::PublicConnector;
+use connectors_sdk::subscription::NatsStream;
+use exporter::config::{load_config, ExporterConfig};
+use exporter::export::{book_rows, export, ticker_rows, trade_rows};
+use exporter::model::Market;
+use exporter::rows::{BookRow, Source, TickerRow, TradeRow};
+use exporter::sink::Sink;
+use futures::future::join_all;
+use log::{info, warn};
+use protocol::client::{NatsClient, NatsConfig};
+use protocol::model::Symbol;
+use protocol::public::book::OrderBookMessage;
+use protocol::public::error::ErrorMessage;
+use protocol::public::ticker::TickerMessage;
+use protocol::public::trade::TradesMessage;
+use protocol::public::types::Exchange;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing::init()?;
+
+    let (nats_config, config): (NatsConfig, ExporterConfig) = load_config()?;
+    let exchange: Exchange = Exchange::from_str_name(&config.exchange.to_uppercase())
+        .ok_or_else(|| anyhow!("Unknown exchange {}", config.exchange))?;
+    let connector: Arc<PublicConnector> =
+        Arc::new(PublicConnector::new(NatsClient::new(&nats_config).await?));
+    let (shutdown, shutdown_out): (watch::Sender<bool>, watch::Receiver<bool>) =
+        watch::channel(false);
+
+    let mut tasks: Vec<JoinHandle<Result<()>>> = Vec::new();
+
+    for symbol in &config.markets {
+        let market: Market = Market::from_nats_format(symbol)?;
+
+        for channel in &config.channels {
+            let task: JoinHandle<Result<()>> = start(
+                &connector,
+                &config,
+                exchange,
+                &market,
+                channel,
+                shutdown_out.clone(),
+            )
+            .await?;
+            tasks.push(task);
+        }
+    }
+
+    shutdown::signal().await?;
+    info!("Stopping exporter, flushing buffered rows");
+    shutdown.send_replace(true);
+
+    for result in join_all(tasks).await {
+        if let Err(error) = result
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result)
+        {
+            warn!("Export failed: {}", error);
+        }
+    }
+
+    tracing::shutdown();
+    Ok(())
+}
+
+async fn start(
+    connector: &Arc<PublicConnector>,
+    config: &ExporterConfig,
+    exchange: Exchange,
+    market: &Market,
+    channel: &str,
+    shutdown: watch::Receiver<bool>,
+) -> Result<JoinHandle<Result<()>>> {
+    let source: Source = Source {
+        exchange: config.exchange.to_lowercase(),
+        market: market.nats_format(),
+    };
+    let directory: PathBuf = PathBuf::from(&config.directory);
+    let interval: Duration = Duration::from_secs(config.flush_interval);
+    let (csv, max_rows): (bool, usize) = (config.csv, config.max_rows);
+
+    info!("Exporting {} of {}", channel, source.market);
+
+    let task: JoinHandle<Result<()>> = match channel {
+        "ticker" => {
+            let stream: NatsStream<TickerMessage> = connector
+                .ticker(exchange, market.clone())
+                .await
+                .map_err(error)?;
+            let sink: Sink<TickerRow> = Sink::new(&directory, &source, csv, max_rows);
+            tokio::spawn(export(
+                stream,
+                sink,
+                interval,
+                shutdown,
+                ticker_rows(source),
+            ))
+        }
+        "trades" => {
+            let stream: NatsStream<TradesMessage> = connector
+                .trades(exchange, market.clone())
+                .await
+                .map_err(error)?;
+            let sink: Sink<TradeRow> = Sink::new(&directory, &source, csv, max_rows);
+            tokio::spawn(export(stream, sink, interval, shutdown, trade_rows(source)))
+        }
+        "book" => {
+            let stream: NatsStream<OrderBookMessage> = connector
+                .order_book(exchange, market.clone())
+                .await
+                .map_err(error)?;
+            let sink: Sink<BookRow> = Sink::new(&directory, &source, csv, max_rows);
+            let (resync, resync_out): (UnboundedSender<()>, UnboundedReceiver<()>) =
+                unbounded_channel();
+            tokio::spawn(resync_book(
+                connector.clone(),
+                exchange,
+                market.clone(),
+                resync_out,
+            ));
+            let rows = book_rows(source, config.book_depth, resync);
+            tokio::spawn(export(stream, sink, interval, shutdown, rows))
+        }
+        _ => return Err(anyhow!("Unknown channel {}", channel)),
+    };
+
+    Ok(task)
+}
+
+/// Requests book snapshots after sequence gaps until the export of the market stops
+async fn resync_book(
+    connector: Arc<PublicConnector>,
+    exchange: Exchange,
+    market: Market,
+    mut resync: UnboundedReceiver<()>,
+) {
+    while resync.recv().await.is_some() {
+        if let Err(error) = connector
+            .order_book_snapshot(exchange, market.clone())
+            .await
+        {
+            warn!("Cannot request book snapshot: {}", error.message);
+        }
+    }
+}
+
+fn error(error: ErrorMessage) -> anyhow::Error {
+    anyhow!("Cannot subscribe stream: {}", error.message)
+}
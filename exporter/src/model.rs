@@ -0,0 +1,51 @@
+use anyhow::{anyhow, Result};
+use protocol::model::{Currency, Symbol};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Market {
+    from: String,
+    to: String,
+}
+
+impl Market {
+    pub fn from_nats_format(symbol: &str) -> Result<Self> {
+        match symbol.split_once('_') {
+            Some((from, to)) if !from.is_empty() && !to.is_empty() => Ok(Market {
+                from: from.to_lowercase(),
+                to: to.to_lowercase(),
+            }),
+            _ => Err(anyhow!(
+                "Invalid market {}, expected format btc_usd",
+                symbol
+            )),
+        }
+    }
+}
+
+impl Symbol for Market {
+    fn from(&self) -> Currency {
+        Currency::new(self.from.clone())
+    }
+
+    fn to(&self) -> Currency {
+        Currency::new(self.to.clone())
+    }
+
+    fn exchange_format(&self) -> String {
+        self.nats_format()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::Market;
+    use protocol::model::Symbol;
+
+    #[test]
+    fn from_nats_format_should_parse_market() {
+        let market: Market = Market::from_nats_format("BTC_usd").unwrap();
+
+        assert_eq!(market.nats_format(), "btc_usd");
+        assert!(Market::from_nats_format("btcusd").is_err());
+    }
+}
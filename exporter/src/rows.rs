@@ -0,0 +1,361 @@
+use arrow_array::{ArrayRef, Float64Array, Int64Array, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use protocol::latency::Stamped;
+use protocol::public::book::{Book, Offer};
+use protocol::public::ticker::TickerMessage;
+use protocol::public::trade::TradesMessage;
+use protocol::public::types::Side;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Flat record of a stream message with arrow schema mirroring the proto fields.
+/// Rates and sizes published as strings are exported as floats, null when not a number.
+pub trait Row: Serialize + Send + Sized + 'static {
+    const CHANNEL: &'static str;
+
+    fn schema() -> Schema;
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef>;
+
+    /// Exchange time in millis, used for the date partition
+    fn timestamp(&self) -> i64;
+}
+
+/// Origin of the exported message
+#[derive(Clone)]
+pub struct Source {
+    pub exchange: String,
+    pub market: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TickerRow {
+    pub exchange: String,
+    pub market: String,
+    pub sequence: i64,
+    pub timestamp: i64,
+    pub ask_price: Option<f64>,
+    pub ask_size: Option<f64>,
+    pub bid_price: Option<f64>,
+    pub bid_size: Option<f64>,
+    /// Connector publish time in micros, 0 without latency stamps
+    pub published: i64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TradeRow {
+    pub exchange: String,
+    pub market: String,
+    pub sequence: i64,
+    pub timestamp: i64,
+    pub id: String,
+    pub rate: Option<f64>,
+    pub size: Option<f64>,
+    pub side: String,
+    pub published: i64,
+}
+
+/// Single price level of the top-N book, one row per level and side
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BookRow {
+    pub exchange: String,
+    pub market: String,
+    pub sequence: i64,
+    pub timestamp: i64,
+    pub side: String,
+    /// 0 for the best price
+    pub level: i64,
+    pub rate: Option<f64>,
+    pub size: Option<f64>,
+    pub published: i64,
+}
+
+impl TickerRow {
+    pub fn from_message(source: &Source, message: &TickerMessage) -> Vec<TickerRow> {
+        let published: i64 = published(message);
+
+        message
+            .tick
+            .iter()
+            .map(|tick| TickerRow {
+                exchange: source.exchange.clone(),
+                market: source.market.clone(),
+                sequence: message.sequence,
+                timestamp: tick.timestamp,
+                ask_price: number(&tick.ask_price),
+                ask_size: number(&tick.ask_size),
+                bid_price: number(&tick.bid_price),
+                bid_size: number(&tick.bid_size),
+                published,
+            })
+            .collect()
+    }
+}
+
+impl TradeRow {
+    pub fn from_message(source: &Source, message: &TradesMessage) -> Vec<TradeRow> {
+        let published: i64 = published(message);
+
+        message
+            .trades
+            .iter()
+            .map(|trade| TradeRow {
+                exchange: source.exchange.clone(),
+                market: source.market.clone(),
+                sequence: message.sequence,
+                timestamp: trade.timestamp,
+                id: trade.id.clone(),
+                rate: number(&trade.rate),
+                size: number(&trade.size),
+                side: side(trade.side),
+                published,
+            })
+            .collect()
+    }
+}
+
+impl BookRow {
+    /// Rows of the book top with levels of asks first
+    pub fn from_book(source: &Source, sequence: i64, published: i64, book: &Book) -> Vec<BookRow> {
+        let row = |side: Side, level: usize, offer: &Offer| BookRow {
+            exchange: source.exchange.clone(),
+            market: source.market.clone(),
+            sequence,
+            timestamp: book.timestamp,
+            side: side.as_str_name().to_lowercase(),
+            level: level as i64,
+            rate: number(&offer.rate),
+            size: number(&offer.size),
+            published,
+        };
+
+        let asks = book
+            .asks
+            .iter()
+            .enumerate()
+            .map(|(level, offer)| row(Side::Sell, level, offer));
+        let bids = book
+            .bids
+            .iter()
+            .enumerate()
+            .map(|(level, offer)| row(Side::Buy, level, offer));
+
+        asks.chain(bids).collect()
+    }
+}
+
+impl Row for TickerRow {
+    const CHANNEL: &'static str = "ticker";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            string("exchange"),
+            string("market"),
+            int("sequence"),
+            int("timestamp"),
+            float("ask_price"),
+            float("ask_size"),
+            float("bid_price"),
+            float("bid_size"),
+            int("published"),
+        ])
+    }
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef> {
+        vec![
+            strings(rows, |row| &row.exchange),
+            strings(rows, |row| &row.market),
+            ints(rows, |row| row.sequence),
+            ints(rows, |row| row.timestamp),
+            floats(rows, |row| row.ask_price),
+            floats(rows, |row| row.ask_size),
+            floats(rows, |row| row.bid_price),
+            floats(rows, |row| row.bid_size),
+            ints(rows, |row| row.published),
+        ]
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+impl Row for TradeRow {
+    const CHANNEL: &'static str = "trades";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            string("exchange"),
+            string("market"),
+            int("sequence"),
+            int("timestamp"),
+            string("id"),
+            float("rate"),
+            float("size"),
+            string("side"),
+            int("published"),
+        ])
+    }
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef> {
+        vec![
+            strings(rows, |row| &row.exchange),
+            strings(rows, |row| &row.market),
+            ints(rows, |row| row.sequence),
+            ints(rows, |row| row.timestamp),
+            strings(rows, |row| &row.id),
+            floats(rows, |row| row.rate),
+            floats(rows, |row| row.size),
+            strings(rows, |row| &row.side),
+            ints(rows, |row| row.published),
+        ]
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+impl Row for BookRow {
+    const CHANNEL: &'static str = "book";
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            string("exchange"),
+            string("market"),
+            int("sequence"),
+            int("timestamp"),
+            string("side"),
+            int("level"),
+            float("rate"),
+            float("size"),
+            int("published"),
+        ])
+    }
+
+    fn columns(rows: &[Self]) -> Vec<ArrayRef> {
+        vec![
+            strings(rows, |row| &row.exchange),
+            strings(rows, |row| &row.market),
+            ints(rows, |row| row.sequence),
+            ints(rows, |row| row.timestamp),
+            strings(rows, |row| &row.side),
+            ints(rows, |row| row.level),
+            floats(rows, |row| row.rate),
+            floats(rows, |row| row.size),
+            ints(rows, |row| row.published),
+        ]
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+pub fn published<M: Stamped>(message: &M) -> i64 {
+    message
+        .latency()
+        .map(|latency| latency.published_micros)
+        .unwrap_or_default()
+}
+
+fn side(side: i32) -> String {
+    Side::try_from(side)
+        .map(|side| side.as_str_name().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn string(name: &str) -> Field {
+    Field::new(name, DataType::Utf8, false)
+}
+
+fn int(name: &str) -> Field {
+    Field::new(name, DataType::Int64, false)
+}
+
+fn float(name: &str) -> Field {
+    Field::new(name, DataType::Float64, true)
+}
+
+fn strings<R>(rows: &[R], value: fn(&R) -> &String) -> ArrayRef {
+    Arc::new(StringArray::from_iter_values(rows.iter().map(value)))
+}
+
+fn ints<R>(rows: &[R], value: fn(&R) -> i64) -> ArrayRef {
+    Arc::new(Int64Array::from_iter_values(rows.iter().map(value)))
+}
+
+fn floats<R>(rows: &[R], value: fn(&R) -> Option<f64>) -> ArrayRef {
+    Arc::new(Float64Array::from_iter(rows.iter().map(value)))
+}
+
+fn number(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rows::{BookRow, Source, TradeRow};
+    use protocol::public::book::{Book, Offer};
+    use protocol::public::trade::{Trade, TradesMessage};
+    use protocol::public::types::Side;
+
+    fn source() -> Source {
+        Source {
+            exchange: "cryptocom".to_string(),
+            market: "btc_usd".to_string(),
+        }
+    }
+
+    #[test]
+    fn trade_rows_should_flatten_trades_message() {
+        let message: TradesMessage = TradesMessage {
+            sequence: 3,
+            trades: vec![Trade {
+                id: "1".to_string(),
+                rate: "96448.00".to_string(),
+                size: "0.1".to_string(),
+                side: Side::Buy as i32,
+                timestamp: 1736286461888,
+            }],
+            ..TradesMessage::default()
+        };
+
+        let rows: Vec<TradeRow> = TradeRow::from_message(&source(), &message);
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].sequence, 3);
+        assert_eq!(rows[0].side, "buy");
+        assert_eq!(rows[0].rate, Some(96448.0));
+        assert_eq!(rows[0].size, Some(0.1));
+        assert_eq!(rows[0].published, 0);
+    }
+
+    #[test]
+    fn book_rows_should_number_levels_per_side() {
+        let offer = |rate: &str| Offer {
+            rate: rate.to_string(),
+            size: "1".to_string(),
+        };
+        let book: Book = Book {
+            asks: vec![offer("101"), offer("102")],
+            bids: vec![offer("100")],
+            timestamp: 1736286461888,
+        };
+
+        let rows: Vec<BookRow> = BookRow::from_book(&source(), 1, 0, &book);
+
+        let levels: Vec<(&str, i64, Option<f64>)> = rows
+            .iter()
+            .map(|row| (row.side.as_str(), row.level, row.rate))
+            .collect();
+        assert_eq!(
+            levels,
+            vec![
+                ("sell", 0, Some(101.0)),
+                ("sell", 1, Some(102.0)),
+                ("buy", 0, Some(100.0))
+            ]
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use crate::rows::{Row, Source};
+use anyhow::{Context, Result};
+use arrow_array::RecordBatch;
+use chrono::{DateTime, Utc};
+use log::info;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::fs;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Buffers rows of a single stream and writes them into files partitioned as
+/// {channel}/exchange={exchange}/market={market}/date={yyyy-mm-dd}/{time}.parquet
+pub struct Sink<R: Row> {
+    directory: PathBuf,
+    csv: bool,
+    max_rows: usize,
+    date: String,
+    rows: Vec<R>,
+}
+
+impl<R: Row> Sink<R> {
+    pub fn new(directory: &Path, source: &Source, csv: bool, max_rows: usize) -> Self {
+        let directory: PathBuf = directory
+            .join(R::CHANNEL)
+            .join(format!("exchange={}", source.exchange))
+            .join(format!("market={}", source.market));
+
+        Sink {
+            directory,
+            csv,
+            max_rows: max_rows.max(1),
+            date: String::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Writes buffered rows when full or when the row starts a new date partition
+    pub fn push(&mut self, row: R) -> Result<()> {
+        let date: String = date(row.timestamp());
+
+        if date != self.date {
+            self.flush()?;
+            self.date = date;
+        }
+
+        self.rows.push(row);
+
+        if self.rows.len() >= self.max_rows {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes buffered rows into a new file, returns path of the parquet file
+    pub fn flush(&mut self) -> Result<Option<PathBuf>> {
+        if self.rows.is_empty() {
+            return Ok(None);
+        }
+
+        let directory: PathBuf = self.directory.join(format!("date={}", self.date));
+        fs::create_dir_all(&directory)
+            .with_context(|| format!("Cannot create directory {}", directory.display()))?;
+
+        let name: String = Utc::now().format("%H%M%S%.6f").to_string();
+        let path: PathBuf = directory.join(format!("{}.parquet", name));
+
+        write_parquet(&path, &self.rows)?;
+
+        if self.csv {
+            write_csv(&directory.join(format!("{}.csv", name)), &self.rows)?;
+        }
+
+        info!("Exported {} rows into {}", self.rows.len(), path.display());
+        self.rows.clear();
+
+        Ok(Some(path))
+    }
+}
+
+fn write_parquet<R: Row>(path: &Path, rows: &[R]) -> Result<()> {
+    let batch: RecordBatch = RecordBatch::try_new(Arc::new(R::schema()), R::columns(rows))?;
+    let properties: WriterProperties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let file: File =
+        File::create(path).with_context(|| format!("Cannot create file {}", path.display()))?;
+
+    let mut writer: ArrowWriter<File> =
+        ArrowWriter::try_new(file, batch.schema(), Some(properties))?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+fn write_csv<R: Row>(path: &Path, rows: &[R]) -> Result<()> {
+    let mut writer: csv::Writer<File> = csv::Writer::from_path(path)?;
+
+    for row in rows {
+        writer.serialize(row)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Date of the exchange time in millis, today for missing timestamps
+fn date(timestamp: i64) -> String {
+    DateTime::from_timestamp_millis(timestamp)
+        .filter(|_| timestamp > 0)
+        .unwrap_or_else(Utc::now)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rows::{Source, TickerRow};
+    use crate::sink::Sink;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::fs;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn row(timestamp: i64) -> TickerRow {
+        TickerRow {
+            exchange: "cryptocom".to_string(),
+            market: "btc_usd".to_string(),
+            sequence: 0,
+            timestamp,
+            ask_price: Some(96448.0),
+            ask_size: Some(0.12219),
+            bid_price: Some(96447.99),
+            bid_size: Some(1.68),
+            published: 0,
+        }
+    }
+
+    #[test]
+    fn flush_should_write_rows_partitioned_by_date() {
+        let directory: PathBuf =
+            std::env::temp_dir().join(format!("export-{}", std::process::id()));
+        let source: Source = Source {
+            exchange: "cryptocom".to_string(),
+            market: "btc_usd".to_string(),
+        };
+        let mut sink: Sink<TickerRow> = Sink::new(&directory, &source, true, 100);
+
+        sink.push(row(1736286461888)).unwrap();
+        sink.push(row(1736286461999)).unwrap();
+        let path: PathBuf = sink.flush().unwrap().unwrap();
+
+        let rows: usize = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum();
+        let csv: String = fs::read_to_string(path.with_extension("csv")).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(path.starts_with(
+            directory.join("ticker/exchange=cryptocom/market=btc_usd/date=2025-01-07")
+        ));
+        assert_eq!(rows, 2);
+        assert_eq!(csv.lines().count(), 3);
+        assert!(sink.flush().unwrap().is_none());
+    }
+}
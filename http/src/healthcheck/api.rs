@@ -8,10 +8,25 @@ use axum::response::IntoResponse;
 use std::sync::Arc;
 
 pub const PATH: &str = "/admin/healthcheck";
+pub const LIVE_PATH: &str = "/admin/live";
+pub const READY_PATH: &str = "/admin/ready";
 
+/// All subsystems, kept for clients of the single healthcheck gate
 pub async fn health_check(State(service): State<Arc<HealthcheckService>>) -> impl IntoResponse {
-    let checks: Vec<HealthcheckResult> = service.health_check();
+    response(service.health_check().await)
+}
+
+/// Process is alive, failing checks should restart it
+pub async fn live(State(service): State<Arc<HealthcheckService>>) -> impl IntoResponse {
+    response(service.liveness().await)
+}
+
+/// Process can serve market data: nats, websocket, markets cache, exchange api
+pub async fn ready(State(service): State<Arc<HealthcheckService>>) -> impl IntoResponse {
+    response(service.health_check().await)
+}
 
+fn response(checks: Vec<HealthcheckResult>) -> impl IntoResponse {
     if checks.iter().any(|check| !check.enabled) {
         Err(ErrorResponse::one(
             errors::unavailable(),
@@ -24,7 +39,7 @@ pub async fn health_check(State(service): State<Arc<HealthcheckService>>) -> imp
 
 #[cfg(test)]
 mod tests {
-    use crate::healthcheck::api::{health_check, PATH};
+    use crate::healthcheck::api::{health_check, live, ready, LIVE_PATH, PATH, READY_PATH};
     use crate::healthcheck::service::HealthcheckService;
     use crate::healthcheck::tests::DbHealthCheck;
     use crate::utils::errors::not_found_handler;
@@ -80,6 +95,24 @@ mod tests {
         response.assert_json_contains(&expected)
     }
 
+    #[tokio::test]
+    async fn live_should_ignore_failing_readiness_checks() {
+        let mut service: HealthcheckService = HealthcheckService::default();
+        service.add(Box::new(DbHealthCheck {}));
+        let app: Router = Router::new()
+            .route(LIVE_PATH, get(live))
+            .route(READY_PATH, get(ready))
+            .with_state(Arc::new(service));
+
+        let server = TestServer::new(app).unwrap();
+
+        server.get("/admin/live").await.assert_status_ok();
+        server
+            .get("/admin/ready")
+            .await
+            .assert_status_service_unavailable();
+    }
+
     async fn example() {}
 
     #[tokio::test]
@@ -1,24 +1,31 @@
+use async_trait::async_trait;
 use serde::Serialize;
 
 const NAME: &str = "http";
 
+/// Single subsystem check. Checks calling external services should apply their own timeout.
+#[async_trait]
 pub trait HealthCheck: Send + Sync {
-    fn check(&self) -> HealthcheckResult;
+    async fn check(&self) -> HealthcheckResult;
 }
 
 #[derive(Serialize, PartialEq, Debug)]
 pub struct HealthcheckResult {
     pub service: String,
     pub enabled: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
 }
 
 pub struct HttpHealthCheck;
 
+#[async_trait]
 impl HealthCheck for HttpHealthCheck {
-    fn check(&self) -> HealthcheckResult {
+    async fn check(&self) -> HealthcheckResult {
         HealthcheckResult {
             service: String::from(NAME),
             enabled: true,
+            details: vec![],
         }
     }
 }
@@ -27,15 +34,16 @@ impl HealthCheck for HttpHealthCheck {
 mod tests {
     use crate::healthcheck::checks::{HealthCheck, HealthcheckResult, HttpHealthCheck, NAME};
 
-    #[test]
-    fn http_check_should_return_true() {
+    #[tokio::test]
+    async fn http_check_should_return_true() {
         let check = HttpHealthCheck;
 
         let expected = HealthcheckResult {
             service: String::from(NAME),
             enabled: true,
+            details: vec![],
         };
 
-        assert_eq!(check.check(), expected);
+        assert_eq!(check.check().await, expected);
     }
 }
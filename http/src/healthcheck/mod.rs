@@ -5,14 +5,17 @@ pub mod service;
 #[cfg(test)]
 mod tests {
     use crate::healthcheck::checks::{HealthCheck, HealthcheckResult};
+    use async_trait::async_trait;
 
     pub struct DbHealthCheck {}
 
+    #[async_trait]
     impl HealthCheck for DbHealthCheck {
-        fn check(&self) -> HealthcheckResult {
+        async fn check(&self) -> HealthcheckResult {
             HealthcheckResult {
                 service: "db".to_string(),
                 enabled: false,
+                details: vec![],
             }
         }
     }
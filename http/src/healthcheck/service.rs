@@ -1,7 +1,11 @@
 use crate::healthcheck::checks::{HealthCheck, HealthcheckResult, HttpHealthCheck};
+use futures::future::join_all;
 
+/// Liveness checks restart the process when failing,
+/// readiness checks only stop the traffic until the subsystems recover
 pub struct HealthcheckService {
-    checks: Vec<Box<dyn HealthCheck>>,
+    liveness: Vec<Box<dyn HealthCheck>>,
+    readiness: Vec<Box<dyn HealthCheck>>,
 }
 
 impl Default for HealthcheckService {
@@ -10,20 +14,32 @@ impl Default for HealthcheckService {
         let check: Box<dyn HealthCheck> = Box::new(service);
 
         HealthcheckService {
-            checks: vec![check],
+            liveness: vec![check],
+            readiness: vec![],
         }
     }
 }
 
 impl HealthcheckService {
+    /// Adds readiness check
     pub fn add(&mut self, check: Box<dyn HealthCheck>) {
-        self.checks.push(check);
+        self.readiness.push(check);
+    }
+
+    pub fn add_liveness(&mut self, check: Box<dyn HealthCheck>) {
+        self.liveness.push(check);
     }
 }
 
 impl HealthcheckService {
-    pub fn health_check(&self) -> Vec<HealthcheckResult> {
-        self.checks.iter().map(|checker| checker.check()).collect()
+    /// All checks, the process is ready only when alive
+    pub async fn health_check(&self) -> Vec<HealthcheckResult> {
+        let checks = self.liveness.iter().chain(self.readiness.iter());
+        join_all(checks.map(|checker| checker.check())).await
+    }
+
+    pub async fn liveness(&self) -> Vec<HealthcheckResult> {
+        join_all(self.liveness.iter().map(|checker| checker.check())).await
     }
 }
 
@@ -33,8 +49,8 @@ mod tests {
     use crate::healthcheck::service::HealthcheckService;
     use crate::healthcheck::tests::DbHealthCheck;
 
-    #[test]
-    fn health_check_should_return_true_for_http_and_false_for_db() {
+    #[tokio::test]
+    async fn health_check_should_return_true_for_http_and_false_for_db() {
         let mut service: HealthcheckService = HealthcheckService::default();
 
         service.add(Box::new(DbHealthCheck {}));
@@ -43,13 +59,27 @@ mod tests {
             HealthcheckResult {
                 service: String::from("http"),
                 enabled: true,
+                details: vec![],
             },
             HealthcheckResult {
                 service: String::from("db"),
                 enabled: false,
+                details: vec![],
             },
         ];
 
-        assert_eq!(service.health_check(), expected);
+        assert_eq!(service.health_check().await, expected);
+    }
+
+    #[tokio::test]
+    async fn liveness_should_skip_readiness_checks() {
+        let mut service: HealthcheckService = HealthcheckService::default();
+
+        service.add(Box::new(DbHealthCheck {}));
+
+        let results: Vec<HealthcheckResult> = service.liveness().await;
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].enabled);
     }
 }
@@ -2,4 +2,5 @@ pub mod healthcheck;
 pub mod metrics;
 pub mod models;
 pub mod server;
+pub mod subscriptions;
 mod utils;
@@ -0,0 +1,99 @@
+use axum_prometheus::metrics::{counter, gauge, histogram};
+use std::time::Duration;
+
+const RECEIVED: &str = "market_messages_received_total";
+const PUBLISHED: &str = "market_messages_published_total";
+const DECODE_ERRORS: &str = "market_decode_errors_total";
+const SEQUENCE_GAPS: &str = "market_sequence_gaps_total";
+const UPDATE_ERRORS: &str = "market_update_errors_total";
+const RECONNECTS: &str = "market_ws_reconnects_total";
+const LATENCY: &str = "market_publish_latency_seconds";
+const LAGGED: &str = "market_channel_lagged_total";
+const SUBSCRIPTIONS: &str = "market_active_subscriptions";
+
+/// Exchange update received from the websocket
+pub fn received(exchange: &str, channel: &str, market: &str) {
+    counter!(
+        RECEIVED,
+        "exchange" => exchange.to_string(),
+        "channel" => channel.to_string(),
+        "market" => market.to_string()
+    )
+    .increment(1);
+}
+
+/// Proto message published to nats stream subject
+pub fn published(exchange: &str, channel: &str, market: &str) {
+    counter!(
+        PUBLISHED,
+        "exchange" => exchange.to_string(),
+        "channel" => channel.to_string(),
+        "market" => market.to_string()
+    )
+    .increment(1);
+}
+
+/// Message not parsed, source is ws or nats
+pub fn decode_error(exchange: &str, source: &str) {
+    counter!(
+        DECODE_ERRORS,
+        "exchange" => exchange.to_string(),
+        "source" => source.to_string()
+    )
+    .increment(1);
+}
+
+/// Missed exchange update, market state is dropped
+pub fn sequence_gap(exchange: &str, channel: &str, market: &str) {
+    counter!(
+        SEQUENCE_GAPS,
+        "exchange" => exchange.to_string(),
+        "channel" => channel.to_string(),
+        "market" => market.to_string()
+    )
+    .increment(1);
+}
+
+/// Exchange update not applied to market state for other reasons than a gap
+pub fn update_error(exchange: &str, channel: &str, market: &str) {
+    counter!(
+        UPDATE_ERRORS,
+        "exchange" => exchange.to_string(),
+        "channel" => channel.to_string(),
+        "market" => market.to_string()
+    )
+    .increment(1);
+}
+
+pub fn reconnect(exchange: &str) {
+    counter!(RECONNECTS, "exchange" => exchange.to_string()).increment(1);
+}
+
+/// Time from exchange timestamp to nats publish
+pub fn latency(exchange: &str, channel: &str, latency: Duration) {
+    histogram!(
+        LATENCY,
+        "exchange" => exchange.to_string(),
+        "channel" => channel.to_string()
+    )
+    .record(latency.as_secs_f64());
+}
+
+/// Updates skipped by slow broadcast receiver
+pub fn lagged(exchange: &str, channel: &str, skipped: u64) {
+    counter!(
+        LAGGED,
+        "exchange" => exchange.to_string(),
+        "channel" => channel.to_string()
+    )
+    .increment(skipped);
+}
+
+pub fn subscriptions(exchange: &str, channel: &str, active: usize) {
+    gauge!(
+        SUBSCRIPTIONS,
+        "exchange" => exchange.to_string(),
+        "channel" => channel.to_string()
+    )
+    .set(active as f64);
+}
@@ -1,2 +1,4 @@
 pub mod api;
+pub mod market;
 pub mod service;
+pub mod task;
@@ -1,5 +1,19 @@
-use axum_prometheus::metrics_exporter_prometheus::PrometheusHandle;
-use axum_prometheus::{GenericMetricLayer, Handle, PrometheusMetricLayer};
+use axum_prometheus::metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use axum_prometheus::utils::SECONDS_DURATION_BUCKETS;
+use axum_prometheus::{
+    GenericMetricLayer, Handle, PrometheusMetricLayerBuilder, AXUM_HTTP_REQUESTS_DURATION_SECONDS,
+};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const LATENCY_SUFFIX: &str = "latency_seconds";
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Global recorder shared by http layer and market data metrics, installed once per process
+static RECORDER: OnceLock<PrometheusHandle> = OnceLock::new();
 
 pub struct MetricsService {
     handler: PrometheusHandle,
@@ -13,7 +27,9 @@ impl MetricsService {
         let (prometheus_layer, handler): (
             GenericMetricLayer<PrometheusHandle, Handle>,
             PrometheusHandle,
-        ) = PrometheusMetricLayer::pair();
+        ) = PrometheusMetricLayerBuilder::new()
+            .with_metrics_from_fn(recorder)
+            .build_pair();
 
         (prometheus_layer, Self { handler })
     }
@@ -22,3 +38,34 @@ impl MetricsService {
         self.handler.render()
     }
 }
+
+/// Installs the recorder on first use. Must be called within tokio runtime.
+pub fn recorder() -> PrometheusHandle {
+    RECORDER
+        .get_or_init(|| {
+            let handle: PrometheusHandle = PrometheusBuilder::new()
+                .set_buckets_for_metric(
+                    Matcher::Full(AXUM_HTTP_REQUESTS_DURATION_SECONDS.to_string()),
+                    SECONDS_DURATION_BUCKETS,
+                )
+                .and_then(|builder| {
+                    builder.set_buckets_for_metric(
+                        Matcher::Suffix(LATENCY_SUFFIX.to_string()),
+                        LATENCY_BUCKETS,
+                    )
+                })
+                .and_then(|builder| builder.install_recorder())
+                .expect("Failed to install metrics recorder");
+
+            let upkeep: PrometheusHandle = handle.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(UPKEEP_INTERVAL).await;
+                    upkeep.run_upkeep();
+                }
+            });
+
+            handle
+        })
+        .clone()
+}
@@ -0,0 +1,8 @@
+use axum_prometheus::metrics::counter;
+
+const RESTARTS: &str = "connector_task_restarts_total";
+
+/// Supervised task restarted after failure
+pub fn restarted(task: &str) {
+    counter!(RESTARTS, "task" => task.to_string()).increment(1);
+}
@@ -20,6 +20,8 @@ impl Error for HttpError {}
 #[serde(rename_all = "UPPERCASE")]
 pub enum ErrorCode {
     Internal,
+    #[serde(rename = "BAD_REQUEST")]
+    BadRequest,
     #[serde(rename = "NOT_FOUND")]
     NotFound,
     Unavailable,
@@ -29,6 +31,7 @@ impl Display for ErrorCode {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             ErrorCode::Internal => write!(f, "INTERNAL"),
+            ErrorCode::BadRequest => write!(f, "BAD_REQUEST"),
             ErrorCode::NotFound => write!(f, "NOT_FOUND"),
             ErrorCode::Unavailable => write!(f, "UNAVAILABLE"),
         }
@@ -73,5 +76,6 @@ mod tests {
             "UNAVAILABLE".to_string()
         );
         assert_eq!(ErrorCode::Internal.to_string(), "INTERNAL".to_string());
+        assert_eq!(ErrorCode::BadRequest.to_string(), "BAD_REQUEST".to_string());
     }
 }
@@ -1,8 +1,9 @@
 use crate::healthcheck::service::HealthcheckService;
 use crate::metrics::service::MetricsService;
+use crate::subscriptions::service::SubscriptionsService;
 use crate::utils::errors::not_found_handler;
-use crate::{healthcheck, metrics};
-use axum::routing::get;
+use crate::{healthcheck, metrics, subscriptions};
+use axum::routing::{get, post};
 use axum::Router;
 use axum_prometheus::metrics_exporter_prometheus::PrometheusHandle;
 use axum_prometheus::{GenericMetricLayer, Handle};
@@ -21,7 +22,10 @@ impl HttpConfig {
     }
 }
 
-pub fn base_router(healthcheck_service: HealthcheckService) -> Router<()> {
+pub fn base_router(
+    healthcheck_service: HealthcheckService,
+    subscriptions_service: SubscriptionsService,
+) -> Router<()> {
     let (prometheus_layer, metric_service): (
         GenericMetricLayer<PrometheusHandle, Handle>,
         MetricsService,
@@ -29,7 +33,19 @@ pub fn base_router(healthcheck_service: HealthcheckService) -> Router<()> {
 
     Router::new()
         .route(healthcheck::api::PATH, get(healthcheck::api::health_check))
+        .route(healthcheck::api::LIVE_PATH, get(healthcheck::api::live))
+        .route(healthcheck::api::READY_PATH, get(healthcheck::api::ready))
         .with_state(Arc::new(healthcheck_service))
+        .route(subscriptions::api::PATH, get(subscriptions::api::list))
+        .route(
+            subscriptions::api::ACTION_PATH,
+            post(subscriptions::api::execute),
+        )
+        .route(
+            subscriptions::api::SNAPSHOT_PATH,
+            get(subscriptions::api::snapshot),
+        )
+        .with_state(Arc::new(subscriptions_service))
         .route(metrics::api::PATH, get(metrics::api::fetch))
         .with_state(Arc::new(metric_service))
         .layer(prometheus_layer)
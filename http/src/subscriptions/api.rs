@@ -0,0 +1,153 @@
+use crate::models::errors::{ErrorCode, HttpError};
+use crate::models::response::{ErrorResponse, OkResponse};
+use crate::subscriptions::hook::{Action, SubscriptionInfo};
+use crate::subscriptions::service::SubscriptionsService;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+pub const PATH: &str = "/admin/subscriptions";
+pub const ACTION_PATH: &str = "/admin/subscriptions/{channel}/{market}/{action}";
+pub const SNAPSHOT_PATH: &str = "/admin/snapshot/{channel}/{market}";
+
+#[derive(Serialize)]
+pub struct ActionResult {
+    pub channel: String,
+    pub market: String,
+    pub action: Action,
+}
+
+pub async fn list(State(service): State<Arc<SubscriptionsService>>) -> impl IntoResponse {
+    let subscriptions: Vec<SubscriptionInfo> = service.subscriptions().await;
+    OkResponse::new(subscriptions, StatusCode::OK)
+}
+
+/// Resync, unsubscribe or subscribe single market of the channel
+pub async fn execute(
+    State(service): State<Arc<SubscriptionsService>>,
+    Path((channel, market, action)): Path<(String, String, Action)>,
+) -> impl IntoResponse {
+    match service.execute(&channel, action, &market).await {
+        Ok(()) => Ok(OkResponse::new(
+            ActionResult {
+                channel,
+                market,
+                action,
+            },
+            StatusCode::OK,
+        )),
+        Err(error) => {
+            let code: StatusCode = status(&error);
+            Err(ErrorResponse::one(error, code))
+        }
+    }
+}
+
+/// Current snapshot of the market rendered as json, for debugging only
+pub async fn snapshot(
+    State(service): State<Arc<SubscriptionsService>>,
+    Path((channel, market)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service.snapshot(&channel, &market).await {
+        Ok(snapshot) => Ok(OkResponse::<Value>::new(snapshot, StatusCode::OK)),
+        Err(error) => {
+            let code: StatusCode = status(&error);
+            Err(ErrorResponse::one(error, code))
+        }
+    }
+}
+
+fn status(error: &HttpError) -> StatusCode {
+    match error.code {
+        ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorCode::BadRequest => StatusCode::BAD_REQUEST,
+        ErrorCode::NotFound => StatusCode::NOT_FOUND,
+        ErrorCode::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::subscriptions::api::{execute, list, snapshot, ACTION_PATH, PATH, SNAPSHOT_PATH};
+    use crate::subscriptions::service::SubscriptionsService;
+    use crate::subscriptions::tests::TickerHook;
+    use axum::routing::{get, post};
+    use axum::Router;
+    use axum_test::TestServer;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn server() -> TestServer {
+        let mut service: SubscriptionsService = SubscriptionsService::default();
+        service.add(Box::new(TickerHook));
+
+        let app: Router = Router::new()
+            .route(PATH, get(list))
+            .route(ACTION_PATH, post(execute))
+            .route(SNAPSHOT_PATH, get(snapshot))
+            .with_state(Arc::new(service));
+
+        TestServer::new(app).unwrap()
+    }
+
+    #[tokio::test]
+    async fn list_should_return_subscriptions_of_all_channels() {
+        let response = server().get("/admin/subscriptions").await;
+
+        let expected = json!({
+            "data": [
+                {
+                    "channel": "ticker",
+                    "market": "btc_usd",
+                    "sequence": 5,
+                    "updated": 1700000000000i64,
+                    "messages": 6
+                }
+            ]
+        });
+
+        response.assert_status_ok();
+        response.assert_json_contains(&expected)
+    }
+
+    #[tokio::test]
+    async fn execute_should_return_not_found_for_unknown_market() {
+        let server: TestServer = server();
+
+        server
+            .post("/admin/subscriptions/ticker/btc_usd/resync")
+            .await
+            .assert_status_ok();
+        server
+            .post("/admin/subscriptions/ticker/eth_usd/unsubscribe")
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn snapshot_should_return_market_state_as_json() {
+        let server: TestServer = server();
+
+        let response = server.get("/admin/snapshot/ticker/btc_usd").await;
+
+        let expected = json!({
+            "data": {
+                "sequence": 5,
+                "tick": {
+                    "ask_price": "96448.00"
+                }
+            }
+        });
+
+        response.assert_status_ok();
+        response.assert_json_contains(&expected);
+
+        server
+            .get("/admin/snapshot/book/btc_usd")
+            .await
+            .assert_status_not_found();
+    }
+}
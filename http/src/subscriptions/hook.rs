@@ -0,0 +1,39 @@
+use crate::models::errors::HttpError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Market subscribed on the exchange websocket and published to nats
+#[derive(Serialize, PartialEq, Debug, Clone)]
+pub struct SubscriptionInfo {
+    pub channel: String,
+    pub market: String,
+    pub sequence: i64,
+    /// Last update time in millis, None before the first update
+    pub updated: Option<i64>,
+    pub messages: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// Drops market state and subscribes again to receive a fresh snapshot
+    Resync,
+    Unsubscribe,
+    /// Subscribes market without waiting for the first snapshot request
+    Subscribe,
+}
+
+/// Implemented by connectors, one hook per stream channel
+#[async_trait]
+pub trait SubscriptionsHook: Send + Sync {
+    fn channel(&self) -> &str;
+
+    async fn subscriptions(&self) -> Vec<SubscriptionInfo>;
+
+    /// Market in nats format e.g. btc_usd
+    async fn execute(&self, action: Action, market: &str) -> Result<(), HttpError>;
+
+    /// Current market state as published in the snapshot message
+    async fn snapshot(&self, market: &str) -> Result<Value, HttpError>;
+}
@@ -0,0 +1,53 @@
+pub mod api;
+pub mod hook;
+pub mod service;
+
+#[cfg(test)]
+mod tests {
+    use crate::models::errors::{ErrorCode, HttpError};
+    use crate::subscriptions::hook::{Action, SubscriptionInfo, SubscriptionsHook};
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+
+    pub struct TickerHook;
+
+    #[async_trait]
+    impl SubscriptionsHook for TickerHook {
+        fn channel(&self) -> &str {
+            "ticker"
+        }
+
+        async fn subscriptions(&self) -> Vec<SubscriptionInfo> {
+            vec![SubscriptionInfo {
+                channel: "ticker".to_string(),
+                market: "btc_usd".to_string(),
+                sequence: 5,
+                updated: Some(1700000000000),
+                messages: 6,
+            }]
+        }
+
+        async fn execute(&self, _: Action, market: &str) -> Result<(), HttpError> {
+            if market == "btc_usd" {
+                Ok(())
+            } else {
+                Err(not_subscribed(market))
+            }
+        }
+
+        async fn snapshot(&self, market: &str) -> Result<Value, HttpError> {
+            if market == "btc_usd" {
+                Ok(json!({"sequence": 5, "tick": {"ask_price": "96448.00"}}))
+            } else {
+                Err(not_subscribed(market))
+            }
+        }
+    }
+
+    fn not_subscribed(market: &str) -> HttpError {
+        HttpError {
+            message: format!("Market {} is not subscribed", market),
+            code: ErrorCode::NotFound,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::models::errors::{ErrorCode, HttpError};
+use crate::subscriptions::hook::{Action, SubscriptionInfo, SubscriptionsHook};
+use futures::future::join_all;
+use serde_json::Value;
+
+#[derive(Default)]
+pub struct SubscriptionsService {
+    hooks: Vec<Box<dyn SubscriptionsHook>>,
+}
+
+impl SubscriptionsService {
+    pub fn add(&mut self, hook: Box<dyn SubscriptionsHook>) {
+        self.hooks.push(hook);
+    }
+
+    pub async fn subscriptions(&self) -> Vec<SubscriptionInfo> {
+        join_all(self.hooks.iter().map(|hook| hook.subscriptions()))
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    pub async fn execute(
+        &self,
+        channel: &str,
+        action: Action,
+        market: &str,
+    ) -> Result<(), HttpError> {
+        self.hook(channel)?.execute(action, market).await
+    }
+
+    pub async fn snapshot(&self, channel: &str, market: &str) -> Result<Value, HttpError> {
+        self.hook(channel)?.snapshot(market).await
+    }
+
+    fn hook(&self, channel: &str) -> Result<&dyn SubscriptionsHook, HttpError> {
+        self.hooks
+            .iter()
+            .find(|hook| hook.channel() == channel)
+            .map(|hook| hook.as_ref())
+            .ok_or_else(|| HttpError {
+                message: format!("Unknown channel {}", channel),
+                code: ErrorCode::NotFound,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::errors::ErrorCode;
+    use crate::subscriptions::hook::Action;
+    use crate::subscriptions::service::SubscriptionsService;
+    use crate::subscriptions::tests::TickerHook;
+
+    #[tokio::test]
+    async fn execute_should_fail_for_unknown_channel() {
+        let mut service: SubscriptionsService = SubscriptionsService::default();
+        service.add(Box::new(TickerHook));
+
+        let result = service.execute("book", Action::Resync, "btc_usd").await;
+
+        assert!(matches!(
+            result.err().map(|error| error.code),
+            Some(ErrorCode::NotFound)
+        ));
+        assert!(service
+            .execute("ticker", Action::Resync, "btc_usd")
+            .await
+            .is_ok());
+    }
+}
@@ -1,16 +1,18 @@
 extern crate prost_build;
 
 fn main() {
-    prost_build::compile_protos(
-        &[
-            "proto/error.proto",
-            "proto/market.proto",
-            "proto/book.proto",
-            "proto/ticker.proto",
-            "proto/trade.proto",
-            "proto/types.proto",
-        ],
-        &["proto/"],
-    )
-    .expect("protoc/Cargo.toml failed to compile protos");
+    prost_build::Config::new()
+        .type_attribute(".", "#[derive(serde::Serialize)]")
+        .compile_protos(
+            &[
+                "proto/error.proto",
+                "proto/market.proto",
+                "proto/book.proto",
+                "proto/ticker.proto",
+                "proto/trade.proto",
+                "proto/types.proto",
+            ],
+            &["proto/"],
+        )
+        .expect("protoc/Cargo.toml failed to compile protos");
 }
@@ -1,9 +1,10 @@
-use async_nats::client::PublishErrorKind;
+use crate::trace;
+use async_nats::client::{FlushError, PublishErrorKind};
 use async_nats::header::IntoHeaderValue;
 use async_nats::subject::ToSubject;
 use async_nats::{
     ClientError, ConnectError, Event, HeaderMap, PublishError, Request, RequestError,
-    RequestErrorKind, SubscribeError, Subscriber,
+    RequestErrorKind, Subject, SubscribeError, Subscriber,
 };
 use async_nats::{HeaderValue, Message as NatsMessage};
 use log::{info, warn};
@@ -49,6 +50,7 @@ impl NatsConfig {
     }
 }
 
+#[derive(Clone)]
 pub struct NatsClient {
     client: async_nats::Client,
     status: Arc<RwLock<Event>>,
@@ -83,7 +85,17 @@ impl NatsClient {
         subject: S,
         message: T,
     ) -> Result<(), PublishError> {
-        self.send(subject, message, Status::Ok).await
+        self.send(subject, None, message, Status::Ok).await
+    }
+
+    /// Publishes message keeping the reply subject of the original request e.g. forwarded requests
+    pub async fn send_message_with_reply<T: Message, S: ToSubject>(
+        &self,
+        subject: S,
+        reply: Option<Subject>,
+        message: T,
+    ) -> Result<(), PublishError> {
+        self.send(subject, reply, message, Status::Ok).await
     }
 
     pub async fn send_error<T: Message, S: ToSubject>(
@@ -91,16 +103,30 @@ impl NatsClient {
         subject: S,
         message: T,
     ) -> Result<(), PublishError> {
-        self.send(subject, message, Status::Error).await
+        self.send(subject, None, message, Status::Error).await
+    }
+
+    /// Publishes already encoded payload with its original headers e.g. replayed frames
+    pub async fn publish<S: ToSubject>(
+        &self,
+        subject: S,
+        headers: HeaderMap,
+        payload: Bytes,
+    ) -> Result<(), PublishError> {
+        self.client
+            .publish_with_headers(subject, headers, payload)
+            .await
     }
 
     async fn send<T: Message, S: ToSubject>(
         &self,
         subject: S,
+        reply: Option<Subject>,
         message: T,
         status: Status,
     ) -> Result<(), PublishError> {
-        let headers: HeaderMap = status.headers();
+        let mut headers: HeaderMap = status.headers();
+        trace::inject(&mut headers);
         let mut buffer: Vec<u8> = Vec::new();
 
         let bytes = match message.encode(&mut buffer) {
@@ -111,9 +137,18 @@ impl NatsClient {
             }
         };
 
-        self.client
-            .publish_with_headers(subject, headers, bytes)
-            .await
+        match reply {
+            Some(reply) => {
+                self.client
+                    .publish_with_reply_and_headers(subject, reply, headers, bytes)
+                    .await
+            }
+            None => {
+                self.client
+                    .publish_with_headers(subject, headers, bytes)
+                    .await
+            }
+        }
     }
 
     pub async fn send_request<T: Message, S: ToSubject>(
@@ -131,11 +166,23 @@ impl NatsClient {
             }
         };
 
-        let request = Request::new().payload(bytes);
+        let mut headers: HeaderMap = HeaderMap::new();
+        trace::inject(&mut headers);
+
+        let request = Request::new().payload(bytes).headers(headers);
 
         self.client.send_request(subject, request).await
     }
 
+    /// Waits until all buffered messages are sent to the server
+    pub async fn flush(&self) -> Result<(), FlushError> {
+        self.client.flush().await
+    }
+
+    pub fn jetstream(&self) -> async_nats::jetstream::Context {
+        async_nats::jetstream::new(self.client.clone())
+    }
+
     pub fn is_healthy(&self) -> bool {
         let status_guard = self.status.read().unwrap();
         *status_guard != Event::Closed
@@ -0,0 +1,101 @@
+use crate::public::book::OrderBookMessage;
+use crate::public::ticker::TickerMessage;
+use crate::public::trade::TradesMessage;
+use crate::public::types::Latency;
+
+/// Stream message carrying latency stamps and leadership epoch of the publishing connector
+pub trait Stamped {
+    fn stamp(&mut self, latency: Latency);
+
+    fn stamp_epoch(&mut self, epoch: u64);
+
+    fn latency(&self) -> Option<&Latency>;
+
+    /// Exchange time in millis of the newest data, None when not provided by the exchange
+    fn exchange_timestamp(&self) -> Option<i64>;
+}
+
+impl Stamped for TickerMessage {
+    fn stamp(&mut self, latency: Latency) {
+        self.latency = Some(latency);
+    }
+
+    fn stamp_epoch(&mut self, epoch: u64) {
+        self.epoch = epoch;
+    }
+
+    fn latency(&self) -> Option<&Latency> {
+        self.latency.as_ref()
+    }
+
+    fn exchange_timestamp(&self) -> Option<i64> {
+        self.tick
+            .as_ref()
+            .map(|tick| tick.timestamp)
+            .filter(|timestamp| *timestamp > 0)
+    }
+}
+
+impl Stamped for TradesMessage {
+    fn stamp(&mut self, latency: Latency) {
+        self.latency = Some(latency);
+    }
+
+    fn stamp_epoch(&mut self, epoch: u64) {
+        self.epoch = epoch;
+    }
+
+    fn latency(&self) -> Option<&Latency> {
+        self.latency.as_ref()
+    }
+
+    fn exchange_timestamp(&self) -> Option<i64> {
+        self.trades
+            .iter()
+            .map(|trade| trade.timestamp)
+            .max()
+            .filter(|timestamp| *timestamp > 0)
+    }
+}
+
+impl Stamped for OrderBookMessage {
+    fn stamp(&mut self, latency: Latency) {
+        self.latency = Some(latency);
+    }
+
+    fn stamp_epoch(&mut self, epoch: u64) {
+        self.epoch = epoch;
+    }
+
+    fn latency(&self) -> Option<&Latency> {
+        self.latency.as_ref()
+    }
+
+    fn exchange_timestamp(&self) -> Option<i64> {
+        self.book
+            .as_ref()
+            .map(|book| book.timestamp)
+            .filter(|timestamp| *timestamp > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::latency::Stamped;
+    use crate::public::trade::{Trade, TradesMessage};
+
+    #[test]
+    fn exchange_timestamp_should_return_newest_trade() {
+        let trade = |timestamp: i64| Trade {
+            timestamp,
+            ..Trade::default()
+        };
+        let message: TradesMessage = TradesMessage {
+            trades: vec![trade(1700000000002), trade(1700000000001)],
+            ..TradesMessage::default()
+        };
+
+        assert_eq!(message.exchange_timestamp(), Some(1700000000002));
+        assert_eq!(TradesMessage::default().exchange_timestamp(), None);
+    }
+}
@@ -1,6 +1,8 @@
 pub mod client;
+pub mod latency;
 pub mod model;
 pub mod topics;
+pub mod trace;
 
 pub mod public {
 
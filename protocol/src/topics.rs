@@ -21,6 +21,18 @@ pub trait Topic: ToSubject {
             topic: self.to_subject(),
         }
     }
+
+    fn error(&self) -> ErrorTopic {
+        ErrorTopic {
+            topic: self.to_subject(),
+        }
+    }
+
+    fn lease(&self) -> LeaseTopic {
+        LeaseTopic {
+            topic: self.to_subject(),
+        }
+    }
 }
 
 pub struct RequestTopic {
@@ -39,12 +51,48 @@ pub struct SnapshotTopic {
     topic: Subject,
 }
 
+/// Market listing changes stream
+/// {exchange}.markets.changes
+pub struct ChangesTopic {
+    topic: Subject,
+}
+
+/// Errors for rejected snapshot requests
+/// {exchange}.{endpoint}.{from}.{to}.error
+pub struct ErrorTopic {
+    topic: Subject,
+}
+
+/// Interest renewal sent periodically by stream consumers
+/// {exchange}.{endpoint}.{from}.{to}.lease
+pub struct LeaseTopic {
+    topic: Subject,
+}
+
 impl ToSubject for SnapshotTopic {
     fn to_subject(&self) -> Subject {
         Subject::from(format!("{}.{}", self.topic, "snapshot"))
     }
 }
 
+impl ToSubject for ErrorTopic {
+    fn to_subject(&self) -> Subject {
+        Subject::from(format!("{}.{}", self.topic, "error"))
+    }
+}
+
+impl ToSubject for LeaseTopic {
+    fn to_subject(&self) -> Subject {
+        Subject::from(format!("{}.{}", self.topic, "lease"))
+    }
+}
+
+impl ToSubject for ChangesTopic {
+    fn to_subject(&self) -> Subject {
+        Subject::from(format!("{}.{}", self.topic, "changes"))
+    }
+}
+
 impl RequestTopic {
     pub fn markets(exchange: Exchange) -> RequestTopic {
         RequestTopic {
@@ -52,6 +100,12 @@ impl RequestTopic {
             endpoint: Endpoint::Markets,
         }
     }
+
+    pub fn changes(&self) -> ChangesTopic {
+        ChangesTopic {
+            topic: self.to_subject(),
+        }
+    }
 }
 
 impl ToSubject for RequestTopic {
@@ -118,6 +172,16 @@ mod tests {
 
             assert_eq!(topic.to_subject().as_str(), expected);
         }
+
+        #[test]
+        fn changes_topic_should_return_kraken_markets_changes() {
+            let exchange: Exchange = Exchange::Kraken;
+            let topic: RequestTopic = RequestTopic::markets(exchange);
+
+            let expected: &str = "kraken.markets.changes";
+
+            assert_eq!(topic.changes().to_subject().as_str(), expected);
+        }
     }
 
     mod ticker {
@@ -138,6 +202,34 @@ mod tests {
 
             assert_eq!(topic.snapshot().to_subject().as_str(), expected);
         }
+
+        #[test]
+        fn ticker_topic_should_return_cryptocom_ticker_btc_usd_error() {
+            let from: String = "BTC".to_string();
+            let to: String = "EUR".to_string();
+
+            let exchange: Exchange = Exchange::Cryptocom;
+            let symbol: TestMarket = TestMarket { from, to };
+            let topic: StreamTopic = StreamTopic::ticker(exchange, &symbol);
+
+            let expected: &str = "cryptocom.ticker.btc.eur.error";
+
+            assert_eq!(topic.error().to_subject().as_str(), expected);
+        }
+
+        #[test]
+        fn ticker_topic_should_return_cryptocom_ticker_btc_usd_lease() {
+            let from: String = "BTC".to_string();
+            let to: String = "EUR".to_string();
+
+            let exchange: Exchange = Exchange::Cryptocom;
+            let symbol: TestMarket = TestMarket { from, to };
+            let topic: StreamTopic = StreamTopic::ticker(exchange, &symbol);
+
+            let expected: &str = "cryptocom.ticker.btc.eur.lease";
+
+            assert_eq!(topic.lease().to_subject().as_str(), expected);
+        }
     }
 
     mod trades {
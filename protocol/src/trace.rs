@@ -0,0 +1,88 @@
+use async_nats::{HeaderMap, Subject};
+use opentelemetry::global;
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::Context;
+use tracing::{info_span, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// W3C trace context of the current span written into nats headers e.g. traceparent
+pub fn inject(headers: &mut HeaderMap) {
+    let context: Context = Span::current().context();
+
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(headers))
+    });
+}
+
+/// Remote parent context sent by the publisher, empty context without trace headers
+pub fn extract(headers: Option<&HeaderMap>) -> Context {
+    match headers {
+        Some(headers) => global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(headers))
+        }),
+        None => Context::new(),
+    }
+}
+
+/// Span of the received nats message continuing the trace of the publisher
+pub fn remote_span(subject: &Subject, headers: Option<&HeaderMap>) -> Span {
+    let span: Span = info_span!("nats_message", subject = %subject);
+    span.set_parent(extract(headers));
+    span
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.insert(key, value);
+    }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(|value| value.as_str())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.iter().map(|(name, _)| name.as_ref()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trace::{extract, HeaderInjector};
+    use async_nats::HeaderMap;
+    use opentelemetry::global;
+    use opentelemetry::trace::TraceContextExt;
+    use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceId, TraceState};
+    use opentelemetry::Context;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+
+    #[test]
+    fn extract_should_return_context_injected_into_headers() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let span_context: SpanContext = SpanContext::new(
+            TraceId::from_bytes([1; 16]),
+            SpanId::from_bytes([2; 8]),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        let context: Context = Context::new().with_remote_span_context(span_context);
+        let mut headers: HeaderMap = HeaderMap::new();
+
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&context, &mut HeaderInjector(&mut headers))
+        });
+
+        let extracted: Context = extract(Some(&headers));
+
+        assert_eq!(
+            extracted.span().span_context().trace_id(),
+            TraceId::from_bytes([1; 16])
+        );
+    }
+}
@@ -0,0 +1,387 @@
+use crate::changes;
+use async_trait::async_trait;
+use chrono::Utc;
+use http::healthcheck::checks::{HealthCheck, HealthcheckResult};
+use protocol::public::error::{ErrorCode, ErrorMessage};
+use protocol::public::market::{Market, MarketChange};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+const HEALTHCHECK_NAME: &str = "markets";
+
+/// Markets snapshot served to the clients instead of calling the exchange on every request
+pub struct CachedMarkets {
+    pub markets: Vec<Market>,
+    pub age: i64,
+    pub stale: bool,
+}
+
+#[derive(Default)]
+struct CacheState {
+    markets: Vec<Market>,
+    updated: Option<i64>,
+    error: Option<ErrorMessage>,
+    /// Time of the last failed refresh, cleared on success
+    failed: Option<Instant>,
+}
+
+#[derive(Default)]
+pub struct MarketsCache {
+    state: RwLock<CacheState>,
+    /// Finished refreshes, successful or not
+    refreshes: AtomicU64,
+    loading: Mutex<()>,
+}
+
+impl MarketsCache {
+    /// Replaces cached markets on success and returns changes against the previous snapshot.
+    /// On failure the last markets are kept and marked as stale.
+    pub fn update(&self, result: Result<Vec<Market>, ErrorMessage>) -> Vec<MarketChange> {
+        self.update_at(result, Utc::now().timestamp_millis())
+    }
+
+    /// Same as update for markets loaded at the given time in millis e.g. shared listing,
+    /// so the cache age reflects the listing age instead of the time it was read.
+    pub fn update_at(
+        &self,
+        result: Result<Vec<Market>, ErrorMessage>,
+        timestamp: i64,
+    ) -> Vec<MarketChange> {
+        let mut state = self.state.write().unwrap();
+        self.refreshes.fetch_add(1, Ordering::SeqCst);
+
+        match result {
+            Ok(markets) => {
+                info!("Markets cache refreshed with {} markets", markets.len());
+                let changes: Vec<MarketChange> = match state.updated {
+                    Some(_) => changes::diff(&state.markets, &markets),
+                    None => vec![],
+                };
+                state.markets = markets;
+                state.updated = Some(timestamp);
+                state.error = None;
+                state.failed = None;
+                changes
+            }
+            Err(error) => {
+                warn!("Markets cache refresh failed: {}", error.message);
+                state.error = Some(error);
+                state.failed = Some(Instant::now());
+                vec![]
+            }
+        }
+    }
+
+    /// Calls refresh when markets are not loaded yet. Concurrent callers share one in-flight
+    /// refresh and take its result instead of calling the exchange again.
+    /// After a failed refresh the exchange is not called again within retry_after.
+    pub async fn load<F, Fut>(&self, retry_after: Duration, refresh: F)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let seen: u64 = self.refreshes.load(Ordering::SeqCst);
+        let _loading = self.loading.lock().await;
+
+        if !self.is_loaded()
+            && !self.is_backing_off(retry_after)
+            && self.refreshes.load(Ordering::SeqCst) == seen
+        {
+            refresh().await;
+        }
+    }
+
+    fn is_backing_off(&self, retry_after: Duration) -> bool {
+        self.state
+            .read()
+            .unwrap()
+            .failed
+            .is_some_and(|failed| failed.elapsed() < retry_after)
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.state.read().unwrap().updated.is_some()
+    }
+
+    /// Returns None when markets are not loaded yet
+    pub fn is_listed(&self, symbol: &str) -> Option<bool> {
+        let state = self.state.read().unwrap();
+
+        state
+            .updated
+            .map(|_| state.markets.iter().any(|market| market.symbol == symbol))
+    }
+
+    pub fn get(&self) -> Result<CachedMarkets, ErrorMessage> {
+        let state = self.state.read().unwrap();
+
+        match state.updated {
+            Some(updated) => Ok(CachedMarkets {
+                markets: state.markets.clone(),
+                age: Utc::now().timestamp_millis() - updated,
+                stale: state.error.is_some(),
+            }),
+            None => Err(state.error.clone().unwrap_or_else(not_loaded)),
+        }
+    }
+}
+
+/// Fails until markets are loaded and when the last successful refresh is older than max age
+pub struct MarketsHealthCheck {
+    cache: Arc<MarketsCache>,
+    max_age: Duration,
+    service: String,
+}
+
+impl MarketsHealthCheck {
+    pub fn new(cache: Arc<MarketsCache>, max_age: Duration) -> Self {
+        MarketsHealthCheck {
+            cache,
+            max_age,
+            service: String::from(HEALTHCHECK_NAME),
+        }
+    }
+
+    /// Service name for connectors with several caches e.g. markets-futures
+    pub fn named(mut self, service: &str) -> Self {
+        self.service = service.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl HealthCheck for MarketsHealthCheck {
+    async fn check(&self) -> HealthcheckResult {
+        let fresh: bool = self
+            .cache
+            .get()
+            .is_ok_and(|cached| cached.age <= self.max_age.as_millis() as i64);
+
+        HealthcheckResult {
+            service: self.service.clone(),
+            enabled: fresh,
+            details: vec![],
+        }
+    }
+}
+
+/// Calls refresh in the given interval. The first refresh is done immediately.
+pub async fn refresh<F, Fut>(interval: Duration, refresh: F) -> anyhow::Result<()>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut interval = tokio::time::interval(interval);
+
+    loop {
+        interval.tick().await;
+        refresh().await;
+    }
+}
+
+fn not_loaded() -> ErrorMessage {
+    ErrorMessage {
+        code: ErrorCode::UnderMaintenance as i32,
+        message: "Markets are not loaded yet!".to_string(),
+        timestamp: Utc::now().timestamp_millis(),
+        exchange_message: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::{MarketsCache, MarketsHealthCheck};
+    use chrono::Utc;
+    use http::healthcheck::checks::HealthCheck;
+    use protocol::public::error::{ErrorCode, ErrorMessage};
+    use protocol::public::market::{ChangeType, Market, MarketChange};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::join;
+
+    fn market(symbol: &str) -> Market {
+        Market {
+            symbol: symbol.to_string(),
+            ..Market::default()
+        }
+    }
+
+    fn error() -> ErrorMessage {
+        ErrorMessage {
+            code: ErrorCode::UnknownCode as i32,
+            message: "Error during request!".to_string(),
+            timestamp: Utc::now().timestamp_millis(),
+            exchange_message: None,
+        }
+    }
+
+    #[test]
+    fn get_should_return_error_when_cache_is_empty() {
+        let cache: MarketsCache = MarketsCache::default();
+
+        let result = cache.get();
+
+        assert!(!cache.is_loaded());
+        assert_eq!(
+            result.err().map(|error| error.code),
+            Some(ErrorCode::UnderMaintenance as i32)
+        );
+    }
+
+    #[test]
+    fn get_should_return_refresh_error_when_nothing_was_loaded() {
+        let cache: MarketsCache = MarketsCache::default();
+
+        cache.update(Err(error()));
+
+        assert_eq!(
+            cache.get().err().map(|error| error.code),
+            Some(ErrorCode::UnknownCode as i32)
+        );
+    }
+
+    #[test]
+    fn get_should_return_fresh_markets() {
+        let cache: MarketsCache = MarketsCache::default();
+
+        cache.update(Ok(vec![market("btc_usd")]));
+
+        let cached = cache.get().expect("cached markets");
+
+        assert!(cache.is_loaded());
+        assert!(!cached.stale);
+        assert_eq!(cached.markets, vec![market("btc_usd")]);
+    }
+
+    #[test]
+    fn update_should_not_return_changes_for_first_snapshot() {
+        let cache: MarketsCache = MarketsCache::default();
+
+        let changes: Vec<MarketChange> = cache.update(Ok(vec![market("btc_usd")]));
+
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn update_should_return_changes_against_previous_snapshot() {
+        let cache: MarketsCache = MarketsCache::default();
+
+        cache.update(Ok(vec![market("btc_usd")]));
+        cache.update(Err(error()));
+        let changes: Vec<MarketChange> = cache.update(Ok(vec![market("eth_usd")]));
+
+        let types: Vec<i32> = changes.iter().map(|change| change.r#type).collect();
+
+        assert_eq!(
+            types,
+            vec![ChangeType::Added as i32, ChangeType::Removed as i32]
+        );
+    }
+
+    #[test]
+    fn get_should_return_stale_markets_after_failed_refresh() {
+        let cache: MarketsCache = MarketsCache::default();
+
+        cache.update(Ok(vec![market("btc_usd")]));
+        cache.update(Err(error()));
+
+        let cached = cache.get().expect("cached markets");
+
+        assert!(cached.stale);
+        assert!(cached.age >= 0);
+        assert_eq!(cached.markets, vec![market("btc_usd")]);
+    }
+
+    #[test]
+    fn get_should_clear_stale_flag_after_successful_refresh() {
+        let cache: MarketsCache = MarketsCache::default();
+
+        cache.update(Err(error()));
+        cache.update(Ok(vec![market("eth_usd")]));
+
+        let cached = cache.get().expect("cached markets");
+
+        assert!(!cached.stale);
+        assert_eq!(cached.markets, vec![market("eth_usd")]);
+    }
+
+    #[tokio::test]
+    async fn load_should_share_refresh_between_concurrent_callers() {
+        let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+        let calls: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+
+        let load = || async {
+            cache
+                .load(Duration::from_secs(60), || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    cache.update(Err(error()));
+                })
+                .await
+        };
+        join!(load(), load(), load());
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(!cache.is_loaded());
+    }
+
+    #[tokio::test]
+    async fn check_should_fail_until_markets_are_loaded() {
+        let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+        let check: MarketsHealthCheck =
+            MarketsHealthCheck::new(cache.clone(), Duration::from_secs(60));
+
+        assert!(!check.check().await.enabled);
+
+        cache.update(Ok(vec![market("BTC_USD")]));
+
+        assert!(check.check().await.enabled);
+    }
+
+    #[tokio::test]
+    async fn check_should_report_named_service() {
+        let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+        let check: MarketsHealthCheck =
+            MarketsHealthCheck::new(cache, Duration::from_secs(60)).named("markets-futures");
+
+        assert_eq!(check.check().await.service, "markets-futures");
+    }
+
+    #[tokio::test]
+    async fn check_should_fail_for_old_listing() {
+        let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+        let check: MarketsHealthCheck =
+            MarketsHealthCheck::new(cache.clone(), Duration::from_secs(60));
+        let loaded: i64 = Utc::now().timestamp_millis() - 3_600_000;
+
+        cache.update_at(Ok(vec![market("BTC_USD")]), loaded);
+
+        assert!(cache.get().unwrap().age >= 3_600_000);
+        assert!(!check.check().await.enabled);
+    }
+
+    #[tokio::test]
+    async fn load_should_not_retry_failed_refresh_within_retry_interval() {
+        let cache: MarketsCache = MarketsCache::default();
+        let calls: AtomicUsize = AtomicUsize::new(0);
+        let refresh = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            cache.update(Err(error()));
+        };
+
+        cache.load(Duration::from_secs(60), refresh).await;
+        cache.load(Duration::from_secs(60), refresh).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        cache.load(Duration::ZERO, refresh).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,217 @@
+use protocol::public::market::{ChangeType, Market, MarketChange, MarketField};
+use std::collections::HashMap;
+
+/// The same symbol can be listed as spot, perpetual and futures with different expiry,
+/// or as several contracts of the same kind e.g. inverse and linear perpetual
+type MarketKey = (String, i32, Option<i64>, String);
+
+fn key(market: &Market) -> MarketKey {
+    (
+        market.symbol.clone(),
+        market.market_type,
+        market.expiry_timestamp,
+        market.instrument_id.clone(),
+    )
+}
+
+/// Compares two markets snapshots and returns added, removed and updated markets
+pub fn diff(previous: &[Market], current: &[Market]) -> Vec<MarketChange> {
+    let before: HashMap<MarketKey, &Market> = previous.iter().map(|m| (key(m), m)).collect();
+    let after: HashMap<MarketKey, &Market> = current.iter().map(|m| (key(m), m)).collect();
+
+    let added = current
+        .iter()
+        .filter(|market| !before.contains_key(&key(market)))
+        .map(|market| change(ChangeType::Added, market, None, vec![]));
+
+    let removed = previous
+        .iter()
+        .filter(|market| !after.contains_key(&key(market)))
+        .map(|market| change(ChangeType::Removed, market, None, vec![]));
+
+    let updated = current.iter().filter_map(|market| {
+        let old: &Market = before.get(&key(market))?;
+        let fields: Vec<MarketField> = fields(old, market);
+
+        if fields.is_empty() {
+            None
+        } else {
+            Some(change(ChangeType::Updated, market, Some(old), fields))
+        }
+    });
+
+    added.chain(removed).chain(updated).collect()
+}
+
+fn change(
+    change_type: ChangeType,
+    market: &Market,
+    previous: Option<&Market>,
+    fields: Vec<MarketField>,
+) -> MarketChange {
+    MarketChange {
+        r#type: change_type as i32,
+        market: Some(market.clone()),
+        previous: previous.cloned(),
+        fields: fields.into_iter().map(|field| field as i32).collect(),
+    }
+}
+
+fn fields(old: &Market, new: &Market) -> Vec<MarketField> {
+    let checks: [(MarketField, bool); 10] = [
+        (
+            MarketField::PricePrecision,
+            old.price_precision != new.price_precision,
+        ),
+        (
+            MarketField::RatePrecision,
+            old.rate_precision != new.rate_precision,
+        ),
+        (
+            MarketField::SizePrecision,
+            old.size_precision != new.size_precision,
+        ),
+        (MarketField::MinSize, old.min_size != new.min_size),
+        (MarketField::MinPrice, old.min_price != new.min_price),
+        (
+            MarketField::PriceTickSize,
+            old.price_tick_size != new.price_tick_size,
+        ),
+        (
+            MarketField::SizeTickSize,
+            old.size_tick_size != new.size_tick_size,
+        ),
+        (
+            MarketField::MinNotional,
+            old.min_notional != new.min_notional,
+        ),
+        (
+            MarketField::ContractSize,
+            old.contract_size != new.contract_size,
+        ),
+        (MarketField::Status, old.status != new.status),
+    ];
+
+    checks
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(field, _)| field)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::changes::diff;
+    use protocol::public::market::{
+        ChangeType, Market, MarketChange, MarketField, MarketStatus, MarketType,
+    };
+
+    fn market(symbol: &str, price_tick_size: &str) -> Market {
+        Market {
+            symbol: symbol.to_string(),
+            price_precision: 2,
+            rate_precision: 2,
+            size_precision: 4,
+            min_size: "0.0001".to_string(),
+            price_tick_size: price_tick_size.to_string(),
+            size_tick_size: "0.0001".to_string(),
+            ..Market::default()
+        }
+    }
+
+    #[test]
+    fn diff_should_return_nothing_for_same_snapshots() {
+        let markets: Vec<Market> = vec![market("btc_usd", "0.01"), market("eth_usd", "0.01")];
+
+        assert!(diff(&markets, &markets).is_empty());
+    }
+
+    #[test]
+    fn diff_should_return_added_market() {
+        let previous: Vec<Market> = vec![market("btc_usd", "0.01")];
+        let current: Vec<Market> = vec![market("btc_usd", "0.01"), market("eth_usd", "0.01")];
+
+        let changes: Vec<MarketChange> = diff(&previous, &current);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].r#type, ChangeType::Added as i32);
+        assert_eq!(changes[0].market, Some(market("eth_usd", "0.01")));
+        assert_eq!(changes[0].previous, None);
+    }
+
+    #[test]
+    fn diff_should_return_removed_market() {
+        let previous: Vec<Market> = vec![market("btc_usd", "0.01"), market("eth_usd", "0.01")];
+        let current: Vec<Market> = vec![market("eth_usd", "0.01")];
+
+        let changes: Vec<MarketChange> = diff(&previous, &current);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].r#type, ChangeType::Removed as i32);
+        assert_eq!(changes[0].market, Some(market("btc_usd", "0.01")));
+    }
+
+    #[test]
+    fn diff_should_return_updated_market_with_changed_fields() {
+        let previous: Vec<Market> = vec![market("btc_usd", "0.01")];
+        let mut updated: Market = market("btc_usd", "0.1");
+        updated.size_precision = 5;
+        updated.status = MarketStatus::CancelOnly as i32;
+        let current: Vec<Market> = vec![updated.clone()];
+
+        let changes: Vec<MarketChange> = diff(&previous, &current);
+
+        let expected: Vec<i32> = vec![
+            MarketField::SizePrecision as i32,
+            MarketField::PriceTickSize as i32,
+            MarketField::Status as i32,
+        ];
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].r#type, ChangeType::Updated as i32);
+        assert_eq!(changes[0].market, Some(updated));
+        assert_eq!(changes[0].previous, Some(market("btc_usd", "0.01")));
+        assert_eq!(changes[0].fields, expected);
+    }
+
+    #[test]
+    fn diff_should_distinguish_market_types_with_same_symbol() {
+        let spot: Market = market("btc_usd", "0.01");
+        let perpetual: Market = Market {
+            market_type: MarketType::Perpetual as i32,
+            ..market("btc_usd", "0.01")
+        };
+
+        let previous: Vec<Market> = vec![spot.clone()];
+        let current: Vec<Market> = vec![spot, perpetual.clone()];
+
+        let changes: Vec<MarketChange> = diff(&previous, &current);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].r#type, ChangeType::Added as i32);
+        assert_eq!(changes[0].market, Some(perpetual));
+    }
+
+    #[test]
+    fn diff_should_distinguish_contracts_with_same_symbol_and_type() {
+        let inverse: Market = Market {
+            market_type: MarketType::Perpetual as i32,
+            instrument_id: "PI_XBTUSD".to_string(),
+            ..market("btc_usd", "0.5")
+        };
+        let linear: Market = Market {
+            market_type: MarketType::Perpetual as i32,
+            instrument_id: "PF_XBTUSD".to_string(),
+            ..market("btc_usd", "1")
+        };
+
+        let previous: Vec<Market> = vec![inverse.clone(), linear.clone()];
+        let current: Vec<Market> = vec![inverse];
+
+        let changes: Vec<MarketChange> = diff(&previous, &current);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].r#type, ChangeType::Removed as i32);
+        assert_eq!(changes[0].market, Some(linear));
+    }
+}
@@ -0,0 +1,32 @@
+use crate::leader::Leader;
+use crate::shard::Shard;
+
+const MARKETS_KEY: &str = "markets";
+
+/// Replica refreshing markets from the exchange and publishing their changes.
+/// The elected leader, with sharding the replica owning the markets listing.
+#[derive(Clone, Default)]
+pub struct Coordinator {
+    leader: Leader,
+    shard: Shard,
+}
+
+impl Coordinator {
+    pub fn new(leader: Leader, shard: Shard) -> Self {
+        Coordinator { leader, shard }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.leader.is_leader() && self.shard.owns(MARKETS_KEY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coordinator::Coordinator;
+
+    #[test]
+    fn coordinator_should_be_active_without_election_and_sharding() {
+        assert!(Coordinator::default().is_active());
+    }
+}
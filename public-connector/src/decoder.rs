@@ -1,7 +1,9 @@
 use anyhow::anyhow;
 use anyhow::Result;
-use async_nats::{Message as NatsMessage, Subject};
+use async_nats::{HeaderMap, Message as NatsMessage, Subject};
 use prost::Message as ProtoMessage;
+use protocol::trace;
+use tracing::Span;
 
 const TOPIC_SEPARATOR: &str = ".";
 
@@ -9,6 +11,7 @@ pub struct NatsEvent<T> {
     pub message: T,
     pub subject: Subject,
     pub reply: Option<Subject>,
+    pub headers: Option<HeaderMap>,
 }
 
 impl<T> NatsEvent<T> {
@@ -30,6 +33,11 @@ impl<T> NatsEvent<T> {
             Err(anyhow!("Wrong topic format {}", self.subject.to_string()))
         }
     }
+
+    /// Span continuing the trace propagated in nats headers by the requester
+    pub fn span(&self) -> Span {
+        trace::remote_span(&self.subject, self.headers.as_ref())
+    }
 }
 
 pub(crate) fn decode<T: ProtoMessage + Default>(message: NatsMessage) -> Result<NatsEvent<T>> {
@@ -39,5 +47,6 @@ pub(crate) fn decode<T: ProtoMessage + Default>(message: NatsMessage) -> Result<
             message: proto,
             subject: message.subject,
             reply: message.reply,
+            headers: message.headers,
         })
 }
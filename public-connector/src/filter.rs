@@ -0,0 +1,187 @@
+use protocol::public::market::{Market, MarketsRequest};
+
+const ANY: char = '*';
+const SINGLE: char = '?';
+
+/// Returns markets matching all filters from request.
+/// Empty filter list means that filter is not applied.
+/// # Examples
+/// symbols: btc_usd, eth_usd
+/// base currencies: btc, eth
+/// quote currencies: usd, usdt
+/// patterns: *_usdt, btc_*
+pub fn filter(markets: Vec<Market>, request: &MarketsRequest) -> Vec<Market> {
+    markets
+        .into_iter()
+        .filter(|market| is_matching(market, request))
+        .collect()
+}
+
+fn is_matching(market: &Market, request: &MarketsRequest) -> bool {
+    check_type(request.market_type, market.market_type)
+        && check_list(&request.symbols, |symbol| market.symbol == symbol)
+        && check_list(&request.base_currencies, |base| {
+            market.base_currency == base
+        })
+        && check_list(&request.quote_currencies, |quote| {
+            market.quote_currency == quote
+        })
+        && check_list(&request.patterns, |pattern| {
+            is_glob_match(pattern, &market.symbol)
+        })
+}
+
+fn check_type(opt: Option<i32>, market_type: i32) -> bool {
+    opt.is_none_or(|t| t == market_type)
+}
+
+fn check_list<F: Fn(&str) -> bool>(values: &[String], check: F) -> bool {
+    values.is_empty() || values.iter().any(|value| check(&value.to_lowercase()))
+}
+
+/// Glob matching with '*' for any sequence and '?' for any single character
+pub fn is_glob_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+
+    let (mut p, mut v): (usize, usize) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == SINGLE || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == ANY {
+            backtrack = Some((p, v));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            v = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == ANY)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filter::{filter, is_glob_match};
+    use protocol::public::market::{Market, MarketType, MarketsRequest};
+
+    fn market(base: &str, quote: &str, market_type: MarketType) -> Market {
+        Market {
+            symbol: format!("{}_{}", base, quote),
+            base_currency: base.to_string(),
+            quote_currency: quote.to_string(),
+            market_type: market_type as i32,
+            ..Market::default()
+        }
+    }
+
+    fn markets() -> Vec<Market> {
+        vec![
+            market("btc", "usd", MarketType::Spot),
+            market("btc", "usdt", MarketType::Spot),
+            market("eth", "usdt", MarketType::Spot),
+            market("btc", "usd", MarketType::Perpetual),
+            market("eth", "usd", MarketType::Future),
+        ]
+    }
+
+    fn symbols(markets: &[Market]) -> Vec<(&str, i32)> {
+        markets
+            .iter()
+            .map(|market| (market.symbol.as_str(), market.market_type))
+            .collect()
+    }
+
+    #[test]
+    fn filter_should_return_all_markets_for_empty_request() {
+        let request: MarketsRequest = MarketsRequest::default();
+
+        assert_eq!(filter(markets(), &request), markets());
+    }
+
+    #[test]
+    fn filter_should_apply_market_type_without_symbols() {
+        let request: MarketsRequest = MarketsRequest {
+            market_type: Some(MarketType::Perpetual as i32),
+            ..MarketsRequest::default()
+        };
+
+        let result: Vec<Market> = filter(markets(), &request);
+
+        assert_eq!(
+            symbols(&result),
+            vec![("btc_usd", MarketType::Perpetual as i32)]
+        );
+    }
+
+    #[test]
+    fn filter_should_apply_symbols_and_market_type() {
+        let request: MarketsRequest = MarketsRequest {
+            symbols: vec!["btc_usd".to_string()],
+            market_type: Some(MarketType::Spot as i32),
+            ..MarketsRequest::default()
+        };
+
+        let result: Vec<Market> = filter(markets(), &request);
+
+        assert_eq!(symbols(&result), vec![("btc_usd", MarketType::Spot as i32)]);
+    }
+
+    #[test]
+    fn filter_should_apply_base_and_quote_currencies() {
+        let request: MarketsRequest = MarketsRequest {
+            base_currencies: vec!["BTC".to_string(), "eth".to_string()],
+            quote_currencies: vec!["usdt".to_string()],
+            ..MarketsRequest::default()
+        };
+
+        let result: Vec<Market> = filter(markets(), &request);
+
+        assert_eq!(
+            symbols(&result),
+            vec![
+                ("btc_usdt", MarketType::Spot as i32),
+                ("eth_usdt", MarketType::Spot as i32)
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_should_apply_patterns() {
+        let request: MarketsRequest = MarketsRequest {
+            patterns: vec!["*_usdt".to_string()],
+            market_type: Some(MarketType::Spot as i32),
+            ..MarketsRequest::default()
+        };
+
+        let result: Vec<Market> = filter(markets(), &request);
+
+        assert_eq!(
+            symbols(&result),
+            vec![
+                ("btc_usdt", MarketType::Spot as i32),
+                ("eth_usdt", MarketType::Spot as i32)
+            ]
+        );
+    }
+
+    #[test]
+    fn glob_match_should_support_wildcards() {
+        assert!(is_glob_match("*_usdt", "btc_usdt"));
+        assert!(is_glob_match("btc_*", "btc_usdt"));
+        assert!(is_glob_match("*_*", "btc_usdt"));
+        assert!(is_glob_match("*", "btc_usdt"));
+        assert!(is_glob_match("?th_usd", "eth_usd"));
+        assert!(is_glob_match("eth_usd", "eth_usd"));
+        assert!(!is_glob_match("*_usdt", "btc_usd"));
+        assert!(!is_glob_match("btc_*", "eth_usd"));
+        assert!(!is_glob_match("?_usd", "eth_usd"));
+        assert!(!is_glob_match("", "eth_usd"));
+    }
+}
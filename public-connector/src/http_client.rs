@@ -3,7 +3,8 @@ use protocol::public::error::{ErrorCode, ErrorMessage};
 use reqwest::{Error, Response, Url};
 use serde::de::DeserializeOwned;
 use std::error::Error as StdError;
-use tracing::{info, warn};
+use std::time::Duration;
+use tracing::{info, instrument, warn};
 
 pub struct HttpClient {
     client: reqwest::Client,
@@ -19,6 +20,7 @@ impl Default for HttpClient {
 }
 
 impl HttpClient {
+    #[instrument(name = "exchange_request", skip_all, fields(url = %url))]
     pub async fn get<T: DeserializeOwned, E: DeserializeOwned + StdError>(
         &self,
         url: &Url,
@@ -30,6 +32,20 @@ impl HttpClient {
             Err(error) => Err(from_error(error)),
         }
     }
+
+    /// Exchange api answering with success status within the timeout
+    pub async fn probe(&self, url: &Url, timeout: Duration) -> bool {
+        let response: Result<Response, Error> =
+            self.client.get(url.clone()).timeout(timeout).send().await;
+
+        match response {
+            Ok(payload) => payload.status().is_success(),
+            Err(error) => {
+                warn!("Exchange api probe failed: {}", error);
+                false
+            }
+        }
+    }
 }
 
 async fn decode<T: DeserializeOwned, E: DeserializeOwned + StdError>(
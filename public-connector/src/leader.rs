@@ -0,0 +1,177 @@
+use anyhow::Result;
+use async_nats::jetstream::kv::{Config, Store};
+use async_nats::jetstream::Context;
+use protocol::client::NatsClient;
+use protocol::public::types::Exchange;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+const LEADER_KEY: &str = "leader";
+const RENEWALS_PER_TIMEOUT: u32 = 3;
+
+/// Leadership term, revision of the lease acquired by the leader
+type Epoch = Option<u64>;
+
+/// Replica view on the active/standby election.
+/// Without running election (standby disabled) the replica is always the leader of epoch 0.
+#[derive(Clone)]
+pub struct Leader {
+    state: watch::Receiver<Epoch>,
+}
+
+impl Default for Leader {
+    fn default() -> Self {
+        let (_, state): (watch::Sender<Epoch>, watch::Receiver<Epoch>) = watch::channel(Some(0));
+
+        Leader { state }
+    }
+}
+
+impl Leader {
+    pub fn is_leader(&self) -> bool {
+        self.state.borrow().is_some()
+    }
+
+    /// Grows with every leadership takeover, 0 when not leading
+    pub fn epoch(&self) -> u64 {
+        self.state.borrow().unwrap_or_default()
+    }
+
+    /// Waits for leadership change. Returns false when election is not running.
+    pub async fn changed(&mut self) -> bool {
+        let changed: bool = self.state.changed().await.is_ok();
+        self.state.borrow_and_update();
+        changed
+    }
+}
+
+/// Leader lease in nats key value bucket {exchange}-leader.
+/// Lease not renewed within timeout expires and standby replica takes over.
+pub struct Election {
+    store: Store,
+    replica: String,
+    timeout: Duration,
+    leader: watch::Sender<Epoch>,
+}
+
+impl Election {
+    pub async fn join(
+        nats_client: &NatsClient,
+        exchange: Exchange,
+        replica: String,
+        timeout: Duration,
+    ) -> Result<(Election, Leader)> {
+        let bucket: String = format!("{}-leader", exchange.as_str_name().to_lowercase());
+        let store: Store = bucket_store(nats_client.jetstream(), bucket, timeout).await?;
+
+        info!("Replica {} joining leader election", replica);
+
+        let (sender, receiver): (watch::Sender<Epoch>, watch::Receiver<Epoch>) =
+            watch::channel(None);
+
+        let election: Election = Election {
+            store,
+            replica,
+            timeout,
+            leader: sender,
+        };
+
+        Ok((election, Leader { state: receiver }))
+    }
+
+    /// Renews the lease as leader or tries to acquire it as standby
+    pub async fn run(&self) -> Result<()> {
+        let mut interval = tokio::time::interval(self.timeout / RENEWALS_PER_TIMEOUT);
+        let mut revision: Option<u64> = None;
+
+        loop {
+            interval.tick().await;
+
+            revision = match revision {
+                Some(current) => self.renew(current).await,
+                None => self.acquire().await,
+            };
+
+            self.leader
+                .send_if_modified(|leader| match (*leader, revision) {
+                    (None, Some(epoch)) => {
+                        info!(
+                            "Replica {} elected as leader of epoch {}",
+                            self.replica, epoch
+                        );
+                        *leader = Some(epoch);
+                        true
+                    }
+                    (Some(_), None) => {
+                        warn!("Replica {} lost leadership", self.replica);
+                        *leader = None;
+                        true
+                    }
+                    _ => false,
+                });
+        }
+    }
+
+    /// Releases the lease so standby replica takes over without waiting for timeout
+    pub async fn resign(&self) -> Result<()> {
+        if self.leader.borrow().is_some() {
+            info!("Replica {} resigning leadership", self.replica);
+
+            self.leader.send_replace(None);
+            self.store.delete(LEADER_KEY).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn acquire(&self) -> Option<u64> {
+        self.store
+            .create(LEADER_KEY, self.replica.clone().into())
+            .await
+            .ok()
+    }
+
+    async fn renew(&self, revision: u64) -> Option<u64> {
+        match self
+            .store
+            .update(LEADER_KEY, self.replica.clone().into(), revision)
+            .await
+        {
+            Ok(revision) => Some(revision),
+            Err(error) => {
+                warn!("Cannot renew leader lease: {}", error);
+                None
+            }
+        }
+    }
+}
+
+async fn bucket_store(jetstream: Context, bucket: String, timeout: Duration) -> Result<Store> {
+    if let Ok(store) = jetstream.get_key_value(&bucket).await {
+        return Ok(store);
+    }
+
+    let config: Config = Config {
+        bucket,
+        history: 1,
+        max_age: timeout,
+        ..Config::default()
+    };
+
+    Ok(jetstream.create_key_value(config).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::leader::Leader;
+
+    #[tokio::test]
+    async fn leader_should_lead_without_election() {
+        let mut leader: Leader = Leader::default();
+
+        assert!(leader.is_leader());
+        assert_eq!(leader.epoch(), 0);
+        assert!(!leader.changed().await);
+    }
+}
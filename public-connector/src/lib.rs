@@ -1,5 +1,16 @@
+pub mod cache;
+pub mod changes;
 pub mod config;
+pub mod coordinator;
 pub mod decoder;
+pub mod filter;
 pub mod http_client;
+pub mod leader;
+pub mod listing;
+pub mod shard;
+pub mod shutdown;
+pub mod stream;
 pub mod subscription;
+pub mod supervisor;
 pub mod utils;
+pub mod whitelist;
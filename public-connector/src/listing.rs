@@ -0,0 +1,84 @@
+use anyhow::Result;
+use async_nats::jetstream::kv::{Config, Store};
+use async_nats::jetstream::Context;
+use chrono::Utc;
+use prost::Message;
+use protocol::client::NatsClient;
+use protocol::public::error::{ErrorCode, ErrorMessage};
+use protocol::public::market::{Market, MarketsMessage};
+use protocol::public::types::Exchange;
+use std::time::Duration;
+use tracing::info;
+
+const LISTING_KEY: &str = "listing";
+
+/// Markets listing shared by the coordinator in nats key value bucket {exchange}-markets.
+/// Other replicas take it instead of calling the exchange. Listing not refreshed within
+/// max age expires, so replicas report stale markets when the coordinator is gone.
+pub struct SharedListing {
+    store: Store,
+    exchange: Exchange,
+}
+
+impl SharedListing {
+    pub async fn open(
+        nats_client: &NatsClient,
+        exchange: Exchange,
+        max_age: Duration,
+    ) -> Result<SharedListing> {
+        let bucket: String = format!("{}-markets", exchange.as_str_name().to_lowercase());
+        let jetstream: Context = nats_client.jetstream();
+
+        info!("Sharing markets listing in bucket {}", bucket);
+
+        let store: Store = match jetstream.get_key_value(&bucket).await {
+            Ok(store) => store,
+            Err(_) => {
+                let config: Config = Config {
+                    bucket,
+                    history: 1,
+                    max_age,
+                    ..Config::default()
+                };
+                jetstream.create_key_value(config).await?
+            }
+        };
+
+        Ok(SharedListing { store, exchange })
+    }
+
+    pub async fn put(&self, markets: &[Market]) -> Result<()> {
+        let message: MarketsMessage = MarketsMessage {
+            timestamp: Utc::now().timestamp_millis(),
+            exchange: self.exchange as i32,
+            markets: markets.to_vec(),
+            stale: false,
+            cache_age: 0,
+        };
+
+        self.store
+            .put(LISTING_KEY, message.encode_to_vec().into())
+            .await?;
+        Ok(())
+    }
+
+    /// Listing with the time in millis it was loaded from the exchange by the coordinator
+    pub async fn get(&self) -> Result<MarketsMessage, ErrorMessage> {
+        match self.store.get(LISTING_KEY).await {
+            Ok(Some(value)) => {
+                MarketsMessage::decode(value).map_err(|error| listing_error(error.to_string()))
+            }
+            Ok(None) => Err(listing_error("Markets listing not shared yet".to_string())),
+            Err(error) => Err(listing_error(error.to_string())),
+        }
+    }
+}
+
+fn listing_error(message: String) -> ErrorMessage {
+    ErrorMessage {
+        code: ErrorCode::UnderMaintenance as i32,
+        message,
+        timestamp: Utc::now().timestamp_millis(),
+        exchange_message: None,
+    }
+}
@@ -0,0 +1,320 @@
+use anyhow::Result;
+use async_nats::jetstream::kv::{Config, Store};
+use async_nats::jetstream::Context;
+use chrono::Utc;
+use futures::TryStreamExt;
+use protocol::client::NatsClient;
+use protocol::model::Symbol;
+use protocol::public::types::Exchange;
+use std::collections::BTreeMap;
+use std::env;
+use std::process;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+const VIRTUAL_NODES: usize = 64;
+const HEARTBEAT: Duration = Duration::from_secs(5);
+const REPLICA_TTL: Duration = Duration::from_secs(15);
+const HOSTNAME: &str = "HOSTNAME";
+
+/// Consistent hashing of markets to connector replicas
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HashRing {
+    nodes: BTreeMap<u64, String>,
+}
+
+impl HashRing {
+    pub fn new(replicas: &[String]) -> Self {
+        let nodes: BTreeMap<u64, String> = replicas
+            .iter()
+            .flat_map(|replica| {
+                (0..VIRTUAL_NODES)
+                    .map(move |node| (hash(&format!("{}#{}", replica, node)), replica.clone()))
+            })
+            .collect();
+
+        HashRing { nodes }
+    }
+
+    /// Replica owning the key, None for empty ring
+    pub fn owner(&self, key: &str) -> Option<&str> {
+        self.nodes
+            .range(hash(key)..)
+            .next()
+            .or_else(|| self.nodes.iter().next())
+            .map(|(_, replica)| replica.as_str())
+    }
+}
+
+/// Replica view on the markets ownership.
+/// Without joined replicas (sharding disabled) all markets are owned.
+#[derive(Clone)]
+pub struct Shard {
+    replica: String,
+    ring: watch::Receiver<HashRing>,
+}
+
+impl Default for Shard {
+    fn default() -> Self {
+        let (_, ring): (watch::Sender<HashRing>, watch::Receiver<HashRing>) =
+            watch::channel(HashRing::default());
+
+        Shard {
+            replica: default_replica(),
+            ring,
+        }
+    }
+}
+
+impl Shard {
+    pub fn replica(&self) -> &str {
+        &self.replica
+    }
+
+    pub fn owner<S: Symbol>(&self, market: &S) -> Option<String> {
+        self.ring
+            .borrow()
+            .owner(&market.nats_format())
+            .map(str::to_string)
+    }
+
+    pub fn is_owner<S: Symbol>(&self, market: &S) -> bool {
+        self.owns(&market.nats_format())
+    }
+
+    /// Ownership of any key e.g. exchange wide work done by a single replica
+    pub fn owns(&self, key: &str) -> bool {
+        self.ring
+            .borrow()
+            .owner(key)
+            .is_none_or(|owner| owner == self.replica)
+    }
+
+    /// Waits for replicas join or leave. Returns false when membership is not running.
+    pub async fn changed(&mut self) -> bool {
+        let changed: bool = self.ring.changed().await.is_ok();
+        self.ring.borrow_and_update();
+        changed
+    }
+}
+
+/// Replica registration in nats key value bucket {exchange}-replicas.
+/// Replicas not renewed within ttl are removed from the ring.
+pub struct Membership {
+    store: Store,
+    replica: String,
+    ring: watch::Sender<HashRing>,
+}
+
+impl Membership {
+    pub async fn join(
+        nats_client: &NatsClient,
+        exchange: Exchange,
+        replica: String,
+    ) -> Result<(Membership, Shard)> {
+        let bucket: String = format!("{}-replicas", exchange.as_str_name().to_lowercase());
+        let store: Store = bucket_store(nats_client.jetstream(), bucket).await?;
+
+        info!("Replica {} joining markets sharding", replica);
+
+        let (sender, receiver): (watch::Sender<HashRing>, watch::Receiver<HashRing>) =
+            watch::channel(HashRing::default());
+
+        let membership: Membership = Membership {
+            store,
+            replica: replica.clone(),
+            ring: sender,
+        };
+
+        membership.heartbeat().await?;
+
+        Ok((
+            membership,
+            Shard {
+                replica,
+                ring: receiver,
+            },
+        ))
+    }
+
+    /// Renews registration and rebalances the ring on replicas change
+    pub async fn run(&self) -> Result<()> {
+        let mut interval = tokio::time::interval(HEARTBEAT);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(error) = self.heartbeat().await {
+                warn!("Cannot renew replica {}: {}", self.replica, error)
+            }
+        }
+    }
+
+    pub async fn leave(&self) -> Result<()> {
+        info!("Replica {} leaving markets sharding", self.replica);
+
+        self.store.delete(&self.replica).await?;
+        Ok(())
+    }
+
+    async fn heartbeat(&self) -> Result<()> {
+        let timestamp: String = Utc::now().timestamp_millis().to_string();
+        self.store.put(&self.replica, timestamp.into()).await?;
+
+        let mut replicas: Vec<String> = self.store.keys().await?.try_collect().await?;
+        replicas.sort();
+
+        let ring: HashRing = HashRing::new(&replicas);
+
+        self.ring.send_if_modified(|current| {
+            if *current == ring {
+                false
+            } else {
+                info!("Rebalancing markets between replicas {:?}", replicas);
+                *current = ring;
+                true
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// Replica id from HOSTNAME, dots, wildcards and whitespace are not allowed in nats subject tokens
+pub fn default_replica() -> String {
+    replica_token(&env::var(HOSTNAME).unwrap_or_else(|_| format!("replica-{}", process::id())))
+}
+
+fn replica_token(replica: &str) -> String {
+    replica
+        .chars()
+        .map(|char| match char {
+            '.' | '*' | '>' => '-',
+            char if char.is_whitespace() => '-',
+            char => char,
+        })
+        .collect()
+}
+
+async fn bucket_store(jetstream: Context, bucket: String) -> Result<Store> {
+    if let Ok(store) = jetstream.get_key_value(&bucket).await {
+        return Ok(store);
+    }
+
+    let config: Config = Config {
+        bucket,
+        history: 1,
+        max_age: REPLICA_TTL,
+        ..Config::default()
+    };
+
+    Ok(jetstream.create_key_value(config).await?)
+}
+
+/// FNV-1a with splitmix64 finalizer, stable between replicas and releases
+fn hash(value: &str) -> u64 {
+    let hash: u64 = value
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash: u64, byte: u8| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+    let hash: u64 = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    let hash: u64 = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shard::{replica_token, HashRing, Shard};
+    use protocol::model::{Currency, Symbol};
+    use tokio::sync::watch;
+
+    struct TestMarket(String);
+
+    impl Symbol for TestMarket {
+        fn from(&self) -> Currency {
+            Currency::new(self.0.clone())
+        }
+
+        fn to(&self) -> Currency {
+            Currency::new("usd".to_string())
+        }
+
+        fn exchange_format(&self) -> String {
+            self.nats_format().to_uppercase()
+        }
+    }
+
+    fn replicas(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn keys() -> Vec<String> {
+        (0..1000).map(|key| format!("market{}_usd", key)).collect()
+    }
+
+    #[test]
+    fn owner_should_return_none_for_empty_ring() {
+        assert_eq!(HashRing::default().owner("btc_usd"), None);
+    }
+
+    #[test]
+    fn owner_should_spread_markets_between_replicas() {
+        let ring: HashRing = HashRing::new(&replicas(&["a", "b", "c"]));
+
+        for replica in ["a", "b", "c"] {
+            let owned: usize = keys()
+                .iter()
+                .filter(|key| ring.owner(key) == Some(replica))
+                .count();
+
+            assert!(owned > 200, "{} owns only {} markets", replica, owned);
+        }
+    }
+
+    #[test]
+    fn owner_should_move_only_markets_of_new_replica_on_join() {
+        let before: HashRing = HashRing::new(&replicas(&["a", "b"]));
+        let after: HashRing = HashRing::new(&replicas(&["a", "b", "c"]));
+
+        for key in keys() {
+            let owner: Option<&str> = after.owner(&key);
+
+            assert!(owner == before.owner(&key) || owner == Some("c"));
+        }
+    }
+
+    #[test]
+    fn shard_should_own_only_keys_of_its_replica() {
+        let ring: HashRing = HashRing::new(&replicas(&["a", "b"]));
+        let owner: String = ring.owner("markets").unwrap().to_string();
+        let (_sender, receiver): (watch::Sender<HashRing>, watch::Receiver<HashRing>) =
+            watch::channel(ring);
+
+        let shard = |replica: &str| Shard {
+            replica: replica.to_string(),
+            ring: receiver.clone(),
+        };
+
+        let other: &str = if owner == "a" { "b" } else { "a" };
+
+        assert!(shard(&owner).owns("markets"));
+        assert!(!shard(other).owns("markets"));
+    }
+
+    #[test]
+    fn shard_should_own_all_markets_without_membership() {
+        let shard: Shard = Shard::default();
+
+        assert!(shard.is_owner(&TestMarket("btc".to_string())));
+    }
+
+    #[test]
+    fn replica_token_should_replace_subject_special_characters() {
+        assert_eq!(replica_token("pod-1.svc"), "pod-1-svc");
+        assert_eq!(replica_token("pod*>1"), "pod--1");
+        assert_eq!(replica_token("pod 1\t2"), "pod-1-2");
+    }
+}
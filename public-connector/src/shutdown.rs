@@ -0,0 +1,70 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use http::healthcheck::checks::{HealthCheck, HealthcheckResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::select;
+use tokio::signal::unix::{signal as unix_signal, Signal, SignalKind};
+use tracing::info;
+
+const NAME: &str = "shutdown";
+
+/// Waits for SIGTERM (kubernetes pod termination) or ctrl-c
+pub async fn signal() -> Result<()> {
+    let mut terminate: Signal = unix_signal(SignalKind::terminate())?;
+
+    select! {
+        _ = terminate.recv() => info!("SIGTERM received, shutting down"),
+        result = tokio::signal::ctrl_c() => {
+            result?;
+            info!("SIGINT received, shutting down")
+        }
+    }
+
+    Ok(())
+}
+
+/// Readiness failing once graceful shutdown started
+#[derive(Clone, Default)]
+pub struct Draining {
+    started: Arc<AtomicBool>,
+}
+
+impl Draining {
+    pub fn start(&self) {
+        self.started.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl HealthCheck for Draining {
+    async fn check(&self) -> HealthcheckResult {
+        HealthcheckResult {
+            service: String::from(NAME),
+            enabled: !self.is_started(),
+            details: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shutdown::Draining;
+    use http::healthcheck::checks::HealthCheck;
+
+    #[tokio::test]
+    async fn check_should_fail_after_shutdown_started() {
+        let draining: Draining = Draining::default();
+        let check: Box<dyn HealthCheck> = Box::new(draining.clone());
+
+        assert!(check.check().await.enabled);
+
+        draining.start();
+
+        assert!(!check.check().await.enabled);
+    }
+}
@@ -0,0 +1,28 @@
+use anyhow::Result;
+use protocol::model::Symbol;
+use protocol::public::types::Exchange;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// Exchange specific part of the streams.
+/// NATS plumbing, snapshots and tasks lifecycle are shared by all exchanges.
+pub trait ExchangeAdapter: Send + Sync + 'static {
+    type Market: Symbol + Clone + Debug + Eq + Hash + Send + Sync + 'static;
+    type Channel: Display + Send;
+    type Request: Send;
+
+    fn exchange(&self) -> Exchange;
+
+    /// Market parsed from nats subject e.g. exchange.ticker.btc.usd.snapshot
+    fn market(&self, from: String, to: String) -> Self::Market;
+
+    fn subscribe(&self, market: &Self::Market, channel: &Self::Channel) -> Self::Request;
+
+    fn unsubscribe(&self, market: &Self::Market, channel: &Self::Channel) -> Self::Request;
+
+    /// Sends subscribe or unsubscribe request to the exchange websocket
+    fn send(&self, request: Self::Request) -> Result<()>;
+
+    /// Websocket connection serving the subscription, None when not subscribed
+    fn connection(&self, market: &Self::Market, channel: &Self::Channel) -> Option<usize>;
+}
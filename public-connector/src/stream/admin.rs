@@ -0,0 +1,194 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use http::models::errors::{ErrorCode, HttpError};
+use http::subscriptions::hook::{Action, SubscriptionInfo, SubscriptionsHook};
+use serde_json::Value;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+
+pub type SnapshotReply = oneshot::Sender<Result<Value, HttpError>>;
+
+/// Admin request processed by the running handler
+pub enum Command {
+    List(oneshot::Sender<Vec<MarketStats>>),
+    Execute(Action, String, oneshot::Sender<Result<(), HttpError>>),
+    Snapshot(String, SnapshotReply),
+}
+
+pub struct MarketStats {
+    pub market: String,
+    pub sequence: i64,
+    pub updated: Option<i64>,
+    pub messages: u64,
+}
+
+/// Counters of a single market task, read by the handler on admin request
+#[derive(Default)]
+pub struct Stats {
+    sequence: AtomicI64,
+    updated: AtomicI64,
+    messages: AtomicU64,
+}
+
+impl Stats {
+    pub fn updated(&self, sequence: i64) {
+        self.sequence.store(sequence, Ordering::Relaxed);
+        self.updated
+            .store(Utc::now().timestamp_millis(), Ordering::Relaxed);
+        self.messages.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self, market: String) -> MarketStats {
+        let updated: i64 = self.updated.load(Ordering::Relaxed);
+
+        MarketStats {
+            market,
+            sequence: self.sequence.load(Ordering::Relaxed),
+            updated: (updated > 0).then_some(updated),
+            messages: self.messages.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Admin api access to the channel handler. Handler restarted by the supervisor
+/// attaches itself again, requests fail while no handler is running.
+#[derive(Clone)]
+pub struct AdminHook {
+    channel: String,
+    commands: Arc<RwLock<Option<Sender<Command>>>>,
+}
+
+impl AdminHook {
+    pub fn new(channel: &str) -> Self {
+        AdminHook {
+            channel: channel.to_string(),
+            commands: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub fn attach(&self, commands: Sender<Command>) {
+        if let Ok(mut attached) = self.commands.write() {
+            *attached = Some(commands);
+        }
+    }
+
+    async fn send(&self, command: Command) -> Result<(), HttpError> {
+        let commands: Option<Sender<Command>> = self
+            .commands
+            .read()
+            .ok()
+            .and_then(|commands| commands.clone());
+
+        match commands {
+            Some(commands) => commands.send(command).await.map_err(|_| not_running()),
+            None => Err(not_running()),
+        }
+    }
+}
+
+#[async_trait]
+impl SubscriptionsHook for AdminHook {
+    fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    async fn subscriptions(&self) -> Vec<SubscriptionInfo> {
+        let (sender, receiver) = oneshot::channel::<Vec<MarketStats>>();
+
+        if self.send(Command::List(sender)).await.is_err() {
+            return vec![];
+        }
+
+        receiver
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|stats| SubscriptionInfo {
+                channel: self.channel.clone(),
+                market: stats.market,
+                sequence: stats.sequence,
+                updated: stats.updated,
+                messages: stats.messages,
+            })
+            .collect()
+    }
+
+    async fn execute(&self, action: Action, market: &str) -> Result<(), HttpError> {
+        let (sender, receiver) = oneshot::channel::<Result<(), HttpError>>();
+
+        self.send(Command::Execute(action, market.to_string(), sender))
+            .await?;
+
+        receiver.await.unwrap_or_else(|_| Err(not_running()))
+    }
+
+    async fn snapshot(&self, market: &str) -> Result<Value, HttpError> {
+        let (sender, receiver) = oneshot::channel::<Result<Value, HttpError>>();
+
+        self.send(Command::Snapshot(market.to_string(), sender))
+            .await?;
+
+        receiver.await.unwrap_or_else(|_| Err(not_running()))
+    }
+}
+
+fn not_running() -> HttpError {
+    HttpError {
+        message: "Channel handler is not running".to_string(),
+        code: ErrorCode::Unavailable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stream::admin::{AdminHook, Command, Stats};
+    use http::models::errors::ErrorCode;
+    use http::subscriptions::hook::{Action, SubscriptionsHook};
+    use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+    #[test]
+    fn stats_should_count_updates() {
+        let stats: Stats = Stats::default();
+
+        assert_eq!(stats.get("btc_usd".to_string()).updated, None);
+
+        stats.updated(0);
+        stats.updated(1);
+
+        let result = stats.get("btc_usd".to_string());
+        assert_eq!(result.sequence, 1);
+        assert_eq!(result.messages, 2);
+        assert!(result.updated.is_some());
+    }
+
+    #[tokio::test]
+    async fn execute_should_fail_without_running_handler() {
+        let hook: AdminHook = AdminHook::new("ticker");
+
+        let result = hook.execute(Action::Resync, "btc_usd").await;
+
+        assert!(matches!(
+            result.err().map(|error| error.code),
+            Some(ErrorCode::Unavailable)
+        ));
+    }
+
+    #[tokio::test]
+    async fn execute_should_forward_command_to_handler() {
+        let hook: AdminHook = AdminHook::new("ticker");
+        let (sender, mut receiver): (Sender<Command>, Receiver<Command>) = channel(1);
+        hook.attach(sender);
+
+        tokio::spawn(async move {
+            if let Some(Command::Execute(Action::Subscribe, market, reply)) = receiver.recv().await
+            {
+                assert_eq!(market, "btc_usd");
+                reply.send(Ok(())).unwrap_or_default();
+            }
+        });
+
+        assert!(hook.execute(Action::Subscribe, "btc_usd").await.is_ok());
+    }
+}
@@ -0,0 +1,688 @@
+use crate::leader::Leader;
+use crate::shard::Shard;
+use crate::stream::adapter::ExchangeAdapter;
+use crate::stream::admin::{AdminHook, Command, MarketStats, SnapshotReply, Stats};
+use crate::stream::state::{SequenceGap, State};
+use crate::whitelist::MarketsValidator;
+use anyhow::{anyhow, Result};
+use async_nats::Subject;
+use chrono::Utc;
+use http::metrics::market as metrics;
+use http::models::errors::{ErrorCode, HttpError};
+use http::subscriptions::hook::Action;
+use prost::Message;
+use protocol::client::NatsClient;
+use protocol::latency::Stamped;
+use protocol::model::Symbol;
+use protocol::public::error::ErrorCode::MarketNotFound;
+use protocol::public::error::ErrorMessage;
+use protocol::public::types::Latency;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::mpsc::error::SendError;
+use tokio::sync::mpsc::{self, channel, Receiver, Sender};
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Instant, Interval};
+use tracing::{info, warn};
+
+const DEFAULT_EXPIRY_CHECK: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub enum Event<K, T> {
+    Get(K),
+    Lease(K),
+    /// Exchange update with connector receive time in micros
+    Updated(K, T, i64),
+}
+
+impl<K: Clone, T> Event<K, T> {
+    pub fn market(&self) -> K {
+        match self {
+            Self::Get(market) => market.clone(),
+            Self::Lease(market) => market.clone(),
+            Self::Updated(market, _, _) => market.clone(),
+        }
+    }
+
+    /// Receive time of exchange update, None for nats requests
+    pub fn received(&self) -> Option<i64> {
+        match self {
+            Self::Updated(_, _, received) => Some(*received),
+            _ => None,
+        }
+    }
+}
+
+type Events<A, T> = Sender<Event<<A as ExchangeAdapter>::Market, T>>;
+type EventsReceiver<A, T> = Receiver<Event<<A as ExchangeAdapter>::Market, T>>;
+
+/// Running market task
+struct Task<A: ExchangeAdapter, T> {
+    events: Events<A, T>,
+    stats: Arc<Stats>,
+    snapshots: Sender<SnapshotReply>,
+    handle: JoinHandle<()>,
+}
+
+/// Connector replica publishing market messages
+struct Origin {
+    exchange: String,
+    instance: String,
+}
+
+/// Market task side of the admin api
+struct TaskAdmin {
+    stats: Arc<Stats>,
+    snapshots: Receiver<SnapshotReply>,
+}
+
+pub struct Handler<A: ExchangeAdapter, T> {
+    nats_client: Arc<NatsClient>,
+    adapter: Arc<A>,
+    state: HashMap<A::Market, Task<A, T>>,
+    receiver: EventsReceiver<A, T>,
+    admin: Sender<Command>,
+    commands: Receiver<Command>,
+    buffer_size: usize,
+    preload: Vec<A::Market>,
+    leases: HashMap<A::Market, Instant>,
+    lease_ttl: Option<Duration>,
+    shard: Shard,
+    leader: Leader,
+    validator: Option<Arc<MarketsValidator>>,
+}
+
+impl<A: ExchangeAdapter, T: Send + 'static> Handler<A, T> {
+    pub fn new(
+        nats_client: Arc<NatsClient>,
+        adapter: Arc<A>,
+        buffer_size: usize,
+    ) -> (Self, Events<A, T>) {
+        let (sender, receiver): (Events<A, T>, EventsReceiver<A, T>) =
+            channel::<Event<A::Market, T>>(buffer_size);
+        let (admin, commands): (Sender<Command>, Receiver<Command>) = channel(buffer_size);
+
+        let handler: Handler<A, T> = Handler {
+            nats_client,
+            adapter,
+            buffer_size,
+            state: HashMap::new(),
+            receiver,
+            admin,
+            commands,
+            preload: vec![],
+            leases: HashMap::new(),
+            lease_ttl: None,
+            shard: Shard::default(),
+            leader: Leader::default(),
+            validator: None,
+        };
+
+        (handler, sender)
+    }
+
+    /// Markets subscribed as soon as websocket is connected,
+    /// before any snapshot request arrives
+    pub fn preload(mut self, markets: Vec<A::Market>) -> Self {
+        self.preload = markets;
+        self
+    }
+
+    /// Markets without snapshot request or lease renewal within ttl are unsubscribed.
+    /// Preloaded markets never expire.
+    pub fn expire_after(mut self, lease_ttl: Duration) -> Self {
+        self.lease_ttl = Some(lease_ttl);
+        self
+    }
+
+    /// Only markets owned by the replica are subscribed, moved markets are released
+    pub fn sharded(mut self, shard: Shard) -> Self {
+        self.shard = shard;
+        self
+    }
+
+    /// Markets are kept subscribed on standby replica, only the leader publishes
+    pub fn standby(mut self, leader: Leader) -> Self {
+        self.leader = leader;
+        self
+    }
+
+    /// Markets subscribed through the admin api are checked like snapshot requests
+    pub fn validated(mut self, validator: Arc<MarketsValidator>) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Subscriptions listed and managed through the admin api
+    pub fn admin(self, hook: AdminHook) -> Self {
+        hook.attach(self.admin.clone());
+        self
+    }
+
+    /// Markets served by a reset websocket connection are subscribed again.
+    pub async fn run<M: Message + Serialize + Stamped, S: State<A, T, M> + 'static>(
+        mut self,
+        mut reset: broadcast::Receiver<usize>,
+        mut connected: watch::Receiver<bool>,
+    ) -> Result<()> {
+        let period: Duration = self.lease_ttl.unwrap_or(DEFAULT_EXPIRY_CHECK);
+        let mut expiry: Interval = interval(period);
+        let exchange: String = self.adapter.exchange().as_str_name().to_lowercase();
+        let channel: String = S::default().channel().to_string();
+
+        if *connected.borrow_and_update() {
+            self.start_preloaded::<M, S>()?
+        }
+
+        loop {
+            select! {
+                Some(event) = self.receiver.recv() => {
+                    self.process::<M, S>(event).await?
+                },
+                Some(command) = self.commands.recv() => {
+                    self.command::<M, S>(command).await?
+                },
+                Ok(connection) = reset.recv() => {
+                    self.reset::<M, S>(connection).await?
+                },
+                _ = expiry.tick(), if self.lease_ttl.is_some() => {
+                    self.expire()
+                },
+                true = self.shard.changed() => {
+                    self.rebalance::<M, S>()?
+                },
+                Ok(_) = connected.changed() => {
+                    if *connected.borrow_and_update() {
+                        self.start_preloaded::<M, S>()?
+                    }
+                }
+            }
+
+            metrics::subscriptions(&exchange, &channel, self.state.len());
+        }
+    }
+
+    fn start_preloaded<M: Message + Serialize + Stamped, S: State<A, T, M> + 'static>(
+        &mut self,
+    ) -> Result<()> {
+        for market in self.preload.clone() {
+            if !self.state.contains_key(&market) && self.shard.is_owner(&market) {
+                if let Err(error) = self.start::<M, S>(market) {
+                    warn!("Cannot preload market: {}", error);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn process<M: Message + Serialize + Stamped, S: State<A, T, M> + 'static>(
+        &mut self,
+        event: Event<A::Market, T>,
+    ) -> Result<()> {
+        let market: A::Market = event.market();
+
+        if !matches!(event, Event::Updated(..)) {
+            self.leases.insert(market.clone(), Instant::now());
+        }
+
+        match (self.state.get(&market), event) {
+            (Some(_), Event::Lease(_)) => Ok(()),
+            (Some(task), event) => task
+                .events
+                .send(event)
+                .await
+                .map_err(|_| anyhow!("Cannot send event")),
+            (None, Event::Updated(..)) => Ok(()),
+            (None, _) => match self.start::<M, S>(market.clone()) {
+                Ok(()) => Ok(()),
+                Err(error) => self.reject::<M, S>(&market, error).await,
+            },
+        }
+    }
+
+    /// Answers the request on the market error subject, next request subscribes again
+    async fn reject<M: Message + Serialize + Stamped, S: State<A, T, M> + 'static>(
+        &self,
+        market: &A::Market,
+        error: anyhow::Error,
+    ) -> Result<()> {
+        warn!("Snapshot request failed: {}", error);
+
+        if self.leader.is_leader() {
+            let subject: Subject = Subject::from(format!("{}.error", S::default().topic(market)));
+            self.nats_client
+                .send_error(subject, exchange_error(error))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn command<M: Message + Serialize + Stamped, S: State<A, T, M> + 'static>(
+        &mut self,
+        command: Command,
+    ) -> Result<()> {
+        match command {
+            Command::List(reply) => {
+                let stats: Vec<MarketStats> = self
+                    .state
+                    .iter()
+                    .map(|(market, task)| task.stats.get(market.nats_format()))
+                    .collect();
+                reply.send(stats).unwrap_or_default();
+            }
+            Command::Execute(action, market, reply) => {
+                let result: Result<(), HttpError> = match self.parse(&market) {
+                    Ok(market) => self.execute::<M, S>(action, market).await,
+                    Err(error) => Err(error),
+                };
+                reply.send(result).unwrap_or_default();
+            }
+            Command::Snapshot(market, reply) => {
+                let task: Option<&Task<A, T>> = match self.parse(&market) {
+                    Ok(market) => self.state.get(&market),
+                    Err(_) => None,
+                };
+
+                if let Some(task) = task {
+                    if let Err(SendError(reply)) = task.snapshots.send(reply).await {
+                        reply.send(Err(not_found(&market))).unwrap_or_default();
+                    }
+                } else {
+                    reply.send(Err(not_found(&market))).unwrap_or_default();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Markets subscribed through the admin api are kept like preloaded ones
+    async fn execute<M: Message + Serialize + Stamped, S: State<A, T, M> + 'static>(
+        &mut self,
+        action: Action,
+        market: A::Market,
+    ) -> Result<(), HttpError> {
+        info!("Admin {:?} of market {}", action, market.nats_format());
+
+        match action {
+            Action::Subscribe if !self.shard.is_owner(&market) => Err(admin_error(
+                ErrorCode::BadRequest,
+                format!("Market {} owned by other replica", market.nats_format()),
+            )),
+            Action::Subscribe => {
+                self.validate(&market)?;
+
+                if !self.preload.contains(&market) {
+                    self.preload.push(market.clone());
+                }
+
+                if self.state.contains_key(&market) {
+                    Ok(())
+                } else {
+                    self.start::<M, S>(market).map_err(internal)
+                }
+            }
+            Action::Unsubscribe => {
+                self.preload.retain(|preloaded| preloaded != &market);
+                self.leases.remove(&market);
+                self.state
+                    .remove(&market)
+                    .map(|_| ())
+                    .ok_or_else(|| not_subscribed(&market))
+            }
+            Action::Resync if !self.state.contains_key(&market) => Err(not_subscribed(&market)),
+            Action::Resync => self.restart::<M, S>(market).await.map_err(internal),
+        }
+    }
+
+    /// Restarts markets served by the websocket connection, other connections are untouched
+    async fn reset<M: Message + Serialize + Stamped, S: State<A, T, M> + 'static>(
+        &mut self,
+        connection: usize,
+    ) -> Result<()> {
+        let channel: A::Channel = S::default().channel();
+        let markets: Vec<A::Market> = self
+            .state
+            .keys()
+            .filter(|market| self.adapter.connection(market, &channel) == Some(connection))
+            .cloned()
+            .collect();
+
+        warn!(
+            "Resubscribing {} {} markets of websocket {}",
+            markets.len(),
+            channel,
+            connection
+        );
+
+        for market in markets {
+            if let Err(error) = self.restart::<M, S>(market).await {
+                warn!("Cannot resubscribe market: {}", error);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Subscribes the market again with a fresh state
+    async fn restart<M: Message + Serialize + Stamped, S: State<A, T, M> + 'static>(
+        &mut self,
+        market: A::Market,
+    ) -> Result<()> {
+        if let Some(task) = self.state.remove(&market) {
+            // unsubscribe is sent when the task ends, before subscribing again
+            drop(task.events);
+            task.handle.await.unwrap_or_default();
+        }
+
+        self.start::<M, S>(market)
+    }
+
+    /// Denied markets are bad requests, markets not listed by the exchange are not found
+    fn validate(&self, market: &A::Market) -> Result<(), HttpError> {
+        let validated: Result<(), ErrorMessage> = self
+            .validator
+            .as_ref()
+            .map_or(Ok(()), |validator| validator.validate(market));
+
+        validated.map_err(|error| {
+            let code: ErrorCode = if error.code == MarketNotFound as i32 {
+                ErrorCode::NotFound
+            } else {
+                ErrorCode::BadRequest
+            };
+            admin_error(code, error.message)
+        })
+    }
+
+    /// Market in nats format e.g. btc_usd
+    fn parse(&self, market: &str) -> Result<A::Market, HttpError> {
+        market
+            .split_once('_')
+            .map(|(from, to)| self.adapter.market(from.to_string(), to.to_string()))
+            .ok_or_else(|| admin_error(ErrorCode::BadRequest, format!("Invalid market {}", market)))
+    }
+
+    fn rebalance<M: Message + Serialize + Stamped, S: State<A, T, M> + 'static>(
+        &mut self,
+    ) -> Result<()> {
+        let moved: Vec<A::Market> = self
+            .state
+            .keys()
+            .filter(|market| !self.shard.is_owner(*market))
+            .cloned()
+            .collect();
+
+        for market in moved {
+            info!(
+                "Releasing market {} owned by other replica",
+                market.nats_format()
+            );
+            self.leases.remove(&market);
+            self.state.remove(&market);
+        }
+
+        self.start_preloaded::<M, S>()
+    }
+
+    fn expire(&mut self) {
+        if let Some(ttl) = self.lease_ttl {
+            for market in expired(&self.leases, &self.preload, ttl, Instant::now()) {
+                info!("Unsubscribing idle market {}", market.nats_format());
+                self.leases.remove(&market);
+                self.state.remove(&market);
+            }
+        }
+    }
+
+    fn start<M: Message + Serialize + Stamped, S: State<A, T, M> + 'static>(
+        &mut self,
+        market: A::Market,
+    ) -> Result<()> {
+        let nats_client: Arc<NatsClient> = self.nats_client.clone();
+        let adapter: Arc<A> = self.adapter.clone();
+        let leader: Leader = self.leader.clone();
+        let (sender, receiver): (Events<A, T>, EventsReceiver<A, T>) =
+            channel::<Event<A::Market, T>>(self.buffer_size);
+
+        let state: S = S::default();
+        let channel: A::Channel = state.channel();
+        let subscribe: A::Request = adapter.subscribe(&market, &channel);
+        let unsubscribe: A::Request = adapter.unsubscribe(&market, &channel);
+
+        if let Err(error) = adapter.send(subscribe) {
+            return Err(anyhow!(
+                "Cannot subscribe {} for {}: {}",
+                channel,
+                market.nats_format(),
+                error
+            ));
+        }
+
+        let stats: Arc<Stats> = Arc::new(Stats::default());
+        let (snapshots, snapshot_requests): (Sender<SnapshotReply>, Receiver<SnapshotReply>) =
+            mpsc::channel(1);
+        let task_admin: TaskAdmin = TaskAdmin {
+            stats: stats.clone(),
+            snapshots: snapshot_requests,
+        };
+        let task_market: A::Market = market.clone();
+        let instance: String = self.shard.replica().to_string();
+
+        let handle: JoinHandle<()> = tokio::spawn(async move {
+            let state: S = S::default();
+            let origin: Origin = Origin {
+                exchange: adapter.exchange().as_str_name().to_lowercase(),
+                instance,
+            };
+            let market: &A::Market = &task_market;
+            run_handler::<A, T, M, S>(
+                nats_client,
+                state,
+                receiver,
+                leader,
+                task_admin,
+                &origin,
+                market,
+            )
+            .await;
+            adapter.send(unsubscribe).unwrap_or_default();
+        });
+
+        let task: Task<A, T> = Task {
+            events: sender,
+            stats,
+            snapshots,
+            handle,
+        };
+        self.state.insert(market, task);
+
+        Ok(())
+    }
+}
+
+/// Markets with all leases older than ttl
+fn expired<K: Clone + Eq + Hash>(
+    leases: &HashMap<K, Instant>,
+    preload: &[K],
+    ttl: Duration,
+    now: Instant,
+) -> Vec<K> {
+    leases
+        .iter()
+        .filter(|(market, _)| !preload.contains(market))
+        .filter(|(_, renewed)| now.duration_since(**renewed) > ttl)
+        .map(|(market, _)| market.clone())
+        .collect()
+}
+
+async fn run_handler<A: ExchangeAdapter, T, M: Message + Serialize + Stamped, S: State<A, T, M>>(
+    nats_client: Arc<NatsClient>,
+    mut state: S,
+    mut handler: EventsReceiver<A, T>,
+    mut leader: Leader,
+    mut admin: TaskAdmin,
+    origin: &Origin,
+    market: &A::Market,
+) {
+    let exchange: &str = &origin.exchange;
+    let channel: String = state.channel().to_string();
+    let symbol: String = market.nats_format();
+
+    info!("Running new {} task for {}", channel, symbol);
+
+    loop {
+        let mut updated: bool = false;
+        let mut received: Option<i64> = None;
+
+        let message: Result<Option<M>> = select! {
+            event = handler.recv() => match event {
+                Some(event) => {
+                    updated = matches!(event, Event::Updated(..));
+                    received = event.received().or_else(|| Some(Utc::now().timestamp_micros()));
+                    state.publish(event).map(Some)
+                },
+                None => break,
+            },
+            true = leader.changed() => {
+                Ok(leader.is_leader().then(|| state.restart()).flatten())
+            },
+            Some(reply) = admin.snapshots.recv() => {
+                reply.send(snapshot(state.get())).unwrap_or_default();
+                Ok(None)
+            }
+        };
+
+        if updated && message.is_ok() {
+            admin.stats.updated(state.sequence());
+        }
+
+        let result: Result<bool> = match message {
+            Ok(Some(mut message)) if leader.is_leader() => {
+                let topic: Subject = state.topic(market);
+                message.stamp(latency(origin, received));
+                message.stamp_epoch(leader.epoch());
+                nats_client
+                    .send_message(topic, message)
+                    .await
+                    .map(|_| true)
+                    .map_err(|error| anyhow!(error))
+            }
+            Ok(_) => Ok(false),
+            Err(error) if error.is::<SequenceGap>() => {
+                metrics::sequence_gap(exchange, &channel, &symbol);
+                Err(error)
+            }
+            Err(error) => {
+                metrics::update_error(exchange, &channel, &symbol);
+                Err(error)
+            }
+        };
+
+        match result {
+            Ok(true) => {
+                metrics::published(exchange, &channel, &symbol);
+
+                if let Some(timestamp) = state.timestamp().filter(|_| updated) {
+                    metrics::latency(exchange, &channel, since(timestamp));
+                }
+            }
+            Ok(false) => {}
+            Err(error) => {
+                warn!("Closing task {} for {}: {}", channel, symbol, error);
+                break;
+            }
+        }
+    }
+}
+
+/// Publish time stamped just before sending, received defaults to publish time
+/// for snapshots of the new leader
+fn latency(origin: &Origin, received: Option<i64>) -> Latency {
+    let published: i64 = Utc::now().timestamp_micros();
+
+    Latency {
+        received_micros: received.unwrap_or(published),
+        published_micros: published,
+        instance: origin.instance.clone(),
+    }
+}
+
+fn snapshot<M: Serialize>(message: M) -> Result<Value, HttpError> {
+    serde_json::to_value(message)
+        .map_err(|error| admin_error(ErrorCode::Internal, error.to_string()))
+}
+
+fn not_found(market: &str) -> HttpError {
+    admin_error(
+        ErrorCode::NotFound,
+        format!("Market {} is not subscribed", market),
+    )
+}
+
+fn not_subscribed<K: Symbol>(market: &K) -> HttpError {
+    not_found(&market.nats_format())
+}
+
+fn internal(error: anyhow::Error) -> HttpError {
+    admin_error(ErrorCode::Internal, error.to_string())
+}
+
+fn exchange_error(error: anyhow::Error) -> ErrorMessage {
+    ErrorMessage {
+        code: protocol::public::error::ErrorCode::ExchangeError as i32,
+        message: error.to_string(),
+        timestamp: Utc::now().timestamp_millis(),
+        exchange_message: None,
+    }
+}
+
+fn admin_error(code: ErrorCode, message: String) -> HttpError {
+    HttpError { message, code }
+}
+
+/// Time elapsed from exchange timestamp in millis, zero for clock skew
+fn since(timestamp: i64) -> Duration {
+    let elapsed: i64 = Utc::now().timestamp_millis() - timestamp;
+    Duration::from_millis(elapsed.max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stream::handler::expired;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    #[test]
+    fn expired_should_return_markets_without_recent_lease() {
+        let now: Instant = Instant::now();
+        let ttl: Duration = Duration::from_secs(30);
+
+        let leases: HashMap<&str, Instant> = HashMap::from([
+            ("btc_usd", now - Duration::from_secs(10)),
+            ("eth_usd", now - Duration::from_secs(40)),
+        ]);
+
+        assert_eq!(expired(&leases, &[], ttl, now), vec!["eth_usd"]);
+    }
+
+    #[test]
+    fn expired_should_skip_preloaded_markets() {
+        let now: Instant = Instant::now();
+        let ttl: Duration = Duration::from_secs(30);
+
+        let leases: HashMap<&str, Instant> =
+            HashMap::from([("btc_usd", now - Duration::from_secs(40))]);
+
+        assert!(expired(&leases, &["btc_usd"], ttl, now).is_empty());
+    }
+}
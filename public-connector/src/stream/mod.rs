@@ -0,0 +1,5 @@
+pub mod adapter;
+pub mod admin;
+pub mod handler;
+pub mod state;
+pub mod subscription;
@@ -0,0 +1,58 @@
+use crate::stream::adapter::ExchangeAdapter;
+use crate::stream::handler::Event;
+use anyhow::Result;
+use async_nats::Subject;
+use prost::Message;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Exchange update missed, the market has to be subscribed again
+#[derive(Debug)]
+pub struct SequenceGap(pub &'static str);
+
+impl Display for SequenceGap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} sequence id missed", self.0)
+    }
+}
+
+impl Error for SequenceGap {}
+
+/// Maps exchange dto into proto messages and keeps snapshot for a single market
+pub trait State<A: ExchangeAdapter, E, M: Message>: Default + Send {
+    fn publish(&mut self, event: Event<A::Market, E>) -> Result<M> {
+        match event {
+            Event::Get(_) | Event::Lease(_) => Ok(self.get()),
+            Event::Updated(_, dto, _) => self.update(dto),
+        }
+    }
+
+    fn update(&mut self, dto: E) -> Result<M>;
+
+    fn get(&self) -> M;
+
+    /// Sequence of the last published message, -1 before the first update
+    fn sequence(&self) -> i64;
+
+    fn set_sequence(&mut self, sequence: i64);
+
+    /// Snapshot starting a new sequence, published by the replica taking over leadership
+    /// with its new epoch. None before the first update.
+    fn restart(&mut self) -> Option<M> {
+        if self.sequence() < 0 {
+            None
+        } else {
+            self.set_sequence(0);
+            Some(self.get())
+        }
+    }
+
+    /// Exchange time in millis of the last update, None when not provided by the exchange
+    fn timestamp(&self) -> Option<i64> {
+        None
+    }
+
+    fn topic(&self, market: &A::Market) -> Subject;
+
+    fn channel(&self) -> A::Channel;
+}
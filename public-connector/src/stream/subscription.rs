@@ -0,0 +1,344 @@
+use crate::decoder::NatsEvent;
+use crate::leader::Leader;
+use crate::shard::Shard;
+use crate::stream::adapter::ExchangeAdapter;
+use crate::stream::handler::Event;
+use crate::stream::handler::Event::{Get, Lease, Updated};
+use crate::subscription::NatsSubscription;
+use crate::whitelist::MarketsValidator;
+use anyhow::Result;
+use async_nats::subject::ToSubject;
+use async_nats::Subject;
+use chrono::Utc;
+use http::metrics::market as metrics;
+use prost::Message;
+use protocol::client::NatsClient;
+use protocol::model::Symbol;
+use protocol::public::types::LeaseRequest;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::Sender;
+use tracing::{debug, warn, Instrument, Span};
+
+const SNAPSHOT_SUFFIX: &str = ".snapshot";
+const ERROR_SUFFIX: &str = ".error";
+const NATS_SOURCE: &str = "nats";
+
+/// Routes snapshot requests and leases to the handler of the replica owning the market.
+/// Requests for markets owned by other replicas are forwarded to {subject}.{replica}.
+pub struct Router<A: ExchangeAdapter> {
+    adapter: Arc<A>,
+    validator: Arc<MarketsValidator>,
+    nats_client: Arc<NatsClient>,
+    shard: Shard,
+    standby: Option<Leader>,
+}
+
+impl<A: ExchangeAdapter> Router<A> {
+    pub fn new(
+        adapter: Arc<A>,
+        validator: Arc<MarketsValidator>,
+        nats_client: Arc<NatsClient>,
+        shard: Shard,
+    ) -> Self {
+        Router {
+            adapter,
+            validator,
+            nats_client,
+            shard,
+            standby: None,
+        }
+    }
+
+    /// Every replica receives all requests to keep markets warm for failover
+    pub fn standby(mut self, leader: Leader) -> Self {
+        self.standby = Some(leader);
+        self
+    }
+
+    /// Queue subscription merged with requests forwarded to this replica
+    pub async fn subscribe<R: Message + Default, T: ToSubject>(
+        &self,
+        topic: T,
+        queue: &str,
+    ) -> Result<NatsSubscription<R>> {
+        let subject: Subject = topic.to_subject();
+        let replica: &str = self.shard.replica();
+        let forwarded: Subject = Subject::from(format!("{}.{}", subject, replica));
+
+        let queue: String = match self.standby {
+            Some(_) => format!("{}.{}", queue, replica),
+            None => queue.to_string(),
+        };
+
+        let shared: NatsSubscription<R> =
+            NatsSubscription::new(&self.nats_client, subject, &queue).await?;
+        let own: NatsSubscription<R> =
+            NatsSubscription::new(&self.nats_client, forwarded, replica).await?;
+
+        Ok(shared.merge(own))
+    }
+
+    /// Forwards snapshot requests from nats to the handler.
+    /// Rejected markets are answered on the request error subject.
+    pub async fn snapshots<E: Send + Sync + 'static, R: Message + Default>(
+        &self,
+        events: Sender<Event<A::Market, E>>,
+        mut subscription: NatsSubscription<R>,
+    ) -> Result<()> {
+        while let Some(result) = subscription.next().await {
+            let event: NatsEvent<R> = match result {
+                Ok(event) => event,
+                Err(error) => {
+                    warn!("Cannot process nats message: {}", error);
+                    metrics::decode_error(&self.exchange(), NATS_SOURCE);
+                    continue;
+                }
+            };
+
+            let market: A::Market = match market(self.adapter.as_ref(), &event) {
+                Ok(market) => market,
+                Err(error) => {
+                    warn!("Cannot process nats message: {}", error);
+                    continue;
+                }
+            };
+
+            if let Some(owner) = self.owner(&event, &market) {
+                self.forward(event, owner).await?;
+            } else if let Err(error) = self.validator.validate(&market) {
+                warn!("Snapshot request rejected: {}", error.message);
+
+                if self.is_leader() {
+                    let subject: Subject = error_subject(&event, self.shard.replica());
+                    self.nats_client.send_error(subject, error).await?;
+                }
+            } else {
+                events.send(Get(market)).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forwards consumers interest renewals to the handler. Rejected markets are ignored.
+    pub async fn leases<E: Send + Sync + 'static>(
+        &self,
+        events: Sender<Event<A::Market, E>>,
+        mut subscription: NatsSubscription<LeaseRequest>,
+    ) -> Result<()> {
+        while let Some(result) = subscription.next().await {
+            let event: NatsEvent<LeaseRequest> = match result {
+                Ok(event) => event,
+                Err(error) => {
+                    warn!("Cannot process nats lease: {}", error);
+                    metrics::decode_error(&self.exchange(), NATS_SOURCE);
+                    continue;
+                }
+            };
+
+            match market(self.adapter.as_ref(), &event) {
+                Ok(market) => match self.owner(&event, &market) {
+                    Some(owner) => self.forward(event, owner).await?,
+                    None if self.validator.validate(&market).is_ok() => {
+                        events.send(Lease(market)).await?
+                    }
+                    None => debug!("Lease ignored for {}", market.nats_format()),
+                },
+                Err(error) => warn!("Cannot process nats lease: {}", error),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forwards exchange updates to the handler. Updates mapped to None are skipped,
+    /// updates missed by the lagging receiver are counted and dropped.
+    pub async fn updates<E, U, F>(
+        &self,
+        channel: A::Channel,
+        events: Sender<Event<A::Market, E>>,
+        mut subscription: broadcast::Receiver<U>,
+        update: F,
+    ) -> Result<()>
+    where
+        E: Send + Sync + 'static,
+        U: Clone,
+        F: Fn(U) -> Option<(A::Market, E)>,
+    {
+        loop {
+            match subscription.recv().await {
+                Ok(result) => {
+                    let received: i64 = Utc::now().timestamp_micros();
+
+                    if let Some((market, dto)) = update(result) {
+                        events.send(Updated(market, dto, received)).await?
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Skipped {} {} updates", skipped, channel);
+                    metrics::lagged(&self.exchange(), &channel.to_string(), skipped);
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+
+    fn exchange(&self) -> String {
+        self.adapter.exchange().as_str_name().to_lowercase()
+    }
+
+    fn is_leader(&self) -> bool {
+        self.standby.as_ref().is_none_or(Leader::is_leader)
+    }
+
+    /// Other replica owning the market. Forwarded requests are always processed locally.
+    fn owner<R>(&self, event: &NatsEvent<R>, market: &A::Market) -> Option<String> {
+        if event.subject.ends_with(&self.forwarded_suffix()) {
+            None
+        } else {
+            self.shard
+                .owner(market)
+                .filter(|owner| owner != self.shard.replica())
+        }
+    }
+
+    async fn forward<R: Message>(&self, event: NatsEvent<R>, owner: String) -> Result<()> {
+        let subject: Subject = Subject::from(format!("{}.{}", event.subject, owner));
+        let span: Span = event.span();
+
+        debug!("Forwarding {} to replica {}", event.subject, owner);
+
+        Ok(self
+            .nats_client
+            .send_message_with_reply(subject, event.reply, event.message)
+            .instrument(span)
+            .await?)
+    }
+
+    fn forwarded_suffix(&self) -> String {
+        format!(".{}", self.shard.replica())
+    }
+}
+
+/// Reply subject if provided, otherwise {exchange}.{endpoint}.{from}.{to}.error
+fn error_subject<R>(event: &NatsEvent<R>, replica: &str) -> Subject {
+    let forwarded: String = format!(".{}", replica);
+
+    event.reply.clone().unwrap_or_else(|| {
+        let topic: &str = event
+            .subject
+            .trim_end_matches(forwarded.as_str())
+            .trim_end_matches(SNAPSHOT_SUFFIX);
+        Subject::from(format!("{}{}", topic, ERROR_SUFFIX))
+    })
+}
+
+fn market<A: ExchangeAdapter, R>(adapter: &A, event: &NatsEvent<R>) -> Result<A::Market> {
+    event.symbols().map(|(from, to)| adapter.market(from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decoder::NatsEvent;
+    use crate::stream::adapter::ExchangeAdapter;
+    use crate::stream::subscription::{error_subject, market};
+    use async_nats::Subject;
+    use protocol::model::{Currency, Symbol};
+    use protocol::public::ticker::TickerRequest;
+    use protocol::public::types::Exchange;
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    struct TestMarket(String, String);
+
+    impl Symbol for TestMarket {
+        fn from(&self) -> Currency {
+            Currency::new(self.0.clone())
+        }
+
+        fn to(&self) -> Currency {
+            Currency::new(self.1.clone())
+        }
+
+        fn exchange_format(&self) -> String {
+            format!("{}{}", self.0, self.1).to_uppercase()
+        }
+    }
+
+    struct TestAdapter;
+
+    impl ExchangeAdapter for TestAdapter {
+        type Market = TestMarket;
+        type Channel = String;
+        type Request = String;
+
+        fn exchange(&self) -> Exchange {
+            Exchange::Kraken
+        }
+
+        fn market(&self, from: String, to: String) -> TestMarket {
+            TestMarket(from, to)
+        }
+
+        fn subscribe(&self, market: &TestMarket, channel: &String) -> String {
+            format!("subscribe {}.{}", channel, market.exchange_format())
+        }
+
+        fn unsubscribe(&self, market: &TestMarket, channel: &String) -> String {
+            format!("unsubscribe {}.{}", channel, market.exchange_format())
+        }
+
+        fn send(&self, _: String) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn connection(&self, _: &TestMarket, _: &String) -> Option<usize> {
+            None
+        }
+    }
+
+    #[test]
+    fn market_should_return_market_from_snapshot_subject() {
+        let event: NatsEvent<TickerRequest> = NatsEvent {
+            message: TickerRequest::default(),
+            subject: Subject::from("kraken.ticker.btc.usd.snapshot"),
+            reply: None,
+            headers: None,
+        };
+
+        let result: TestMarket = market(&TestAdapter, &event).expect("snapshot market");
+
+        assert_eq!(result, TestMarket("btc".to_string(), "usd".to_string()));
+    }
+
+    #[test]
+    fn error_subject_should_replace_snapshot_suffix() {
+        let event: NatsEvent<TickerRequest> = NatsEvent {
+            message: TickerRequest::default(),
+            subject: Subject::from("kraken.ticker.btc.usd.snapshot"),
+            reply: None,
+            headers: None,
+        };
+
+        assert_eq!(
+            error_subject(&event, "replica-1").as_str(),
+            "kraken.ticker.btc.usd.error"
+        );
+    }
+
+    #[test]
+    fn error_subject_should_skip_forwarded_replica() {
+        let event: NatsEvent<TickerRequest> = NatsEvent {
+            message: TickerRequest::default(),
+            subject: Subject::from("kraken.ticker.btc.usd.snapshot.replica-1"),
+            reply: None,
+            headers: None,
+        };
+
+        assert_eq!(
+            error_subject(&event, "replica-1").as_str(),
+            "kraken.ticker.btc.usd.error"
+        );
+    }
+}
@@ -1,14 +1,15 @@
 use crate::decoder::{decode, NatsEvent};
 use anyhow::Result;
 use async_nats::subject::ToSubject;
-use async_nats::Subscriber;
+use async_nats::{Message as NatsMessage, Subscriber};
+use futures::stream::{select, BoxStream};
 use futures::StreamExt;
 use prost::Message;
 use protocol::client::NatsClient;
 use tracing::info;
 
 pub struct NatsSubscription<R: Message + Default> {
-    subscriber: Subscriber,
+    subscriber: BoxStream<'static, NatsMessage>,
     marker: std::marker::PhantomData<R>,
 }
 
@@ -23,11 +24,19 @@ impl<R: Message + Default> NatsSubscription<R> {
         let subscriber: Subscriber = nats_client.queue_subscribe(topic, queue.into()).await?;
 
         Ok(NatsSubscription {
-            subscriber,
+            subscriber: subscriber.boxed(),
             marker: std::marker::PhantomData,
         })
     }
 
+    /// Receives messages from both subscriptions
+    pub fn merge(self, other: NatsSubscription<R>) -> NatsSubscription<R> {
+        NatsSubscription {
+            subscriber: select(self.subscriber, other.subscriber).boxed(),
+            marker: std::marker::PhantomData,
+        }
+    }
+
     pub async fn next(&mut self) -> Option<Result<NatsEvent<R>>> {
         self.subscriber.next().await.map(decode::<R>)
     }
@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use http::healthcheck::checks::{HealthCheck, HealthcheckResult};
+use http::metrics::task as metrics;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::select;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+const NAME: &str = "supervisor";
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const STABLE_PERIOD: Duration = Duration::from_secs(60);
+const MAX_RESTARTS: u32 = 5;
+
+/// Restarts failed tasks with exponential backoff instead of stopping the process.
+/// Supervised tasks are long running, task finishing without error is restarted as well.
+/// Task crashing MAX_RESTARTS times in a row fails the healthcheck
+/// until it keeps running for the stable period.
+#[derive(Clone, Default)]
+pub struct Supervisor {
+    failing: Arc<RwLock<HashSet<&'static str>>>,
+}
+
+impl Supervisor {
+    /// Runs task forever, never returns
+    pub async fn supervise<F, Fut>(&self, name: &'static str, mut task: F) -> Result<()>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let mut restarts: u32 = 0;
+
+        loop {
+            let running = task();
+            tokio::pin!(running);
+
+            let result: Result<()> = select! {
+                result = &mut running => result,
+                _ = sleep(STABLE_PERIOD) => {
+                    restarts = 0;
+                    self.recovered(name);
+                    running.await
+                }
+            };
+
+            let error = match result {
+                Ok(()) => anyhow!("task finished unexpectedly"),
+                Err(error) => error,
+            };
+
+            restarts += 1;
+            metrics::restarted(name);
+
+            if restarts >= MAX_RESTARTS {
+                self.failed(name);
+            }
+
+            let delay: Duration = backoff(restarts);
+            warn!(
+                "Task {} failed, restart {} in {:?}: {}",
+                name, restarts, delay, error
+            );
+
+            sleep(delay).await;
+        }
+    }
+
+    fn failed(&self, name: &'static str) {
+        if let Ok(mut failing) = self.failing.write() {
+            failing.insert(name);
+        }
+    }
+
+    fn recovered(&self, name: &'static str) {
+        if let Ok(mut failing) = self.failing.write() {
+            if failing.remove(name) {
+                info!("Task {} recovered", name);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for Supervisor {
+    async fn check(&self) -> HealthcheckResult {
+        let mut failing: Vec<String> = self
+            .failing
+            .read()
+            .map(|failing| failing.iter().map(|name| name.to_string()).collect())
+            .unwrap_or_default();
+        failing.sort();
+
+        HealthcheckResult {
+            service: String::from(NAME),
+            enabled: failing.is_empty(),
+            details: failing,
+        }
+    }
+}
+
+/// Exponential delay doubling with every restart, capped at MAX_BACKOFF
+fn backoff(restarts: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(restarts.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::supervisor::{backoff, Supervisor, MAX_RESTARTS};
+    use anyhow::anyhow;
+    use http::healthcheck::checks::HealthCheck;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[test]
+    fn backoff_should_double_up_to_max() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(3), Duration::from_secs(4));
+        assert_eq!(backoff(30), Duration::from_secs(60));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn supervise_should_restart_failed_task_and_fail_healthcheck() {
+        let supervisor: Supervisor = Supervisor::default();
+        let mut runs: u32 = 0;
+
+        let result = timeout(
+            Duration::from_secs(30),
+            supervisor.supervise("test", || {
+                runs += 1;
+                async { Err(anyhow!("task failed")) }
+            }),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(runs, MAX_RESTARTS);
+        assert!(!supervisor.check().await.enabled);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn supervise_should_restart_finished_task() {
+        let supervisor: Supervisor = Supervisor::default();
+        let mut runs: u32 = 0;
+
+        let result = timeout(
+            Duration::from_secs(2),
+            supervisor.supervise("test", || {
+                runs += 1;
+                async { Ok(()) }
+            }),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(runs, 2);
+    }
+}
@@ -1,9 +1,16 @@
+use crate::http_client::HttpClient;
+use async_trait::async_trait;
 use http::healthcheck::checks::{HealthCheck, HealthcheckResult};
 use http::healthcheck::service::HealthcheckService;
 use protocol::client::NatsClient;
+use reqwest::Url;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
 
 const NAME: &str = "nats";
+const EXCHANGE_NAME: &str = "exchange";
 
 struct NatsHealthCheck {
     nats_client: Arc<NatsClient>,
@@ -19,11 +26,65 @@ impl NatsHealthCheck {
     }
 }
 
+#[async_trait]
 impl HealthCheck for NatsHealthCheck {
-    fn check(&self) -> HealthcheckResult {
+    async fn check(&self) -> HealthcheckResult {
         HealthcheckResult {
             service: String::from(NAME),
             enabled: self.check(),
+            details: vec![],
+        }
+    }
+}
+
+/// Probes exchange rest api, fails on error status or no response within the timeout.
+/// Probe result is reused for the interval, so readiness checks do not use up rate limit.
+pub struct ExchangeHealthCheck {
+    http_client: Arc<HttpClient>,
+    url: Url,
+    timeout: Duration,
+    interval: Duration,
+    last_probe: Mutex<Option<(Instant, bool)>>,
+}
+
+impl ExchangeHealthCheck {
+    pub fn new(
+        http_client: Arc<HttpClient>,
+        url: Url,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Self {
+        ExchangeHealthCheck {
+            http_client,
+            url,
+            timeout,
+            interval,
+            last_probe: Mutex::new(None),
+        }
+    }
+
+    /// Concurrent checks wait for one in-flight probe
+    async fn probe(&self) -> bool {
+        let mut last_probe = self.last_probe.lock().await;
+
+        match *last_probe {
+            Some((probed, healthy)) if probed.elapsed() < self.interval => healthy,
+            _ => {
+                let healthy: bool = self.http_client.probe(&self.url, self.timeout).await;
+                *last_probe = Some((Instant::now(), healthy));
+                healthy
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for ExchangeHealthCheck {
+    async fn check(&self) -> HealthcheckResult {
+        HealthcheckResult {
+            service: String::from(EXCHANGE_NAME),
+            enabled: self.probe().await,
+            details: vec![],
         }
     }
 }
@@ -33,3 +94,46 @@ pub fn nats_healthcheck(nats_client: Arc<NatsClient>) -> HealthcheckService {
     healthcheck.add(Box::new(NatsHealthCheck::new(nats_client)));
     healthcheck
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::http_client::HttpClient;
+    use crate::utils::check::ExchangeHealthCheck;
+    use http::healthcheck::checks::HealthCheck;
+    use reqwest::Url;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+
+    #[tokio::test]
+    async fn check_should_reuse_probe_within_interval() {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url: Url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let probes: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+
+        let accepted: Arc<AtomicUsize> = probes.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                let mut request: [u8; 1024] = [0; 1024];
+                stream.read(&mut request).await.unwrap_or_default();
+                stream.write_all(RESPONSE).await.unwrap_or_default();
+            }
+        });
+
+        let check: ExchangeHealthCheck = ExchangeHealthCheck::new(
+            Arc::new(HttpClient::default()),
+            url,
+            Duration::from_secs(5),
+            Duration::from_secs(60),
+        );
+
+        assert!(check.check().await.enabled);
+        assert!(check.check().await.enabled);
+        assert_eq!(probes.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,27 +1,81 @@
 use anyhow::{Context, Result};
+use opentelemetry::global;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::runtime;
+use opentelemetry_sdk::trace::TracerProvider;
 use std::env;
 use std::str::FromStr;
 use tracing::subscriber::set_global_default;
-use tracing::Level;
+use tracing::{Level, Subscriber};
 use tracing_log::LogTracer;
-use tracing_subscriber::fmt::Subscriber;
-use tracing_subscriber::FmtSubscriber;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{fmt, Layer, Registry};
 
 const ENV_LOG_LEVEL: &str = "RUST_LOG";
+const ENV_LOG_FORMAT: &str = "LOG_FORMAT";
+const ENV_OTLP_ENDPOINT: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
 const DEFAULT_LOG_LEVEL: Level = Level::INFO;
+const TRACER_NAME: &str = "connector";
 
+/// Logs in text or json format (LOG_FORMAT=json), spans exported over otlp when
+/// OTEL_EXPORTER_OTLP_ENDPOINT is set. Must be called inside the tokio runtime.
 pub fn init() -> Result<()> {
     let level: Level = env::var(ENV_LOG_LEVEL)
         .map(parse_level)
         .unwrap_or(DEFAULT_LOG_LEVEL);
 
     LogTracer::init()?;
+    global::set_text_map_propagator(TraceContextPropagator::new());
 
-    let subscriber: Subscriber = FmtSubscriber::builder().with_max_level(level).finish();
+    let subscriber = Registry::default()
+        .with(LevelFilter::from_level(level))
+        .with(format_layer())
+        .with(otlp_layer()?);
 
     set_global_default(subscriber).context("Invalid setting for tracking")
 }
 
+/// Flushes spans not exported yet
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}
+
+fn format_layer<S>() -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    match env::var(ENV_LOG_FORMAT).as_deref() {
+        Ok("json") => fmt::layer().json().boxed(),
+        _ => fmt::layer().boxed(),
+    }
+}
+
+fn otlp_layer<S>() -> Result<Option<Box<dyn Layer<S> + Send + Sync>>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    if env::var(ENV_OTLP_ENDPOINT).is_err() {
+        return Ok(None);
+    }
+
+    let exporter: SpanExporter = SpanExporter::builder()
+        .with_tonic()
+        .build()
+        .context("Invalid otlp exporter setting")?;
+    let provider: TracerProvider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .build();
+
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(TRACER_NAME));
+    global::set_tracer_provider(provider);
+
+    Ok(Some(layer.boxed()))
+}
+
 fn parse_level(level: String) -> Level {
     Level::from_str(level.as_str()).unwrap_or(DEFAULT_LOG_LEVEL)
 }
@@ -0,0 +1,213 @@
+use crate::cache::MarketsCache;
+use crate::filter::is_glob_match;
+use chrono::Utc;
+use protocol::model::Symbol;
+use protocol::public::error::{ErrorCode, ErrorMessage};
+use serde::{Deserialize, Deserializer};
+use std::sync::Arc;
+use tracing::warn;
+
+const LIST_SEPARATOR: char = ',';
+
+/// Markets allowed for streaming. Both lists accept symbols and glob patterns.
+/// # Examples
+/// allowed: btc_usd, *_eur
+/// denied: usdt_*
+#[derive(Clone, Debug, Default)]
+pub struct Whitelist {
+    allowed: Vec<String>,
+    denied: Vec<String>,
+}
+
+impl Whitelist {
+    pub fn new(allowed: Vec<String>, denied: Vec<String>) -> Self {
+        Whitelist {
+            allowed: lowercase(allowed),
+            denied: lowercase(denied),
+        }
+    }
+
+    /// Empty allowed list means all markets which are not denied
+    pub fn is_allowed(&self, symbol: &str) -> bool {
+        let allowed: bool = self.allowed.is_empty() || matches(&self.allowed, symbol);
+
+        allowed && !matches(&self.denied, symbol)
+    }
+}
+
+/// Checks requested markets against whitelist and markets listed by the exchange
+pub struct MarketsValidator {
+    whitelist: Whitelist,
+    cache: Arc<MarketsCache>,
+}
+
+impl MarketsValidator {
+    pub fn new(whitelist: Whitelist, cache: Arc<MarketsCache>) -> Self {
+        MarketsValidator { whitelist, cache }
+    }
+
+    /// Markets are checked only against whitelist until the cache is loaded
+    pub fn validate<S: Symbol>(&self, market: &S) -> Result<(), ErrorMessage> {
+        let symbol: String = market.nats_format();
+
+        if !self.whitelist.is_allowed(&symbol) {
+            return Err(error(
+                ErrorCode::ActionBlocked,
+                format!("Market {} is not supported!", symbol),
+            ));
+        }
+
+        match self.cache.is_listed(&symbol) {
+            Some(true) => Ok(()),
+            Some(false) => Err(error(
+                ErrorCode::MarketNotFound,
+                format!("Market {} not found!", symbol),
+            )),
+            None => {
+                warn!("Markets not loaded, cannot validate {}", symbol);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Reads list from config array or comma separated env variable e.g. btc_usd,*_eur
+pub fn list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum List {
+        Values(Vec<String>),
+        Joined(String),
+    }
+
+    let values: Vec<String> = match List::deserialize(deserializer)? {
+        List::Values(values) => values,
+        List::Joined(joined) => joined
+            .split(LIST_SEPARATOR)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect(),
+    };
+
+    Ok(values)
+}
+
+fn matches(patterns: &[String], symbol: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| is_glob_match(pattern, symbol))
+}
+
+fn lowercase(values: Vec<String>) -> Vec<String> {
+    values.iter().map(|value| value.to_lowercase()).collect()
+}
+
+fn error(code: ErrorCode, message: String) -> ErrorMessage {
+    ErrorMessage {
+        code: code as i32,
+        message,
+        timestamp: Utc::now().timestamp_millis(),
+        exchange_message: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::MarketsCache;
+    use crate::whitelist::{MarketsValidator, Whitelist};
+    use protocol::model::{Currency, Symbol};
+    use protocol::public::error::ErrorCode;
+    use protocol::public::market::Market;
+    use std::sync::Arc;
+
+    struct TestMarket(&'static str, &'static str);
+
+    impl Symbol for TestMarket {
+        fn from(&self) -> Currency {
+            Currency::new(self.0.to_string())
+        }
+
+        fn to(&self) -> Currency {
+            Currency::new(self.1.to_string())
+        }
+
+        fn exchange_format(&self) -> String {
+            self.nats_format().to_uppercase()
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn listed(symbols: &[&str]) -> Arc<MarketsCache> {
+        let cache: MarketsCache = MarketsCache::default();
+        let markets: Vec<Market> = symbols
+            .iter()
+            .map(|symbol| Market {
+                symbol: symbol.to_string(),
+                ..Market::default()
+            })
+            .collect();
+
+        cache.update(Ok(markets));
+        Arc::new(cache)
+    }
+
+    #[test]
+    fn whitelist_should_allow_all_markets_by_default() {
+        let whitelist: Whitelist = Whitelist::default();
+
+        assert!(whitelist.is_allowed("btc_usd"));
+    }
+
+    #[test]
+    fn whitelist_should_apply_allowed_and_denied_patterns() {
+        let whitelist: Whitelist =
+            Whitelist::new(strings(&["*_EUR", "btc_usd"]), strings(&["usdt_*"]));
+
+        assert!(whitelist.is_allowed("btc_eur"));
+        assert!(whitelist.is_allowed("btc_usd"));
+        assert!(!whitelist.is_allowed("eth_usd"));
+        assert!(!whitelist.is_allowed("usdt_eur"));
+    }
+
+    #[test]
+    fn validate_should_reject_market_not_listed_by_exchange() {
+        let validator: MarketsValidator =
+            MarketsValidator::new(Whitelist::default(), listed(&["btc_usd"]));
+
+        let result = validator.validate(&TestMarket("xyz", "usd"));
+
+        assert!(validator.validate(&TestMarket("btc", "usd")).is_ok());
+        assert_eq!(
+            result.err().map(|error| error.code),
+            Some(ErrorCode::MarketNotFound as i32)
+        );
+    }
+
+    #[test]
+    fn validate_should_reject_denied_market() {
+        let whitelist: Whitelist = Whitelist::new(vec![], strings(&["btc_*"]));
+        let validator: MarketsValidator = MarketsValidator::new(whitelist, listed(&["btc_usd"]));
+
+        let result = validator.validate(&TestMarket("btc", "usd"));
+
+        assert_eq!(
+            result.err().map(|error| error.code),
+            Some(ErrorCode::ActionBlocked as i32)
+        );
+    }
+
+    #[test]
+    fn validate_should_use_whitelist_only_before_markets_are_loaded() {
+        let validator: MarketsValidator =
+            MarketsValidator::new(Whitelist::default(), Arc::new(MarketsCache::default()));
+
+        assert!(validator.validate(&TestMarket("xyz", "usd")).is_ok());
+    }
+}
@@ -0,0 +1,48 @@
+use crate::client::request::{Channel, ExchangeRequest, Method};
+use crate::client::ws_client::WsClient;
+use crate::model::Market;
+use anyhow::Result;
+use connector::stream::adapter::ExchangeAdapter;
+use protocol::public::types::Exchange;
+use std::sync::Arc;
+
+pub struct CryptocomAdapter {
+    ws_client: Arc<WsClient>,
+}
+
+impl CryptocomAdapter {
+    pub fn new(ws_client: Arc<WsClient>) -> Self {
+        CryptocomAdapter { ws_client }
+    }
+}
+
+impl ExchangeAdapter for CryptocomAdapter {
+    type Market = Market;
+    type Channel = Channel;
+    type Request = ExchangeRequest;
+
+    fn exchange(&self) -> Exchange {
+        Exchange::Cryptocom
+    }
+
+    fn market(&self, from: String, to: String) -> Market {
+        Market::new(from, to)
+    }
+
+    fn subscribe(&self, market: &Market, channel: &Channel) -> ExchangeRequest {
+        ExchangeRequest::new(market, channel, Method::Subscribe)
+    }
+
+    fn unsubscribe(&self, market: &Market, channel: &Channel) -> ExchangeRequest {
+        ExchangeRequest::new(market, channel, Method::Unsubscribe)
+    }
+
+    fn send(&self, request: ExchangeRequest) -> Result<()> {
+        self.ws_client.send(request)
+    }
+
+    fn connection(&self, market: &Market, channel: &Channel) -> Option<usize> {
+        self.ws_client
+            .connection(&ExchangeRequest::new(market, channel, Method::Subscribe))
+    }
+}
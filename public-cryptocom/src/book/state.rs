@@ -1,13 +1,14 @@
+use crate::adapter::CryptocomAdapter;
 use crate::book::models::{OrderBook, Pair, Update};
 use crate::client::request::Channel;
 use crate::model::Market;
 use crate::topics;
-use crate::utils::state::State;
 use anyhow::anyhow;
 use anyhow::Result;
 use async_nats::subject::ToSubject;
 use async_nats::Subject;
 use chrono::Utc;
+use connector::stream::state::{SequenceGap, State};
 use protocol::public::book::{Book, Offer, OrderBookMessage};
 use protocol::public::types::{Exchange, MessageType};
 use rust_decimal::Decimal;
@@ -39,7 +40,7 @@ impl OrderBookState {
         if self.last_update != Decimal::NEGATIVE_ONE || self.last_update == update {
             Ok(())
         } else {
-            Err(anyhow!("Order book sequence id missed"))
+            Err(anyhow!(SequenceGap("Order book")))
         }
     }
 
@@ -63,6 +64,8 @@ impl OrderBookState {
             sequence: self.sequence,
             exchange: Exchange::Cryptocom as i32,
             book: Some(state),
+            latency: None,
+            epoch: 0,
         })
     }
 
@@ -94,6 +97,8 @@ impl OrderBookState {
             sequence: self.sequence,
             exchange: Exchange::Cryptocom as i32,
             book: Some(state),
+            latency: None,
+            epoch: 0,
         })
     }
 
@@ -120,7 +125,7 @@ impl OrderBookState {
     }
 }
 
-impl State<OrderBook, OrderBookMessage> for OrderBookState {
+impl State<CryptocomAdapter, OrderBook, OrderBookMessage> for OrderBookState {
     fn update(&mut self, book: OrderBook) -> Result<OrderBookMessage> {
         let state: Book = Book::from(&book);
         match book {
@@ -135,9 +140,23 @@ impl State<OrderBook, OrderBookMessage> for OrderBookState {
             sequence: self.sequence,
             exchange: Exchange::Cryptocom as i32,
             book: Some(self.book()),
+            latency: None,
+            epoch: 0,
         }
     }
 
+    fn sequence(&self) -> i64 {
+        self.sequence
+    }
+
+    fn set_sequence(&mut self, sequence: i64) {
+        self.sequence = sequence;
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        Some(self.timestamp)
+    }
+
     fn topic(&self, market: &Market) -> Subject {
         topics::order_book(market).to_subject()
     }
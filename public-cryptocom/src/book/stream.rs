@@ -1,57 +1,90 @@
+use crate::adapter::CryptocomAdapter;
 use crate::book::models::OrderBook;
 use crate::book::state::OrderBookState;
+use crate::client::request::Channel;
 use crate::client::response::WsResult;
 use crate::client::ws_client::WsClient;
 use crate::config::ExchangeConfig;
+use crate::model::Market;
 use crate::topics;
-use crate::utils::handler::Event::Updated;
-use crate::utils::handler::{Event, Handler};
-use crate::utils::stream::handle_nats_subscription;
 use anyhow::Result;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use connector::stream::handler::{Event, Handler};
+use connector::stream::subscription::Router;
 use connector::subscription::NatsSubscription;
+use connector::whitelist::MarketsValidator;
 use log::info;
 use protocol::client::NatsClient;
 use protocol::public::book::{OrderBookMessage, OrderBookRequest};
-use protocol::topics::{SnapshotTopic, Topic};
+use protocol::public::types::LeaseRequest;
+use protocol::topics::{LeaseTopic, SnapshotTopic, Topic};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::select;
 use tokio::sync::broadcast::Receiver;
 use tokio::sync::mpsc::Sender;
+use tokio::sync::watch;
 
 const QUEUE: &str = "cryptocom.book";
+pub const CHANNEL: &str = "book";
+
+type BookHandler = Handler<CryptocomAdapter, OrderBook>;
 
 pub async fn run(
     nats_client: Arc<NatsClient>,
     ws_client: Arc<WsClient>,
+    cache: Arc<MarketsCache>,
+    shard: Shard,
+    leader: Leader,
+    admin: AdminHook,
     config: &ExchangeConfig,
 ) -> Result<()> {
     let topic: SnapshotTopic = topics::order_book(&config.markets).snapshot();
+    let lease_topic: LeaseTopic = topics::order_book(&config.markets).lease();
+    let lease_ttl: Duration = Duration::from_secs(config.lease_ttl);
 
     info!("Starting book stream processing");
 
+    let validator: Arc<MarketsValidator> =
+        Arc::new(MarketsValidator::new(config.whitelist(), cache));
+    let adapter: Arc<CryptocomAdapter> = Arc::new(CryptocomAdapter::new(ws_client.clone()));
+    let router: Router<CryptocomAdapter> = Router::new(
+        adapter.clone(),
+        validator.clone(),
+        nats_client.clone(),
+        shard.clone(),
+    )
+    .standby(leader.clone());
+
     let nats_subscription: NatsSubscription<OrderBookRequest> =
-        NatsSubscription::new(&nats_client, topic, QUEUE).await?;
-    let shutdown: Receiver<()> = ws_client.subscribe_shutdown();
+        router.subscribe(topic, QUEUE).await?;
+    let lease_subscription: NatsSubscription<LeaseRequest> =
+        router.subscribe(lease_topic, QUEUE).await?;
+    let reset: Receiver<usize> = ws_client.subscribe_reset();
+    let connected: watch::Receiver<bool> = ws_client.subscribe_connection();
     let ws_subscription: Receiver<WsResult<OrderBook>> = ws_client.subscribe_book();
-    let (message_handler, tickers): (Handler<OrderBook>, Sender<Event<OrderBook>>) =
-        Handler::new(nats_client, ws_client, config);
+    let (message_handler, books): (BookHandler, Sender<Event<Market, OrderBook>>) =
+        Handler::new(nats_client, adapter, config.max_buffer_size);
+    let message_handler: BookHandler = message_handler
+        .preload(config.preload(CHANNEL)?)
+        .expire_after(lease_ttl)
+        .sharded(shard)
+        .standby(leader)
+        .validated(validator)
+        .admin(admin);
 
     select! {
-        result = message_handler.run::<OrderBookMessage, OrderBookState>(shutdown) => result,
-        result = handle_nats_subscription(tickers.clone(), nats_subscription) => result,
-        result = handle_ws_subscription(tickers.clone(), ws_subscription) => result
+        result = message_handler.run::<OrderBookMessage, OrderBookState>(reset, connected) => result,
+        result = router.snapshots(books.clone(), nats_subscription) => result,
+        result = router.leases(books.clone(), lease_subscription) => result,
+        result = router.updates(Channel::Book, books.clone(), ws_subscription, update) => result
     }
 }
 
-async fn handle_ws_subscription(
-    books: Sender<Event<OrderBook>>,
-    mut subscription: Receiver<WsResult<OrderBook>>,
-) -> Result<()> {
-    while let Ok(result) = subscription.recv().await {
-        if let Some(book) = result.data.first() {
-            books.send(Updated(result.market, book.clone())).await?
-        }
-    }
-
-    Ok(())
+fn update(result: WsResult<OrderBook>) -> Option<(Market, OrderBook)> {
+    let book: OrderBook = result.data.first()?.clone();
+    Some((result.market, book))
 }
@@ -0,0 +1,124 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::thread::JoinHandle;
+
+/// Inbound websocket text frame as received from the exchange
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CapturedFrame {
+    /// Receive time in micros since epoch
+    pub timestamp: i64,
+    pub connection: usize,
+    pub frame: String,
+}
+
+/// Json lines file of captured frames, flushed after every frame.
+/// Frames are written by a dedicated thread, so recording never blocks the socket reader.
+pub struct Capture {
+    frames: Option<Sender<CapturedFrame>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl Capture {
+    pub fn create(path: &str) -> Result<Self> {
+        let file: File = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Cannot open capture file {}", path))?;
+
+        let (frames, frames_out): (Sender<CapturedFrame>, Receiver<CapturedFrame>) = channel();
+        let writer: JoinHandle<()> = thread::Builder::new()
+            .name("ws-capture".to_string())
+            .spawn(move || write(LineWriter::new(file), frames_out))?;
+
+        Ok(Capture {
+            frames: Some(frames),
+            writer: Some(writer),
+        })
+    }
+
+    pub fn record(&self, connection: usize, frame: &str) -> Result<()> {
+        let captured: CapturedFrame = CapturedFrame {
+            timestamp: Utc::now().timestamp_micros(),
+            connection,
+            frame: frame.to_string(),
+        };
+
+        self.frames
+            .as_ref()
+            .and_then(|frames| frames.send(captured).ok())
+            .ok_or_else(|| anyhow!("Capture writer stopped"))
+    }
+}
+
+/// Writes the frames already recorded before closing the file
+impl Drop for Capture {
+    fn drop(&mut self) {
+        drop(self.frames.take());
+
+        if let Some(writer) = self.writer.take() {
+            writer.join().unwrap_or_default();
+        }
+    }
+}
+
+fn write(mut writer: LineWriter<File>, frames: Receiver<CapturedFrame>) {
+    for frame in frames {
+        let written: Result<()> = serde_json::to_string(&frame)
+            .map_err(|error| anyhow!(error))
+            .and_then(|line| Ok(writeln!(writer, "{}", line)?));
+
+        if let Err(error) = written {
+            warn!("Cannot write captured frame: {}", error);
+        }
+    }
+}
+
+/// Captured frames in the receive order, blank lines are skipped
+pub fn read(path: &Path) -> Result<Vec<CapturedFrame>> {
+    let file: File =
+        File::open(path).with_context(|| format!("Cannot open capture file {}", path.display()))?;
+
+    BufReader::new(file)
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str::<CapturedFrame>(&line?)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::capture::{read, Capture, CapturedFrame};
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn read_should_return_recorded_frames() {
+        let path: PathBuf =
+            std::env::temp_dir().join(format!("capture-{}.jsonl", std::process::id()));
+        let capture: Capture = Capture::create(path.to_str().unwrap()).unwrap();
+
+        capture
+            .record(0, r#"{"id":1,"method":"public/heartbeat"}"#)
+            .unwrap();
+        capture
+            .record(1, r#"{"id":2,"method":"subscribe"}"#)
+            .unwrap();
+        drop(capture);
+
+        let frames: Vec<CapturedFrame> = read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].connection, 1);
+        assert_eq!(frames[1].frame, r#"{"id":2,"method":"subscribe"}"#);
+        assert!(frames[0].timestamp <= frames[1].timestamp);
+    }
+}
@@ -1,3 +1,6 @@
+pub mod capture;
+pub mod pool;
 pub mod request;
 pub mod response;
+pub mod watchdog;
 pub mod ws_client;
@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// Assignment of exchange channels e.g. ticker.BTC_USD to websocket connections
+pub struct Pool {
+    channels: Vec<usize>,
+    assigned: HashMap<String, usize>,
+    max_channels: usize,
+}
+
+impl Pool {
+    pub fn new(connections: usize, max_channels: usize) -> Self {
+        Pool {
+            channels: vec![0; connections.max(1)],
+            assigned: HashMap::new(),
+            max_channels,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Connection already serving the channel or the least loaded one below the cap
+    pub fn subscribe(&mut self, channel: &str) -> Result<usize> {
+        if let Some(connection) = self.assigned.get(channel) {
+            return Ok(*connection);
+        }
+
+        let (connection, _): (usize, &usize) = self
+            .channels
+            .iter()
+            .enumerate()
+            .filter(|(_, channels)| **channels < self.max_channels)
+            .min_by_key(|(_, channels)| **channels)
+            .ok_or_else(|| anyhow!("Websocket channels limit reached for {}", channel))?;
+
+        self.channels[connection] += 1;
+        self.assigned.insert(channel.to_string(), connection);
+
+        Ok(connection)
+    }
+
+    /// Connection serving the channel without releasing it
+    pub fn connection(&self, channel: &str) -> Option<usize> {
+        self.assigned.get(channel).copied()
+    }
+
+    /// Connection serving the channel, None for not subscribed channel
+    pub fn unsubscribe(&mut self, channel: &str) -> Option<usize> {
+        let connection: usize = self.assigned.remove(channel)?;
+        self.channels[connection] -= 1;
+
+        Some(connection)
+    }
+
+    /// Releases all channels with connections serving them
+    pub fn drain(&mut self) -> Vec<(String, usize)> {
+        self.channels.iter_mut().for_each(|channels| *channels = 0);
+        self.assigned.drain().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::pool::Pool;
+
+    #[test]
+    fn subscribe_should_spread_channels_between_connections() {
+        let mut pool: Pool = Pool::new(2, 10);
+
+        assert_eq!(pool.subscribe("ticker.BTC_USD").unwrap(), 0);
+        assert_eq!(pool.subscribe("ticker.ETH_USD").unwrap(), 1);
+        assert_eq!(pool.subscribe("ticker.BTC_USD").unwrap(), 0);
+        assert_eq!(pool.connection("ticker.ETH_USD"), Some(1));
+        assert_eq!(pool.connection("ticker.SOL_USD"), None);
+    }
+
+    #[test]
+    fn subscribe_should_fail_when_all_connections_are_full() {
+        let mut pool: Pool = Pool::new(2, 1);
+
+        pool.subscribe("ticker.BTC_USD").unwrap();
+        pool.subscribe("ticker.ETH_USD").unwrap();
+
+        assert!(pool.subscribe("ticker.SOL_USD").is_err());
+    }
+
+    #[test]
+    fn unsubscribe_should_release_connection_capacity() {
+        let mut pool: Pool = Pool::new(1, 1);
+
+        pool.subscribe("ticker.BTC_USD").unwrap();
+
+        assert_eq!(pool.unsubscribe("ticker.BTC_USD"), Some(0));
+        assert_eq!(pool.unsubscribe("ticker.BTC_USD"), None);
+        assert_eq!(pool.subscribe("ticker.ETH_USD").unwrap(), 0);
+    }
+
+    #[test]
+    fn drain_should_release_all_channels() {
+        let mut pool: Pool = Pool::new(2, 1);
+
+        pool.subscribe("ticker.BTC_USD").unwrap();
+        pool.subscribe("ticker.ETH_USD").unwrap();
+
+        assert_eq!(pool.drain().len(), 2);
+        assert_eq!(pool.unsubscribe("ticker.BTC_USD"), None);
+        assert_eq!(pool.subscribe("ticker.SOL_USD").unwrap(), 0);
+    }
+}
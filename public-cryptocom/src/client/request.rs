@@ -4,7 +4,7 @@ use protocol::model::Symbol;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Method {
     #[serde(rename = "public/respond-heartbeat")]
@@ -83,6 +83,28 @@ impl ExchangeRequest {
         }
     }
 
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Exchange channel name e.g. ticker.BTC_USD, None for heartbeat
+    pub fn channel(&self) -> Option<&str> {
+        self.params
+            .as_ref()
+            .and_then(|params| params.channels.first())
+            .map(String::as_str)
+    }
+
+    /// Unsubscribe by exchange channel name e.g. ticker.BTC_USD
+    pub fn unsubscribe(channel: String) -> Self {
+        let params: Params = Params {
+            channels: vec![channel],
+            book_subscription_type: None,
+        };
+
+        Self::from_params(params, Method::Unsubscribe)
+    }
+
     pub fn new(market: &Market, channel: &Channel, method: Method) -> Self {
         if *channel == Channel::Book {
             Self::from_params(Params::book(channel, market), method)
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Time of the last data event per subscribed channel e.g. book.BTC_USD.
+/// Only watched channel types (e.g. ticker, book) make their connection stale,
+/// quiet channels of other types (e.g. trade of thin markets) are only reported.
+pub struct Watchdog {
+    channels: HashMap<String, (usize, Instant)>,
+    stale_after: Duration,
+    watched: Vec<String>,
+}
+
+impl Watchdog {
+    pub fn new(stale_after: Duration, watched: Vec<String>) -> Self {
+        Watchdog {
+            channels: HashMap::new(),
+            stale_after,
+            watched,
+        }
+    }
+
+    pub fn subscribed(&mut self, channel: &str, connection: usize, now: Instant) {
+        self.channels.insert(key(channel), (connection, now));
+    }
+
+    pub fn unsubscribed(&mut self, channel: &str) {
+        self.channels.remove(&key(channel));
+    }
+
+    pub fn received(&mut self, channel: &str, now: Instant) {
+        if let Some((_, last)) = self.channels.get_mut(&key(channel)) {
+            *last = now;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.channels.clear();
+    }
+
+    /// Restarts thresholds of channels served by reconnected connection
+    pub fn reset(&mut self, connection: usize, now: Instant) {
+        self.channels
+            .values_mut()
+            .filter(|(served_by, _)| *served_by == connection)
+            .for_each(|(_, last)| *last = now);
+    }
+
+    /// Channels without data event within the threshold
+    pub fn stale(&self, now: Instant) -> Vec<String> {
+        let mut stale: Vec<String> = self
+            .channels
+            .iter()
+            .filter(|(_, (_, last))| now.duration_since(*last) > self.stale_after)
+            .map(|(channel, _)| channel.clone())
+            .collect();
+
+        stale.sort();
+        stale
+    }
+
+    /// Connection serving watched channels, all of them stale
+    pub fn is_stale(&self, connection: usize, now: Instant) -> bool {
+        let mut channels = self
+            .channels
+            .iter()
+            .filter(|(channel, (served_by, _))| {
+                *served_by == connection && self.is_watched(channel)
+            })
+            .map(|(_, served)| served)
+            .peekable();
+
+        channels.peek().is_some()
+            && channels.all(|(_, last)| now.duration_since(*last) > self.stale_after)
+    }
+
+    fn is_watched(&self, channel: &str) -> bool {
+        channel
+            .split('.')
+            .next()
+            .is_some_and(|kind| self.watched.iter().any(|watched| watched == kind))
+    }
+}
+
+/// Subscription name without book depth, book.BTC_USD.10 is book.BTC_USD
+fn key(channel: &str) -> String {
+    channel
+        .splitn(3, '.')
+        .take(2)
+        .collect::<Vec<&str>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::watchdog::Watchdog;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    const STALE_AFTER: Duration = Duration::from_secs(30);
+
+    fn watched() -> Vec<String> {
+        vec!["ticker".to_string(), "book".to_string()]
+    }
+
+    #[test]
+    fn stale_should_return_channels_without_recent_data() {
+        let now: Instant = Instant::now();
+        let mut watchdog: Watchdog = Watchdog::new(STALE_AFTER, watched());
+
+        watchdog.subscribed("ticker.BTC_USD", 0, now - Duration::from_secs(60));
+        watchdog.subscribed("book.ETH_USD.10", 0, now - Duration::from_secs(60));
+        watchdog.received("book.ETH_USD", now - Duration::from_secs(10));
+
+        assert_eq!(watchdog.stale(now), vec!["ticker.BTC_USD".to_string()]);
+    }
+
+    #[test]
+    fn is_stale_should_require_all_connection_channels_stale() {
+        let now: Instant = Instant::now();
+        let mut watchdog: Watchdog = Watchdog::new(STALE_AFTER, watched());
+
+        watchdog.subscribed("ticker.BTC_USD", 0, now - Duration::from_secs(60));
+        watchdog.subscribed("ticker.ETH_USD", 1, now - Duration::from_secs(60));
+        watchdog.subscribed("ticker.SOL_USD", 1, now);
+
+        assert!(watchdog.is_stale(0, now));
+        assert!(!watchdog.is_stale(1, now));
+        assert!(!watchdog.is_stale(2, now));
+    }
+
+    #[test]
+    fn is_stale_should_skip_not_watched_channels() {
+        let now: Instant = Instant::now();
+        let mut watchdog: Watchdog = Watchdog::new(STALE_AFTER, watched());
+
+        watchdog.subscribed("trade.BTC_EUR", 0, now - Duration::from_secs(60));
+        watchdog.subscribed("trade.ETH_EUR", 1, now - Duration::from_secs(60));
+        watchdog.subscribed("ticker.ETH_EUR", 1, now);
+
+        assert!(!watchdog.is_stale(0, now));
+        assert!(!watchdog.is_stale(1, now));
+        assert_eq!(
+            watchdog.stale(now),
+            vec!["trade.BTC_EUR".to_string(), "trade.ETH_EUR".to_string()]
+        );
+    }
+}
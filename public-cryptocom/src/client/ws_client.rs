@@ -1,64 +1,114 @@
 use crate::book::models::OrderBook;
-use crate::client::request::ExchangeRequest;
+use crate::client::capture;
+use crate::client::capture::{Capture, CapturedFrame};
+use crate::client::pool::Pool;
+use crate::client::request::{ExchangeRequest, Method as RequestMethod};
 use crate::client::response::{ExchangeResponse, Method, WsResult};
+use crate::client::watchdog::Watchdog;
 use crate::config::ExchangeConfig;
 use crate::ticker::models::Ticker;
 use crate::trades::models::Transaction;
 use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::future::try_join_all;
 use futures::stream::SplitSink;
 use futures::stream::SplitStream;
 use futures::{SinkExt, StreamExt};
+use http::healthcheck::checks::{HealthCheck, HealthcheckResult};
+use http::metrics::market as metrics;
 use log::{debug, info, warn};
+use protocol::model::Symbol;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 use tokio::net::TcpStream;
-use tokio::sync::broadcast;
+use tokio::select;
 use tokio::sync::broadcast::{Receiver, Sender};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{broadcast, watch, Mutex as AsyncMutex, OwnedMutexGuard};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep_until, Instant, Interval, MissedTickBehavior};
 use tokio_tungstenite::tungstenite::handshake::client::Response;
 use tokio_tungstenite::tungstenite::http::Uri;
 use tokio_tungstenite::tungstenite::{Error, Message};
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+const HEALTHCHECK_NAME: &str = "ws";
+const EXCHANGE: &str = "cryptocom";
+const WS_SOURCE: &str = "ws";
+
 type Event = ExchangeResponse<Option<WsResult<Value>>>;
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 type WsSender<T> = Sender<WsResult<T>>;
 type WsReceiver<T> = Receiver<WsResult<T>>;
+type Requests = (UnboundedSender<Message>, UnboundedReceiver<Message>);
 
+/// Pool of websocket connections. Channels are spread between connections
+/// up to the per connection cap, subscribe and unsubscribe requests are rate limited.
+/// Inbound frames can be captured into a file and replayed later instead of the exchange.
 pub struct WsClient {
     ws_uri: Uri,
+    capture: Option<Capture>,
+    replay: Option<PathBuf>,
     channels_in: ChannelsIn,
     channels_out: ChannelsOut,
+    connections: Vec<Connection>,
+    pool: Mutex<Pool>,
+    requests_per_second: u32,
+    status: Status,
 }
 
 #[derive(Clone)]
 struct ChannelsIn {
-    message_in: Sender<Message>,
     tickers_in: Sender<WsResult<Ticker>>,
     trades_in: Sender<WsResult<Transaction>>,
     books_in: Sender<WsResult<OrderBook>>,
-    shutdown_in: Sender<()>,
+    reset_in: Sender<usize>,
 }
 
 struct ChannelsOut {
-    message_out: Receiver<Message>,
     tickers_out: Receiver<WsResult<Ticker>>,
     trades_out: Receiver<WsResult<Transaction>>,
     books_out: Receiver<WsResult<OrderBook>>,
-    shutdown_out: Receiver<()>,
+    reset_out: Receiver<usize>,
+}
+
+/// Outgoing messages of a single socket. Control messages (heartbeat, pong) skip rate limit.
+struct Connection {
+    id: usize,
+    requests_in: UnboundedSender<Message>,
+    requests_out: Arc<AsyncMutex<UnboundedReceiver<Message>>>,
+    control_in: Sender<Message>,
+}
+
+/// Connected once all sockets of the pool are established.
+/// Socket without inbound frames or with all watched channels stale is reconnected.
+struct Status {
+    established: watch::Sender<usize>,
+    total: usize,
+    connected: watch::Sender<bool>,
+    closing: AtomicBool,
+    watchdog: Mutex<Watchdog>,
+    idle_timeout: Duration,
 }
 
 impl WsClient {
     pub fn new(config: &ExchangeConfig) -> Result<WsClient> {
         let ws_uri: Uri = Uri::from_str(config.ws_url.as_str())?;
         let size: usize = config.max_buffer_size;
+        let pool: Pool = Pool::new(config.ws_connections, config.ws_max_channels);
+        let total: usize = pool.size();
+        let stale_timeout: Duration = Duration::from_secs(config.ws_stale_timeout);
 
-        let (shutdown_in, shutdown_out): (Sender<()>, Receiver<()>) =
-            broadcast::channel::<()>(size);
-        let (message_in, message_out): (Sender<Message>, Receiver<Message>) =
-            broadcast::channel::<Message>(size);
+        let (reset_in, reset_out): (Sender<usize>, Receiver<usize>) =
+            broadcast::channel::<usize>(size);
         let (tickers_in, tickers_out): (WsSender<Ticker>, WsReceiver<Ticker>) =
             broadcast::channel::<WsResult<Ticker>>(size);
         let (trades_in, trades_out): (WsSender<Transaction>, WsReceiver<Transaction>) =
@@ -67,37 +117,154 @@ impl WsClient {
             broadcast::channel::<WsResult<OrderBook>>(size);
 
         let channels_in = ChannelsIn {
-            message_in,
             tickers_in,
             trades_in,
             books_in,
-            shutdown_in,
+            reset_in,
         };
         let channels_out = ChannelsOut {
-            message_out,
             tickers_out,
             trades_out,
             books_out,
-            shutdown_out,
+            reset_out,
         };
+        let connections: Vec<Connection> = (0..total)
+            .map(|id| {
+                let (requests_in, requests_out): Requests = unbounded_channel();
+
+                Connection {
+                    id,
+                    requests_in,
+                    requests_out: Arc::new(AsyncMutex::new(requests_out)),
+                    control_in: broadcast::Sender::new(size),
+                }
+            })
+            .collect();
+
+        let capture: Option<Capture> = config
+            .ws_capture_file
+            .as_deref()
+            .map(Capture::create)
+            .transpose()?;
 
         Ok(WsClient {
             ws_uri,
+            capture,
+            replay: config.ws_replay_file.as_ref().map(PathBuf::from),
             channels_in,
             channels_out,
+            connections,
+            pool: Mutex::new(pool),
+            requests_per_second: config.ws_requests_per_second.max(1),
+            status: Status {
+                established: watch::Sender::new(0),
+                total,
+                connected: watch::Sender::new(false),
+                closing: AtomicBool::new(false),
+                watchdog: Mutex::new(Watchdog::new(
+                    stale_timeout,
+                    config.ws_stale_channels.clone(),
+                )),
+                idle_timeout: Duration::from_secs(config.ws_idle_timeout),
+            },
         })
     }
 
+    /// Routes subscription to the connection serving the channel
     pub fn send(&self, request: ExchangeRequest) -> Result<()> {
-        self.channels_in.send_json(request)
+        let channel: String = request
+            .channel()
+            .ok_or_else(|| anyhow!("Request without channel"))?
+            .to_string();
+
+        let connection: usize = {
+            let mut pool = self
+                .pool
+                .lock()
+                .map_err(|_| anyhow!("Pool lock poisoned"))?;
+
+            match request.method() {
+                RequestMethod::Subscribe => pool.subscribe(&channel)?,
+                _ => match pool.unsubscribe(&channel) {
+                    Some(connection) => connection,
+                    None => {
+                        debug!("Skipping unsubscribe of not subscribed {}", channel);
+                        return Ok(());
+                    }
+                },
+            }
+        };
+
+        let mut watchdog = self.status.watchdog()?;
+
+        match request.method() {
+            RequestMethod::Subscribe => watchdog.subscribed(&channel, connection, Instant::now()),
+            _ => watchdog.unsubscribed(&channel),
+        }
+
+        self.connections[connection].send_request(request)
     }
 
-    fn subscribe_message(&self) -> Receiver<Message> {
-        self.channels_out.message_out.resubscribe()
+    /// Connection serving the channel of subscribe request
+    pub fn connection(&self, request: &ExchangeRequest) -> Option<usize> {
+        let channel: &str = request.channel()?;
+
+        self.pool.lock().ok()?.connection(channel)
     }
 
-    pub fn subscribe_shutdown(&self) -> Receiver<()> {
-        self.channels_out.shutdown_out.resubscribe()
+    /// Unsubscribes all channels of all connections
+    pub fn unsubscribe_all(&self) -> Result<()> {
+        let channels: Vec<(String, usize)> = self
+            .pool
+            .lock()
+            .map_err(|_| anyhow!("Pool lock poisoned"))?
+            .drain();
+
+        self.status.watchdog()?.clear();
+
+        info!("Unsubscribing {} websocket channels", channels.len());
+
+        for (channel, connection) in channels {
+            self.connections[connection].send_request(ExchangeRequest::unsubscribe(channel))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends close frame after queued requests and waits for all connections to close
+    pub async fn close(&self) -> Result<()> {
+        self.status.closing.store(true, Ordering::SeqCst);
+
+        for connection in &self.connections {
+            connection.close()?;
+        }
+
+        self.status
+            .established
+            .subscribe()
+            .wait_for(|established| *established == 0)
+            .await?;
+
+        info!("All websocket connections closed");
+        Ok(())
+    }
+
+    /// Subscribed channels without data events within the stale timeout
+    pub fn stale_channels(&self) -> Vec<String> {
+        self.status
+            .watchdog()
+            .map(|watchdog| watchdog.stale(Instant::now()))
+            .unwrap_or_default()
+    }
+
+    /// Id of connection lost on error, channels it served have to be subscribed again
+    pub fn subscribe_reset(&self) -> Receiver<usize> {
+        self.channels_out.reset_out.resubscribe()
+    }
+
+    /// Current connection status, true after all websocket connections are established
+    pub fn subscribe_connection(&self) -> watch::Receiver<bool> {
+        self.status.connected.subscribe()
     }
 
     pub fn subscribe_book(&self) -> Receiver<WsResult<OrderBook>> {
@@ -113,44 +280,160 @@ impl WsClient {
     }
 
     pub async fn run(&self) -> Result<()> {
-        loop {
+        if let Some(path) = &self.replay {
+            return self.run_replay(path).await;
+        }
+
+        try_join_all(
+            self.connections
+                .iter()
+                .map(|connection| self.run_connection(connection)),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn run_connection(&self, connection: &Connection) -> Result<()> {
+        while !self.status.is_closing() {
             let ws_uri: &Uri = &self.ws_uri;
             let channels_in: &ChannelsIn = &self.channels_in;
-            let message_out: Receiver<Message> = self.subscribe_message();
-
-            if let Err(error) = connect(ws_uri, channels_in, message_out).await {
-                warn!("Websocket restarting on error: {}", error);
+            let rate: u32 = self.requests_per_second;
+            let capture: Option<&Capture> = self.capture.as_ref();
+
+            if connect(ws_uri, channels_in, connection, rate, &self.status, capture)
+                .await
+                .is_err()
+            {
+                info!("Websocket {} restarting", connection.id);
+                metrics::reconnect(EXCHANGE);
             }
         }
+
+        Ok(())
+    }
+
+    /// Feeds captured frames instead of the exchange, each through the connection it was
+    /// received on. Frames of connections above the pool size go to the last connection.
+    async fn run_replay(&self, path: &Path) -> Result<()> {
+        let frames: Vec<CapturedFrame> = capture::read(path)?;
+        let last: usize = self.connections.len() - 1;
+
+        info!("Replaying {} frames from {}", frames.len(), path.display());
+
+        let mut routed: Vec<Vec<CapturedFrame>> = vec![Vec::new(); self.connections.len()];
+
+        for frame in frames {
+            routed[frame.connection.min(last)].push(frame);
+        }
+
+        try_join_all(
+            self.connections
+                .iter()
+                .zip(&routed)
+                .map(|(connection, frames)| {
+                    replay(frames, &self.channels_in, connection, &self.status)
+                }),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Fails while websocket is disconnected, stale channels are reported in details only
+pub struct WsHealthCheck {
+    ws_client: Arc<WsClient>,
+}
+
+impl WsHealthCheck {
+    pub fn new(ws_client: Arc<WsClient>) -> Self {
+        WsHealthCheck { ws_client }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for WsHealthCheck {
+    async fn check(&self) -> HealthcheckResult {
+        let connected: bool = *self.ws_client.status.connected.borrow();
+        let stale: Vec<String> = self.ws_client.stale_channels();
+
+        HealthcheckResult {
+            service: String::from(HEALTHCHECK_NAME),
+            enabled: connected,
+            details: stale,
+        }
     }
 }
 
 async fn connect(
     uri: &Uri,
     channels: &ChannelsIn,
-    mut message_out: Receiver<Message>,
+    connection: &Connection,
+    rate: u32,
+    status: &Status,
+    capture: Option<&Capture>,
 ) -> Result<()> {
+    let requests_out: OwnedMutexGuard<UnboundedReceiver<Message>> =
+        connection.requests_out.clone().lock_owned().await;
+    let control_out: Receiver<Message> = connection.control_in.subscribe();
+
     let (ws_stream, _): (WsStream, Response) = connect_async(uri).await?;
 
-    let (mut sink, mut stream): (SplitSink<WsStream, Message>, SplitStream<WsStream>) =
+    let (sink, mut stream): (SplitSink<WsStream, Message>, SplitStream<WsStream>) =
         ws_stream.split();
 
-    info!("WebSocket connection established {}!", uri.to_string());
-
-    tokio::spawn(async move {
-        while let Ok(result) = message_out.recv().await {
-            if let Err(error) = sink.send(result).await {
-                warn!("Websocket connection already closed: {}", error);
-                break;
+    info!(
+        "WebSocket {} connection established {}!",
+        connection.id,
+        uri.to_string()
+    );
+
+    let writer: JoinHandle<()> = tokio::spawn(write(sink, requests_out, control_out, rate));
+    status.established(connection.id);
+
+    let mut result: Result<()> = Ok(());
+    let mut last_frame: Instant = Instant::now();
+    let mut watchdog: Interval = interval(WATCHDOG_INTERVAL);
+
+    loop {
+        let message = select! {
+            message = stream.next() => match message {
+                Some(message) => message,
+                None if status.is_closing() => break,
+                None => {
+                    warn!("Websocket {} stream ended", connection.id);
+                    result = Err(anyhow!(Error::ConnectionClosed));
+                    break;
+                }
+            },
+            _ = watchdog.tick() => {
+                result = status.check(connection.id, last_frame);
+
+                if result.is_err() {
+                    break;
+                }
+                continue;
             }
-        }
-    });
+        };
+
+        last_frame = Instant::now();
 
-    while let Some(message) = stream.next().await {
-        let result: Result<()> = match message {
+        result = match message {
             Ok(Message::Text(json)) => {
                 debug!("Processing ws message: {}", json);
-                process_event(json, channels)
+
+                if let Some(Err(error)) =
+                    capture.map(|capture| capture.record(connection.id, &json))
+                {
+                    warn!("Cannot capture ws message: {}", error);
+                }
+
+                process_event(json, channels, connection, status)
+            }
+            Ok(Message::Close(_)) if status.is_closing() => {
+                info!("Websocket {} closed", connection.id);
+                break;
             }
             Ok(Message::Close(_)) => {
                 warn!("Websocket connection closed by client!");
@@ -158,7 +441,7 @@ async fn connect(
             }
             Ok(Message::Ping(data)) => {
                 debug!("Processing ping message");
-                channels.send_message(Message::Pong(data))
+                connection.send_control(Message::Pong(data))
             }
             Ok(_) => {
                 warn!("Unsupported message type!");
@@ -170,57 +453,223 @@ async fn connect(
             }
         };
 
-        if let Err(error) = result {
-            channels.shutdown_in.send(())?;
-            return Err(error);
+        if result.is_err() {
+            break;
         }
     }
 
+    writer.abort();
+    status.lost(connection.id);
+
+    if let Err(error) = &result {
+        warn!(
+            "Websocket {} failed, resetting its channels: {}",
+            connection.id, error
+        );
+        channels.reset_in.send(connection.id).unwrap_or_default();
+    }
+
+    result
+}
+
+/// Replayed connection is established at once and starts feeding frames after the first
+/// subscription, keeping the captured gaps between frames. Frames failing to process are
+/// logged and skipped. Connection stays open without frames until closed.
+async fn replay(
+    frames: &[CapturedFrame],
+    channels: &ChannelsIn,
+    connection: &Connection,
+    status: &Status,
+) -> Result<()> {
+    let mut requests_out: OwnedMutexGuard<UnboundedReceiver<Message>> =
+        connection.requests_out.clone().lock_owned().await;
+    let _control_out: Receiver<Message> = connection.control_in.subscribe();
+
+    status.established(connection.id);
+
+    if let Some(first) = frames.first() {
+        match requests_out.recv().await {
+            Some(message) if !message.is_close() => {}
+            _ => {
+                status.lost(connection.id);
+                return Ok(());
+            }
+        }
+
+        let started: Instant = Instant::now();
+
+        for frame in frames {
+            let offset: i64 = (frame.timestamp - first.timestamp).max(0);
+            sleep_until(started + Duration::from_micros(offset as u64)).await;
+
+            if let Err(error) = process_event(frame.frame.clone(), channels, connection, status) {
+                warn!("Cannot process captured frame: {}", error);
+            }
+        }
+
+        info!("Websocket {} replay finished", connection.id);
+    }
+
+    while let Some(message) = requests_out.recv().await {
+        if message.is_close() {
+            break;
+        }
+    }
+
+    status.lost(connection.id);
     Ok(())
 }
 
-impl ChannelsIn {
-    fn send_json<T: Serialize>(&self, message: T) -> Result<()> {
-        let json: String = serde_json::to_string(&message)?;
-        self.send_message(Message::text(json))
+/// Sends control messages first, subscriptions at most rate per second.
+/// Subscriptions are queued while the socket is reconnecting.
+async fn write(
+    mut sink: SplitSink<WsStream, Message>,
+    mut requests_out: OwnedMutexGuard<UnboundedReceiver<Message>>,
+    mut control_out: Receiver<Message>,
+    rate: u32,
+) {
+    let mut throttle: Interval = interval(Duration::from_secs(1) / rate);
+    throttle.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        let message: Message = select! {
+            biased;
+            Ok(message) = control_out.recv() => message,
+            Some(message) = requests_out.recv() => {
+                throttle.tick().await;
+                message
+            },
+            else => break,
+        };
+
+        if let Err(error) = sink.send(message).await {
+            warn!("Websocket connection already closed: {}", error);
+            break;
+        }
+    }
+}
+
+impl Status {
+    fn established(&self, connection: usize) {
+        self.established
+            .send_modify(|established| *established += 1);
+        let established: usize = *self.established.borrow();
+
+        debug!(
+            "Websocket {} ready, {} connections up",
+            connection, established
+        );
+        self.connected.send_replace(established == self.total);
+    }
+
+    fn lost(&self, connection: usize) {
+        self.established
+            .send_modify(|established| *established -= 1);
+        self.connected.send_replace(false);
+
+        if let Ok(mut watchdog) = self.watchdog() {
+            watchdog.reset(connection, Instant::now());
+        }
+    }
+
+    /// Fails for half-open socket, without frames or data events within thresholds
+    fn check(&self, connection: usize, last_frame: Instant) -> Result<()> {
+        let now: Instant = Instant::now();
+        let idle: Duration = now.duration_since(last_frame);
+
+        if idle > self.idle_timeout {
+            Err(anyhow!("Websocket {} idle for {:?}", connection, idle))
+        } else if self.watchdog()?.is_stale(connection, now) {
+            Err(anyhow!("Websocket {} channels are stale", connection))
+        } else {
+            Ok(())
+        }
     }
 
-    fn send_message(&self, message: Message) -> Result<()> {
-        self.message_in
-            .send(message)
-            .map(|_| ())
-            .map_err(|error| anyhow!(error))
+    fn watchdog(&self) -> Result<MutexGuard<'_, Watchdog>> {
+        self.watchdog
+            .lock()
+            .map_err(|_| anyhow!("Watchdog lock poisoned"))
     }
 
+    fn is_closing(&self) -> bool {
+        self.closing.load(Ordering::SeqCst)
+    }
+}
+
+impl Connection {
+    fn send_request(&self, request: ExchangeRequest) -> Result<()> {
+        let json: String = serde_json::to_string(&request)?;
+        self.requests_in
+            .send(Message::text(json))
+            .map_err(|_| anyhow!("Websocket {} requests closed", self.id))
+    }
+
+    fn close(&self) -> Result<()> {
+        self.requests_in
+            .send(Message::Close(None))
+            .map_err(|_| anyhow!("Websocket {} requests closed", self.id))
+    }
+
+    fn send_control(&self, message: Message) -> Result<()> {
+        send(&self.control_in, message)
+    }
+
+    fn send_json<T: Serialize>(&self, message: T) -> Result<()> {
+        let json: String = serde_json::to_string(&message)?;
+        self.send_control(Message::text(json))
+    }
+}
+
+impl ChannelsIn {
     fn send_ticker(&self, message: WsResult<Ticker>) -> Result<()> {
-        self.tickers_in
-            .send(message)
-            .map(|_| ())
-            .map_err(|error| anyhow!(error))
+        send(&self.tickers_in, message)
     }
 
     fn send_trade(&self, message: WsResult<Transaction>) -> Result<()> {
-        self.trades_in
-            .send(message)
-            .map(|_| ())
-            .map_err(|error| anyhow!(error))
+        send(&self.trades_in, message)
     }
 
     fn send_book(&self, message: WsResult<OrderBook>) -> Result<()> {
-        self.books_in
-            .send(message)
-            .map(|_| ())
-            .map_err(|error| anyhow!(error))
+        send(&self.books_in, message)
     }
 }
 
-fn process_event(json: String, channels: &ChannelsIn) -> Result<()> {
-    match from_string::<Event>(&json) {
+fn send<T>(sender: &Sender<T>, message: T) -> Result<()> {
+    sender
+        .send(message)
+        .map(|_| ())
+        .map_err(|_| anyhow!("Channel closed"))
+}
+
+fn process_event(
+    json: String,
+    channels: &ChannelsIn,
+    connection: &Connection,
+    status: &Status,
+) -> Result<()> {
+    let event: Result<Event> = from_string::<Event>(&json);
+
+    if let Ok(ExchangeResponse {
+        result: Some(result),
+        ..
+    }) = &event
+    {
+        let channel: String = format!("{}.{}", result.channel, result.market.exchange_format());
+        status.watchdog()?.received(&channel, Instant::now());
+        metrics::received(
+            EXCHANGE,
+            &result.channel.to_string(),
+            &result.market.nats_format(),
+        );
+    }
+
+    match event {
         Ok(ExchangeResponse {
             id,
             method: Method::Heartbeat,
             result: _,
-        }) => channels.send_json(ExchangeRequest::heartbeat(id)),
+        }) => connection.send_json(ExchangeRequest::heartbeat(id)),
         Ok(ExchangeResponse {
             id: _,
             method: Method::Subscribe,
@@ -270,9 +719,14 @@ fn process_event(json: String, channels: &ChannelsIn) -> Result<()> {
 
 fn from_value<T: DeserializeOwned>(json: &[Value]) -> Result<Vec<T>> {
     let array: Value = Value::Array(json.to_vec());
-    serde_json::from_value::<Vec<T>>(array).map_err(|error| anyhow!(error))
+    serde_json::from_value::<Vec<T>>(array).map_err(decode_error)
 }
 
 fn from_string<T: DeserializeOwned>(json: &str) -> Result<T> {
-    serde_json::from_str::<T>(json).map_err(|error| anyhow!(error))
+    serde_json::from_str::<T>(json).map_err(decode_error)
+}
+
+fn decode_error(error: serde_json::Error) -> anyhow::Error {
+    metrics::decode_error(EXCHANGE, WS_SOURCE);
+    anyhow!(error)
 }
@@ -1,8 +1,10 @@
 use crate::model::Market;
 use anyhow::Result;
 use connector::config::load_file;
+use connector::shard::default_replica;
+use connector::whitelist::{list, Whitelist};
 use http::server;
-use log::info;
+use log::{info, warn};
 use protocol::client;
 use serde::Deserialize;
 use std::env;
@@ -15,10 +17,112 @@ pub struct ExchangeConfig {
     pub ws_url: String,
     pub markets_url: String,
     pub markets: Market,
+    #[serde(default, deserialize_with = "list")]
+    pub allowed_markets: Vec<String>,
+    #[serde(default, deserialize_with = "list")]
+    pub denied_markets: Vec<String>,
+    #[serde(default, deserialize_with = "list")]
+    pub preload_markets: Vec<String>,
+    #[serde(default, deserialize_with = "list")]
+    pub preload_channels: Vec<String>,
+    pub markets_refresh_interval: u64,
+    #[serde(default = "default_lease_ttl")]
+    pub lease_ttl: u64,
+    #[serde(default)]
+    pub sharding: bool,
+    #[serde(default = "default_replica")]
+    pub replica_id: String,
+    #[serde(default)]
+    pub standby: bool,
+    #[serde(default = "default_failover_timeout")]
+    pub failover_timeout: u64,
+    #[serde(default = "default_ws_connections")]
+    pub ws_connections: usize,
+    #[serde(default = "default_ws_max_channels")]
+    pub ws_max_channels: usize,
+    #[serde(default = "default_ws_requests_per_second")]
+    pub ws_requests_per_second: u32,
+    #[serde(default = "default_ws_idle_timeout")]
+    pub ws_idle_timeout: u64,
+    #[serde(default = "default_ws_stale_timeout")]
+    pub ws_stale_timeout: u64,
+    /// Channel types whose silence reconnects the websocket, others are only reported
+    #[serde(default = "default_ws_stale_channels", deserialize_with = "list")]
+    pub ws_stale_channels: Vec<String>,
+    /// Appends every inbound websocket text frame to the file
+    #[serde(default)]
+    pub ws_capture_file: Option<String>,
+    /// Feeds frames captured in the file instead of connecting to the exchange
+    #[serde(default)]
+    pub ws_replay_file: Option<String>,
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
     pub max_concurrency: usize,
     pub max_buffer_size: usize,
 }
 
+impl ExchangeConfig {
+    pub fn whitelist(&self) -> Whitelist {
+        Whitelist::new(self.allowed_markets.clone(), self.denied_markets.clone())
+    }
+
+    /// Markets subscribed at startup for the channel e.g. ticker, trades, book
+    pub fn preload(&self, channel: &str) -> Result<Vec<Market>> {
+        if !self.preload_channels.iter().any(|name| name == channel) {
+            return Ok(vec![]);
+        }
+
+        let whitelist: Whitelist = self.whitelist();
+        let mut markets: Vec<Market> = vec![];
+
+        for symbol in &self.preload_markets {
+            if whitelist.is_allowed(&symbol.to_lowercase()) {
+                markets.push(Market::from_nats_format(symbol.to_string())?);
+            } else {
+                warn!("Skipping preload of not allowed market {}", symbol);
+            }
+        }
+
+        Ok(markets)
+    }
+}
+
+fn default_lease_ttl() -> u64 {
+    60
+}
+
+fn default_failover_timeout() -> u64 {
+    10
+}
+
+fn default_ws_connections() -> usize {
+    2
+}
+
+fn default_ws_max_channels() -> usize {
+    400
+}
+
+fn default_ws_requests_per_second() -> u32 {
+    50
+}
+
+fn default_ws_idle_timeout() -> u64 {
+    60
+}
+
+fn default_ws_stale_timeout() -> u64 {
+    120
+}
+
+fn default_ws_stale_channels() -> Vec<String> {
+    vec!["ticker".to_string(), "book".to_string()]
+}
+
+fn default_shutdown_timeout() -> u64 {
+    20
+}
+
 pub struct AppConfig {
     pub http: server::HttpConfig,
     pub nats: client::NatsConfig,
@@ -40,3 +144,40 @@ pub fn load_config() -> Result<AppConfig> {
         exchange,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::config::ExchangeConfig;
+    use config::{Config, File, FileFormat};
+
+    const MINIMAL: &str = r#"
+        ws_url = "wss://stream.crypto.com/exchange/v1/market"
+        markets_url = "https://api.crypto.com/exchange/v1/public/get-instruments"
+        markets = "*_*"
+        markets_refresh_interval = 60
+        max_concurrency = 10
+        max_buffer_size = 100
+    "#;
+
+    #[test]
+    fn deserialize_should_default_optional_fields() {
+        let config: ExchangeConfig = Config::builder()
+            .add_source(File::from_str(MINIMAL, FileFormat::Toml))
+            .build()
+            .and_then(Config::try_deserialize)
+            .expect("exchange config");
+
+        assert!(config.preload_channels.is_empty());
+        assert!(!config.sharding);
+        assert!(!config.standby);
+        assert_eq!(config.lease_ttl, 60);
+        assert_eq!(config.failover_timeout, 10);
+        assert_eq!(config.ws_connections, 2);
+        assert_eq!(config.ws_max_channels, 400);
+        assert_eq!(config.ws_requests_per_second, 50);
+        assert_eq!(config.ws_idle_timeout, 60);
+        assert_eq!(config.ws_stale_timeout, 120);
+        assert_eq!(config.ws_stale_channels, vec!["ticker", "book"]);
+        assert_eq!(config.shutdown_timeout, 20);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod adapter;
 pub mod book;
 pub mod client;
 pub mod config;
@@ -6,4 +7,3 @@ pub mod model;
 pub mod ticker;
 pub mod topics;
 pub mod trades;
-mod utils;
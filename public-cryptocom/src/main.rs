@@ -1,16 +1,34 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use connector::cache::{MarketsCache, MarketsHealthCheck};
+use connector::coordinator::Coordinator;
 use connector::http_client::HttpClient;
-use connector::utils::check::nats_healthcheck;
+use connector::leader::{Election, Leader};
+use connector::listing::SharedListing;
+use connector::shard::{Membership, Shard};
+use connector::shutdown::{self, Draining};
+use connector::stream::admin::AdminHook;
+use connector::supervisor::Supervisor;
+use connector::utils::check::{nats_healthcheck, ExchangeHealthCheck};
 use connector::utils::tracing;
 use http::healthcheck::service::HealthcheckService;
 use http::server::{base_router, HttpConfig};
+use http::subscriptions::service::SubscriptionsService;
+use log::{info, warn};
 use protocol::client::NatsClient;
-use public_cryptocom::client::ws_client::WsClient;
-use public_cryptocom::config::{load_config, AppConfig};
+use protocol::public::types::Exchange;
+use public_cryptocom::client::ws_client::{WsClient, WsHealthCheck};
+use public_cryptocom::config::{load_config, AppConfig, ExchangeConfig};
 use public_cryptocom::{book, markets, ticker, trades};
+use reqwest::Url;
+use std::future::pending;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::select;
+use tokio::time::timeout;
+
+const EXCHANGE_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const EXCHANGE_PROBE_INTERVAL: Duration = Duration::from_secs(60);
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -22,32 +40,251 @@ async fn main() -> Result<()> {
     let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&config.nats).await?);
     let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&config.exchange)?);
 
-    let healthcheck: HealthcheckService = nats_healthcheck(nats_client.clone());
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    let (membership, shard): (Option<Membership>, Shard) =
+        join_sharding(&nats_client, &config.exchange).await?;
+    let (election, leader): (Option<Election>, Leader) =
+        join_election(&nats_client, &config.exchange).await?;
+
+    let markets_url: Url = Url::parse(&config.exchange.markets_url)?;
+    let markets_age: Duration = Duration::from_secs(config.exchange.markets_refresh_interval * 3);
+    let listing: Option<Arc<SharedListing>> =
+        share_listing(&nats_client, &config.exchange, markets_age).await?;
+    let draining: Draining = Draining::default();
+    let supervisor: Supervisor = Supervisor::default();
+    let ticker_admin: AdminHook = AdminHook::new(ticker::stream::CHANNEL);
+    let trades_admin: AdminHook = AdminHook::new(trades::stream::CHANNEL);
+    let books_admin: AdminHook = AdminHook::new(book::stream::CHANNEL);
+
+    let markets_stream_task = supervisor.supervise("markets", || {
+        markets::stream::run(
+            nats_client.clone(),
+            http_client.clone(),
+            cache.clone(),
+            Coordinator::new(leader.clone(), shard.clone()),
+            listing.clone(),
+            &config.exchange,
+        )
+    });
+    let ticker_stream_task = supervisor.supervise("ticker", || {
+        ticker::stream::run(
+            nats_client.clone(),
+            ws_client.clone(),
+            cache.clone(),
+            shard.clone(),
+            leader.clone(),
+            ticker_admin.clone(),
+            &config.exchange,
+        )
+    });
+    let trades_stream_task = supervisor.supervise("trades", || {
+        trades::stream::run(
+            nats_client.clone(),
+            ws_client.clone(),
+            cache.clone(),
+            shard.clone(),
+            leader.clone(),
+            trades_admin.clone(),
+            &config.exchange,
+        )
+    });
+    let books_stream_task = supervisor.supervise("book", || {
+        book::stream::run(
+            nats_client.clone(),
+            ws_client.clone(),
+            cache.clone(),
+            shard.clone(),
+            leader.clone(),
+            books_admin.clone(),
+            &config.exchange,
+        )
+    });
+    let ws_task = supervisor.supervise("ws", || ws_client.run());
+    let server_task = supervisor.supervise("http", || {
+        let markets: MarketsHealthCheck = MarketsHealthCheck::new(cache.clone(), markets_age);
+        let exchange: ExchangeHealthCheck = ExchangeHealthCheck::new(
+            http_client.clone(),
+            markets_url.clone(),
+            EXCHANGE_PROBE_TIMEOUT,
+            EXCHANGE_PROBE_INTERVAL,
+        );
+        let healthcheck: HealthcheckService = healthcheck(
+            nats_client.clone(),
+            ws_client.clone(),
+            markets,
+            exchange,
+            draining.clone(),
+            supervisor.clone(),
+        );
+        let mut subscriptions: SubscriptionsService = SubscriptionsService::default();
+        subscriptions.add(Box::new(ticker_admin.clone()));
+        subscriptions.add(Box::new(trades_admin.clone()));
+        subscriptions.add(Box::new(books_admin.clone()));
+        run_server(&config.http, healthcheck, subscriptions)
+    });
+
+    // streams stop accepting requests on signal, websocket and http keep running until drained
+    let streams_task = async {
+        select! {
+            task = markets_stream_task => task,
+            task = ticker_stream_task => task,
+            task = trades_stream_task => task,
+            task = books_stream_task => task,
+            task = run_membership(membership.as_ref()) => task,
+            task = run_election(election.as_ref()) => task,
+            signal = shutdown::signal() => signal,
+        }?;
+
+        let grace_period: Duration = Duration::from_secs(config.exchange.shutdown_timeout);
+        let drain_task = drain(
+            &draining,
+            &nats_client,
+            &ws_client,
+            membership.as_ref(),
+            election.as_ref(),
+        );
 
-    // maybe tokio spawn?
-    let markets_stream_task =
-        markets::stream::run(nats_client.clone(), http_client.clone(), &config.exchange);
-    let ticker_stream_task =
-        ticker::stream::run(nats_client.clone(), ws_client.clone(), &config.exchange);
-    let trades_stream_task =
-        trades::stream::run(nats_client.clone(), ws_client.clone(), &config.exchange);
-    let books_stream_task =
-        book::stream::run(nats_client.clone(), ws_client.clone(), &config.exchange);
+        match timeout(grace_period, drain_task).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!("Graceful shutdown exceeded {:?}", grace_period);
+                Ok(())
+            }
+        }
+    };
 
     select! {
-        ws = ws_client.run() => ws?,
-        task = markets_stream_task => task?,
-        task = ticker_stream_task => task?,
-        task = trades_stream_task => task?,
-        task = books_stream_task => task?,
-        task = run_server(&config.http, healthcheck) => task?,
+        ws = ws_task => ws?,
+        task = streams_task => task?,
+        task = server_task => task?,
     }
 
+    info!("Connector stopped");
+    tracing::shutdown();
     Ok(())
 }
 
-async fn run_server(config: &HttpConfig, service: HealthcheckService) -> Result<()> {
-    let router = base_router(service);
+/// Steps are best effort, nats is always flushed and websocket closed
+/// even when key value store is not reachable
+async fn drain(
+    draining: &Draining,
+    nats_client: &NatsClient,
+    ws_client: &WsClient,
+    membership: Option<&Membership>,
+    election: Option<&Election>,
+) -> Result<()> {
+    draining.start();
+
+    if let Some(election) = election {
+        if let Err(error) = election.resign().await {
+            warn!("Cannot resign leadership: {}", error);
+        }
+    }
+
+    if let Some(membership) = membership {
+        if let Err(error) = membership.leave().await {
+            warn!("Cannot leave sharding membership: {}", error);
+        }
+    }
+
+    if let Err(error) = ws_client.unsubscribe_all() {
+        warn!("Cannot unsubscribe websocket channels: {}", error);
+    }
+
+    if let Err(error) = nats_client.flush().await {
+        warn!("Cannot flush nats messages: {}", error);
+    }
+
+    ws_client.close().await
+}
+
+async fn join_sharding(
+    nats_client: &NatsClient,
+    config: &ExchangeConfig,
+) -> Result<(Option<Membership>, Shard)> {
+    if config.sharding {
+        let replica: String = config.replica_id.clone();
+        let (membership, shard): (Membership, Shard) =
+            Membership::join(nats_client, Exchange::Cryptocom, replica).await?;
+        Ok((Some(membership), shard))
+    } else {
+        Ok((None, Shard::default()))
+    }
+}
+
+/// Replicas other than the coordinator take markets from the listing shared over nats
+async fn share_listing(
+    nats_client: &NatsClient,
+    config: &ExchangeConfig,
+    max_age: Duration,
+) -> Result<Option<Arc<SharedListing>>> {
+    if config.sharding || config.standby {
+        let listing: SharedListing =
+            SharedListing::open(nats_client, Exchange::Cryptocom, max_age).await?;
+        Ok(Some(Arc::new(listing)))
+    } else {
+        Ok(None)
+    }
+}
+
+async fn run_membership(membership: Option<&Membership>) -> Result<()> {
+    match membership {
+        Some(membership) => membership.run().await,
+        None => pending().await,
+    }
+}
+
+async fn join_election(
+    nats_client: &NatsClient,
+    config: &ExchangeConfig,
+) -> Result<(Option<Election>, Leader)> {
+    if config.standby && config.sharding {
+        bail!("Standby mode cannot be combined with markets sharding")
+    }
+
+    if config.standby {
+        let replica: String = config.replica_id.clone();
+        let timeout: Duration = Duration::from_secs(config.failover_timeout);
+        let (election, leader): (Election, Leader) =
+            Election::join(nats_client, Exchange::Cryptocom, replica, timeout).await?;
+        Ok((Some(election), leader))
+    } else {
+        Ok((None, Leader::default()))
+    }
+}
+
+async fn run_election(election: Option<&Election>) -> Result<()> {
+    match election {
+        Some(election) => election.run().await,
+        None => pending().await,
+    }
+}
+
+/// All subsystems including supervised tasks only affect readiness,
+/// restarting tasks is left to the supervisor instead of killing the process
+fn healthcheck(
+    nats_client: Arc<NatsClient>,
+    ws_client: Arc<WsClient>,
+    markets: MarketsHealthCheck,
+    exchange: ExchangeHealthCheck,
+    draining: Draining,
+    supervisor: Supervisor,
+) -> HealthcheckService {
+    let mut healthcheck: HealthcheckService = nats_healthcheck(nats_client);
+    healthcheck.add(Box::new(WsHealthCheck::new(ws_client)));
+    healthcheck.add(Box::new(markets));
+    healthcheck.add(Box::new(exchange));
+    healthcheck.add(Box::new(draining));
+    healthcheck.add(Box::new(supervisor));
+    healthcheck
+}
+
+async fn run_server(
+    config: &HttpConfig,
+    service: HealthcheckService,
+    subscriptions: SubscriptionsService,
+) -> Result<()> {
+    let router = base_router(service, subscriptions);
 
     let listener: TcpListener = TcpListener::bind(config.address())
         .await
@@ -1,37 +1,57 @@
 use crate::client::response::{ExchangeError, ExchangeResponse, HttpResult};
 use crate::config::ExchangeConfig;
 use crate::markets::models::Instrument;
+use crate::topics;
 use anyhow::{anyhow, Result};
 use async_nats::Subject;
 use chrono::Utc;
+use connector::cache::{CachedMarkets, MarketsCache};
+use connector::coordinator::Coordinator;
 use connector::decoder::NatsEvent;
+use connector::filter::filter;
 use connector::http_client::HttpClient;
-use log::info;
+use connector::listing::SharedListing;
+use log::{info, warn};
 use protocol::client::NatsClient;
 use protocol::public::error::ErrorMessage;
-use protocol::public::market::{Market, MarketsMessage, MarketsRequest};
+use protocol::public::market::{
+    Market, MarketChange, MarketsChangesMessage, MarketsMessage, MarketsRequest,
+};
 use protocol::public::types::Exchange;
+use protocol::topics::ChangesTopic;
 use reqwest::Url;
 use std::sync::Arc;
+use std::time::Duration;
 
 type InstrumentsResponse = ExchangeResponse<HttpResult<Instrument>>;
 
 pub struct RequestHandler {
     http_client: Arc<HttpClient>,
     nats_client: Arc<NatsClient>,
+    cache: Arc<MarketsCache>,
+    coordinator: Coordinator,
+    listing: Option<Arc<SharedListing>>,
     markets_url: Url,
+    refresh_interval: Duration,
 }
 
 impl RequestHandler {
     pub fn new(
         http_client: Arc<HttpClient>,
         nats_client: Arc<NatsClient>,
+        cache: Arc<MarketsCache>,
+        coordinator: Coordinator,
+        listing: Option<Arc<SharedListing>>,
         config: &ExchangeConfig,
     ) -> Result<Self> {
         Ok(RequestHandler {
             http_client,
             nats_client,
+            cache,
+            coordinator,
+            listing,
             markets_url: Url::parse(&config.markets_url)?,
+            refresh_interval: Duration::from_secs(config.markets_refresh_interval),
         })
     }
 
@@ -44,8 +64,61 @@ impl RequestHandler {
         }
     }
 
+    /// Reloads markets and publishes listing changes. Used by the periodic refresh task.
+    /// Only the coordinator calls the exchange, other replicas take the shared listing.
+    pub async fn refresh(&self) {
+        match &self.listing {
+            Some(listing) if !self.coordinator.is_active() => match listing.get().await {
+                Ok(message) => {
+                    self.cache.update_at(Ok(message.markets), message.timestamp);
+                }
+                Err(error) => {
+                    self.cache.update(Err(error));
+                }
+            },
+            _ => self.reload().await,
+        }
+    }
+
+    async fn reload(&self) {
+        let result: Result<Vec<Market>, ErrorMessage> = self.call_api().await;
+
+        if let (Some(listing), Ok(markets)) = (&self.listing, &result) {
+            if let Err(error) = listing.put(markets).await {
+                warn!("Cannot share markets listing: {}", error)
+            }
+        }
+
+        let changes: Vec<MarketChange> = self.cache.update(result);
+
+        if !changes.is_empty() && self.coordinator.is_active() {
+            self.publish_changes(changes).await
+        }
+    }
+
+    async fn publish_changes(&self, changes: Vec<MarketChange>) {
+        info!("Publishing {} markets changes", changes.len());
+
+        let message: MarketsChangesMessage = MarketsChangesMessage {
+            timestamp: Utc::now().timestamp_millis(),
+            exchange: Exchange::Cryptocom as i32,
+            changes,
+        };
+
+        let topic: ChangesTopic = topics::markets().changes();
+
+        if let Err(error) = self.nats_client.send_message(topic, message).await {
+            warn!("Cannot publish markets changes: {}", error)
+        }
+    }
+
     async fn get_markets(&self, request: MarketsRequest, reply_topic: Subject) -> Result<()> {
-        let response: Result<MarketsMessage, ErrorMessage> = self.call_api(request).await;
+        self.cache
+            .load(self.refresh_interval, || self.refresh())
+            .await;
+
+        let response: Result<MarketsMessage, ErrorMessage> =
+            self.cache.get().map(|cached| to_message(cached, request));
 
         match response {
             Ok(markets_message) => self
@@ -61,37 +134,21 @@ impl RequestHandler {
         }
     }
 
-    async fn call_api(&self, request: MarketsRequest) -> Result<MarketsMessage, ErrorMessage> {
+    async fn call_api(&self) -> Result<Vec<Market>, ErrorMessage> {
         self.http_client
             .get::<InstrumentsResponse, ExchangeError>(&self.markets_url)
             .await
             .map(|response| response.result.data)
-            .map(|instruments| filter(instruments, request))
-            .map(to_message)
+            .map(|instruments| instruments.iter().map(Market::from).collect())
     }
 }
 
-fn to_message(markets: Vec<Market>) -> MarketsMessage {
+fn to_message(cached: CachedMarkets, request: MarketsRequest) -> MarketsMessage {
     MarketsMessage {
         timestamp: Utc::now().timestamp_millis(),
         exchange: Exchange::Cryptocom as i32,
-        markets,
+        markets: filter(cached.markets, &request),
+        stale: cached.stale,
+        cache_age: cached.age,
     }
 }
-
-fn filter(instruments: Vec<Instrument>, request: MarketsRequest) -> Vec<Market> {
-    if request.symbols.is_empty() {
-        instruments.iter().map(Market::from).collect()
-    } else {
-        instruments
-            .iter()
-            .map(Market::from)
-            .filter(|market| request.symbols.contains(&market.symbol))
-            .filter(|market| check(request.market_type, market.market_type))
-            .collect()
-    }
-}
-
-fn check(opt: Option<i32>, market_type: i32) -> bool {
-    opt.map_or(true, |t| t == market_type)
-}
@@ -1,4 +1,4 @@
-use protocol::public::market::{Market, MarketType};
+use protocol::public::market::{Market, MarketStatus, MarketType};
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use std::cmp::PartialEq;
@@ -27,6 +27,8 @@ impl From<&InstType> for MarketType {
 
 #[derive(Deserialize)]
 pub struct Instrument {
+    #[serde(rename = "symbol")]
+    instrument_name: String,
     base_ccy: String,
     quote_ccy: String,
     inst_type: InstType,
@@ -35,6 +37,9 @@ pub struct Instrument {
     price_tick_size: Decimal,
     qty_tick_size: Decimal,
     expiry_timestamp_ms: i64,
+    tradable: bool,
+    #[serde(default)]
+    contract_size: Option<Decimal>,
 }
 
 impl Instrument {
@@ -49,8 +54,35 @@ impl Instrument {
             Some(self.expiry_timestamp_ms)
         }
     }
+
+    fn is_derivative(&self) -> bool {
+        self.inst_type != InstType::CcyPair
+    }
+
+    /// Derivatives are settled in quote currency
+    fn settlement_currency(&self) -> Option<String> {
+        self.is_derivative().then(|| self.quote_ccy.to_lowercase())
+    }
+
+    fn contract_size(&self) -> Option<String> {
+        if self.is_derivative() {
+            Some(self.contract_size.unwrap_or(Decimal::ONE).to_string())
+        } else {
+            None
+        }
+    }
+
+    fn status(&self) -> MarketStatus {
+        if self.tradable {
+            MarketStatus::Online
+        } else {
+            MarketStatus::Offline
+        }
+    }
 }
 
+/// Crypto.com does not publish max order size, price limits and min notional.
+/// Minimal order quantity is equal to quantity tick size.
 impl From<&Instrument> for Market {
     fn from(instrument: &Instrument) -> Self {
         Market {
@@ -59,21 +91,103 @@ impl From<&Instrument> for Market {
             rate_precision: instrument.quote_decimals,
             size_precision: instrument.quantity_decimals,
             min_size: instrument.qty_tick_size.to_string(),
-            max_size: i64::MAX.to_string(),
-            min_price: instrument.price_tick_size.to_string(),
-            max_price: i64::MAX.to_string(),
+            max_size: String::new(),
+            min_price: String::new(),
+            max_price: String::new(),
             market_type: MarketType::from(&instrument.inst_type) as i32,
             expiry_timestamp: instrument.expiry_timestamp(),
+            price_tick_size: instrument.price_tick_size.to_string(),
+            size_tick_size: instrument.qty_tick_size.to_string(),
+            min_notional: None,
+            base_currency: instrument.base_ccy.to_lowercase(),
+            quote_currency: instrument.quote_ccy.to_lowercase(),
+            contract_size: instrument.contract_size(),
+            settlement_currency: instrument.settlement_currency(),
+            status: instrument.status() as i32,
+            instrument_id: instrument.instrument_name.clone(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::markets::models::InstType;
+    use crate::markets::models::{InstType, Instrument};
+    use protocol::public::market::{Market, MarketStatus, MarketType};
     use serde_json;
     use serde_json::from_str;
 
+    const SPOT: &str = r#"{
+        "symbol": "BTC_USD",
+        "inst_type": "CCY_PAIR",
+        "display_name": "BTC/USD",
+        "base_ccy": "BTC",
+        "quote_ccy": "USD",
+        "quote_decimals": 2,
+        "quantity_decimals": 5,
+        "price_tick_size": "0.01",
+        "qty_tick_size": "0.00001",
+        "max_leverage": "50",
+        "tradable": true,
+        "expiry_timestamp_ms": 0,
+        "beta_product": false,
+        "margin_buy_enabled": true,
+        "margin_sell_enabled": true
+    }"#;
+
+    const FUTURE: &str = r#"{
+        "symbol": "BTCUSD-250328",
+        "inst_type": "FUTURE",
+        "display_name": "BTCUSD Futures 20250328",
+        "base_ccy": "BTC",
+        "quote_ccy": "USD",
+        "quote_decimals": 1,
+        "quantity_decimals": 4,
+        "price_tick_size": "0.5",
+        "qty_tick_size": "0.0001",
+        "max_leverage": "100",
+        "tradable": false,
+        "expiry_timestamp_ms": 1743148800000,
+        "underlying_symbol": "BTCUSD-INDEX",
+        "contract_size": "1"
+    }"#;
+
+    #[test]
+    fn from_instrument_should_return_spot_market() {
+        let instrument: Instrument = from_str(SPOT).expect("spot instrument");
+
+        let market: Market = Market::from(&instrument);
+
+        assert_eq!(market.symbol, "btc_usd");
+        assert_eq!(market.instrument_id, "BTC_USD");
+        assert_eq!(market.market_type, MarketType::Spot as i32);
+        assert_eq!(market.min_size, "0.00001");
+        assert_eq!(market.min_price, "");
+        assert_eq!(market.max_size, "");
+        assert_eq!(market.max_price, "");
+        assert_eq!(market.price_tick_size, "0.01");
+        assert_eq!(market.size_tick_size, "0.00001");
+        assert_eq!(market.base_currency, "btc");
+        assert_eq!(market.quote_currency, "usd");
+        assert_eq!(market.contract_size, None);
+        assert_eq!(market.settlement_currency, None);
+        assert_eq!(market.status, MarketStatus::Online as i32);
+    }
+
+    #[test]
+    fn from_instrument_should_return_future_market() {
+        let instrument: Instrument = from_str(FUTURE).expect("future instrument");
+
+        let market: Market = Market::from(&instrument);
+
+        assert_eq!(market.instrument_id, "BTCUSD-250328");
+        assert_eq!(market.market_type, MarketType::Future as i32);
+        assert_eq!(market.expiry_timestamp, Some(1743148800000));
+        assert_eq!(market.price_tick_size, "0.5");
+        assert_eq!(market.contract_size, Some("1".to_string()));
+        assert_eq!(market.settlement_currency, Some("usd".to_string()));
+        assert_eq!(market.status, MarketStatus::Offline as i32);
+    }
+
     #[test]
     fn deserialize_should_return_inst_type() {
         let ccy_pair = from_str(r#""CCY_PAIR""#);
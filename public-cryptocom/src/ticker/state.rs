@@ -1,11 +1,12 @@
+use crate::adapter::CryptocomAdapter;
 use crate::client::request::Channel;
 use crate::model::Market;
 use crate::ticker::models::Ticker;
 use crate::topics;
-use crate::utils::state::State;
 use anyhow::Result;
 use async_nats::subject::ToSubject;
 use async_nats::Subject;
+use connector::stream::state::State;
 use protocol::public::ticker::{Tick, TickerMessage};
 use protocol::public::types::{Exchange, MessageType};
 
@@ -23,7 +24,7 @@ impl Default for TickerState {
     }
 }
 
-impl State<Ticker, TickerMessage> for TickerState {
+impl State<CryptocomAdapter, Ticker, TickerMessage> for TickerState {
     fn update(&mut self, dto: Ticker) -> Result<TickerMessage> {
         self.sequence += 1;
         self.state = Tick::from(&dto);
@@ -39,6 +40,8 @@ impl State<Ticker, TickerMessage> for TickerState {
             sequence: self.sequence,
             exchange: Exchange::Cryptocom as i32,
             tick: Some(self.state.clone()),
+            latency: None,
+            epoch: 0,
         })
     }
 
@@ -48,9 +51,23 @@ impl State<Ticker, TickerMessage> for TickerState {
             sequence: self.sequence,
             exchange: Exchange::Cryptocom as i32,
             tick: Some(self.state.clone()),
+            latency: None,
+            epoch: 0,
         }
     }
 
+    fn sequence(&self) -> i64 {
+        self.sequence
+    }
+
+    fn set_sequence(&mut self, sequence: i64) {
+        self.sequence = sequence;
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        Some(self.state.timestamp)
+    }
+
     fn topic(&self, market: &Market) -> Subject {
         topics::ticker(market).to_subject()
     }
@@ -59,3 +76,42 @@ impl State<Ticker, TickerMessage> for TickerState {
         Channel::Ticker
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ticker::models::Ticker;
+    use crate::ticker::state::TickerState;
+    use connector::stream::state::State;
+    use protocol::public::ticker::TickerMessage;
+    use protocol::public::types::MessageType;
+    use rust_decimal::Decimal;
+
+    fn ticker(t: i64) -> Ticker {
+        Ticker {
+            b: Decimal::ONE,
+            bs: Decimal::ONE,
+            k: Decimal::TWO,
+            ks: Decimal::ONE,
+            i: "BTC_USD".to_string(),
+            t,
+        }
+    }
+
+    #[test]
+    fn restart_should_publish_snapshot_with_new_sequence() {
+        let mut state: TickerState = TickerState::default();
+
+        assert_eq!(state.restart(), None);
+
+        state.update(ticker(1)).unwrap();
+        state.update(ticker(2)).unwrap();
+        let restarted: Option<TickerMessage> = state.restart();
+
+        assert_eq!(restarted.as_ref().map(|message| message.sequence), Some(0));
+        assert_eq!(
+            restarted.map(|message| message.r#type),
+            Some(MessageType::Snapshot as i32)
+        );
+        assert_eq!(state.update(ticker(3)).unwrap().sequence, 1);
+    }
+}
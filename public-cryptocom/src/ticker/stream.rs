@@ -1,57 +1,89 @@
+use crate::adapter::CryptocomAdapter;
+use crate::client::request::Channel;
 use crate::client::response::WsResult;
 use crate::client::ws_client::WsClient;
 use crate::config::ExchangeConfig;
+use crate::model::Market;
 use crate::ticker::models::Ticker;
 use crate::ticker::state::TickerState;
 use crate::topics;
-use crate::utils::handler::Event::Updated;
-use crate::utils::handler::{Event, Handler};
-use crate::utils::stream::handle_nats_subscription;
 use anyhow::Result;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use connector::stream::handler::{Event, Handler};
+use connector::stream::subscription::Router;
 use connector::subscription::NatsSubscription;
+use connector::whitelist::MarketsValidator;
 use log::info;
 use protocol::client::NatsClient;
 use protocol::public::ticker::{TickerMessage, TickerRequest};
-use protocol::topics::{SnapshotTopic, Topic};
+use protocol::public::types::LeaseRequest;
+use protocol::topics::{LeaseTopic, SnapshotTopic, Topic};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::select;
 use tokio::sync::broadcast::Receiver;
 use tokio::sync::mpsc::Sender;
+use tokio::sync::watch;
 
 const QUEUE: &str = "cryptocom.ticker";
+pub const CHANNEL: &str = "ticker";
+
+type TickerHandler = Handler<CryptocomAdapter, Ticker>;
 
 pub async fn run(
     nats_client: Arc<NatsClient>,
     ws_client: Arc<WsClient>,
+    cache: Arc<MarketsCache>,
+    shard: Shard,
+    leader: Leader,
+    admin: AdminHook,
     config: &ExchangeConfig,
 ) -> Result<()> {
     let topic: SnapshotTopic = topics::ticker(&config.markets).snapshot();
+    let lease_topic: LeaseTopic = topics::ticker(&config.markets).lease();
+    let lease_ttl: Duration = Duration::from_secs(config.lease_ttl);
 
     info!("Starting ticker stream processing");
 
-    let nats_subscription: NatsSubscription<TickerRequest> =
-        NatsSubscription::new(&nats_client, topic, QUEUE).await?;
-    let shutdown: Receiver<()> = ws_client.subscribe_shutdown();
+    let validator: Arc<MarketsValidator> =
+        Arc::new(MarketsValidator::new(config.whitelist(), cache));
+    let adapter: Arc<CryptocomAdapter> = Arc::new(CryptocomAdapter::new(ws_client.clone()));
+    let router: Router<CryptocomAdapter> = Router::new(
+        adapter.clone(),
+        validator.clone(),
+        nats_client.clone(),
+        shard.clone(),
+    )
+    .standby(leader.clone());
+
+    let nats_subscription: NatsSubscription<TickerRequest> = router.subscribe(topic, QUEUE).await?;
+    let lease_subscription: NatsSubscription<LeaseRequest> =
+        router.subscribe(lease_topic, QUEUE).await?;
+    let reset: Receiver<usize> = ws_client.subscribe_reset();
+    let connected: watch::Receiver<bool> = ws_client.subscribe_connection();
     let ws_subscription: Receiver<WsResult<Ticker>> = ws_client.subscribe_ticker();
-    let (message_handler, tickers): (Handler<Ticker>, Sender<Event<Ticker>>) =
-        Handler::new(nats_client, ws_client, config);
+    let (message_handler, tickers): (TickerHandler, Sender<Event<Market, Ticker>>) =
+        Handler::new(nats_client, adapter, config.max_buffer_size);
+    let message_handler: TickerHandler = message_handler
+        .preload(config.preload(CHANNEL)?)
+        .expire_after(lease_ttl)
+        .sharded(shard)
+        .standby(leader)
+        .validated(validator)
+        .admin(admin);
 
     select! {
-        result = message_handler.run::<TickerMessage, TickerState>(shutdown) => result,
-        result = handle_nats_subscription(tickers.clone(), nats_subscription) => result,
-        result = handle_ws_subscription(tickers.clone(), ws_subscription) => result
+        result = message_handler.run::<TickerMessage, TickerState>(reset, connected) => result,
+        result = router.snapshots(tickers.clone(), nats_subscription) => result,
+        result = router.leases(tickers.clone(), lease_subscription) => result,
+        result = router.updates(Channel::Ticker, tickers.clone(), ws_subscription, update) => result
     }
 }
 
-async fn handle_ws_subscription(
-    tickers: Sender<Event<Ticker>>,
-    mut subscription: Receiver<WsResult<Ticker>>,
-) -> Result<()> {
-    while let Ok(result) = subscription.recv().await {
-        if let Some(ticker) = result.data.first() {
-            tickers.send(Updated(result.market, ticker.clone())).await?
-        }
-    }
-
-    Ok(())
+fn update(result: WsResult<Ticker>) -> Option<(Market, Ticker)> {
+    let ticker: Ticker = result.data.first()?.clone();
+    Some((result.market, ticker))
 }
@@ -1,11 +1,12 @@
+use crate::adapter::CryptocomAdapter;
 use crate::client::request::Channel;
 use crate::model::Market;
 use crate::topics;
 use crate::trades::models::Transaction;
-use crate::utils::state::State;
 use anyhow::{anyhow, Result};
 use async_nats::subject::ToSubject;
 use async_nats::Subject;
+use connector::stream::state::{SequenceGap, State};
 use protocol::public::trade::{Trade, TradesMessage};
 use protocol::public::types::{Exchange, MessageType};
 use rust_decimal::Decimal;
@@ -33,12 +34,12 @@ impl TradesState {
         if self.last_id == Decimal::NEGATIVE_ONE || self.last_id + Decimal::ONE == id {
             Ok(())
         } else {
-            Err(anyhow!("Transaction sequence id missed"))
+            Err(anyhow!(SequenceGap("Transaction")))
         }
     }
 }
 
-impl State<Vec<Transaction>, TradesMessage> for TradesState {
+impl State<CryptocomAdapter, Vec<Transaction>, TradesMessage> for TradesState {
     fn update(&mut self, trades: Vec<Transaction>) -> Result<TradesMessage> {
         if let Some(last_id) = trades.first().map(|tx| tx.m) {
             self.check_last_id(last_id)?;
@@ -63,6 +64,8 @@ impl State<Vec<Transaction>, TradesMessage> for TradesState {
             sequence: self.sequence,
             exchange: Exchange::Cryptocom as i32,
             trades: update,
+            latency: None,
+            epoch: 0,
         })
     }
 
@@ -72,9 +75,23 @@ impl State<Vec<Transaction>, TradesMessage> for TradesState {
             sequence: self.sequence,
             exchange: Exchange::Cryptocom as i32,
             trades: self.state.clone(),
+            latency: None,
+            epoch: 0,
         }
     }
 
+    fn sequence(&self) -> i64 {
+        self.sequence
+    }
+
+    fn set_sequence(&mut self, sequence: i64) {
+        self.sequence = sequence;
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        self.state.first().map(|trade| trade.timestamp)
+    }
+
     fn topic(&self, market: &Market) -> Subject {
         topics::trades(market).to_subject()
     }
@@ -87,3 +104,34 @@ impl State<Vec<Transaction>, TradesMessage> for TradesState {
 fn convert(state: &[Transaction]) -> Vec<Trade> {
     state.iter().map(Trade::from).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::trades::models::{TradeSide, Transaction};
+    use crate::trades::state::TradesState;
+    use connector::stream::state::{SequenceGap, State};
+    use rust_decimal::Decimal;
+
+    fn transaction(m: i64) -> Transaction {
+        Transaction {
+            d: Decimal::from(m),
+            p: Decimal::ONE,
+            q: Decimal::ONE,
+            s: TradeSide::Buy,
+            t: 1736286461888,
+            m: Decimal::from(m),
+        }
+    }
+
+    #[test]
+    fn update_should_fail_with_sequence_gap_on_missed_transaction() {
+        let mut state: TradesState = TradesState::default();
+
+        assert!(state.update(vec![transaction(1)]).is_ok());
+        assert!(state.update(vec![transaction(2)]).is_ok());
+
+        let error = state.update(vec![transaction(4)]).unwrap_err();
+
+        assert!(error.is::<SequenceGap>());
+    }
+}
@@ -1,59 +1,92 @@
+use crate::adapter::CryptocomAdapter;
+use crate::client::request::Channel;
 use crate::client::response::WsResult;
 use crate::client::ws_client::WsClient;
 use crate::config::ExchangeConfig;
+use crate::model::Market;
 use crate::topics;
 use crate::trades::models::Transaction;
 use crate::trades::state::TradesState;
-use crate::utils::handler::Event::Updated;
-use crate::utils::handler::{Event, Handler};
-use crate::utils::stream::handle_nats_subscription;
 use anyhow::Result;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use connector::stream::handler::{Event, Handler};
+use connector::stream::subscription::Router;
 use connector::subscription::NatsSubscription;
+use connector::whitelist::MarketsValidator;
 use log::info;
 use protocol::client::NatsClient;
 use protocol::public::trade::{TradesMessage, TradesRequest};
-use protocol::topics::{SnapshotTopic, Topic};
+use protocol::public::types::LeaseRequest;
+use protocol::topics::{LeaseTopic, SnapshotTopic, Topic};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::select;
 use tokio::sync::broadcast::Receiver;
 use tokio::sync::mpsc::Sender;
+use tokio::sync::watch;
 
 const QUEUE: &str = "cryptocom.trades";
+pub const CHANNEL: &str = "trades";
+
+type TradesHandler = Handler<CryptocomAdapter, Vec<Transaction>>;
 
 pub async fn run(
     nats_client: Arc<NatsClient>,
     ws_client: Arc<WsClient>,
+    cache: Arc<MarketsCache>,
+    shard: Shard,
+    leader: Leader,
+    admin: AdminHook,
     config: &ExchangeConfig,
 ) -> Result<()> {
     let topic: SnapshotTopic = topics::trades(&config.markets).snapshot();
+    let lease_topic: LeaseTopic = topics::trades(&config.markets).lease();
+    let lease_ttl: Duration = Duration::from_secs(config.lease_ttl);
 
     info!("Starting trades stream processing");
 
-    let nats_subscription: NatsSubscription<TradesRequest> =
-        NatsSubscription::new(&nats_client, topic, QUEUE).await?;
-    let shutdown: Receiver<()> = ws_client.subscribe_shutdown();
+    let validator: Arc<MarketsValidator> =
+        Arc::new(MarketsValidator::new(config.whitelist(), cache));
+    let adapter: Arc<CryptocomAdapter> = Arc::new(CryptocomAdapter::new(ws_client.clone()));
+    let router: Router<CryptocomAdapter> = Router::new(
+        adapter.clone(),
+        validator.clone(),
+        nats_client.clone(),
+        shard.clone(),
+    )
+    .standby(leader.clone());
+
+    let nats_subscription: NatsSubscription<TradesRequest> = router.subscribe(topic, QUEUE).await?;
+    let lease_subscription: NatsSubscription<LeaseRequest> =
+        router.subscribe(lease_topic, QUEUE).await?;
+    let reset: Receiver<usize> = ws_client.subscribe_reset();
+    let connected: watch::Receiver<bool> = ws_client.subscribe_connection();
     let ws_subscription: Receiver<WsResult<Transaction>> = ws_client.subscribe_trade();
-    let (message_handler, trades): (Handler<Vec<Transaction>>, Sender<Event<Vec<Transaction>>>) =
-        Handler::new(nats_client, ws_client, config);
+    let (message_handler, trades): (TradesHandler, Sender<Event<Market, Vec<Transaction>>>) =
+        Handler::new(nats_client, adapter, config.max_buffer_size);
+    let message_handler: TradesHandler = message_handler
+        .preload(config.preload(CHANNEL)?)
+        .expire_after(lease_ttl)
+        .sharded(shard)
+        .standby(leader)
+        .validated(validator)
+        .admin(admin);
 
     select! {
-        result = message_handler.run::<TradesMessage, TradesState>(shutdown) => result,
-        result = handle_nats_subscription(trades.clone(), nats_subscription) => result,
-        result = handle_ws_subscription(trades.clone(), ws_subscription) => result
+        result = message_handler.run::<TradesMessage, TradesState>(reset, connected) => result,
+        result = router.snapshots(trades.clone(), nats_subscription) => result,
+        result = router.leases(trades.clone(), lease_subscription) => result,
+        result = router.updates(Channel::Trade, trades.clone(), ws_subscription, update) => result
     }
 }
 
-async fn handle_ws_subscription(
-    tickers: Sender<Event<Vec<Transaction>>>,
-    mut subscription: Receiver<WsResult<Transaction>>,
-) -> Result<()> {
-    while let Ok(result) = subscription.recv().await {
-        if !result.data.is_empty() {
-            tickers
-                .send(Updated(result.market.clone(), result.data))
-                .await?
-        }
+fn update(result: WsResult<Transaction>) -> Option<(Market, Vec<Transaction>)> {
+    if result.data.is_empty() {
+        None
+    } else {
+        Some((result.market, result.data))
     }
-
-    Ok(())
 }
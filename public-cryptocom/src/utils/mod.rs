@@ -1,3 +0,0 @@
-pub mod handler;
-pub mod state;
-pub mod stream;
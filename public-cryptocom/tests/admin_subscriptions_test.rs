@@ -0,0 +1,118 @@
+use anyhow::Result;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::scenario::Scenario;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use http::models::errors::ErrorCode;
+use http::subscriptions::hook::{Action, SubscriptionInfo, SubscriptionsHook};
+use protocol::client::{NatsClient, NatsConfig};
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use public_cryptocom::ticker;
+use std::sync::Arc;
+
+mod common;
+
+const FIRST: &str = r#"{
+  "id": 1,
+  "method": "subscribe",
+  "code": 0,
+  "result": {
+    "instrument_name": "BTC_USD",
+    "subscription": "ticker.BTC_USD",
+    "channel": "ticker",
+    "data": [
+      {
+        "h": "102780.56",
+        "l": "96109.81",
+        "a": "96441.70",
+        "c": "-0.0526",
+        "b": "96447.99",
+        "bs": "1.68000",
+        "k": "96448.00",
+        "ks": "0.12219",
+        "i": "BTC_USD",
+        "v": "28786.2439",
+        "vv": "2836123068.86",
+        "oi": "0",
+        "t": 1736286461888
+      }
+    ]
+  }
+}"#;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    ExchangeConfig {
+        preload_markets: vec!["btc_usd".to_string()],
+        preload_channels: vec!["ticker".to_string()],
+        denied_markets: vec!["eth_usd".to_string()],
+        ..common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+    }
+}
+
+#[tokio::test]
+async fn admin_unsubscribe_preloaded_market() -> Result<()> {
+    let scenario: Scenario = Scenario::default().expect("subscribe").send(FIRST);
+    let sim: SimServer = ExchangeSim::default().ws(scenario).start().await?;
+
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+
+    let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+    let admin: AdminHook = AdminHook::new("ticker");
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move {
+        ws.run().await.expect("running ws stream");
+    });
+
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    let hook: AdminHook = admin.clone();
+    tokio::task::spawn(async move {
+        ticker::stream::run(
+            nats_client,
+            ws_client,
+            cache,
+            Shard::default(),
+            Leader::default(),
+            hook,
+            &exchange_config,
+        )
+        .await
+        .expect("running ticker stream");
+    });
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+    let subscriptions: Vec<SubscriptionInfo> = admin.subscriptions().await;
+
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0].market, "btc_usd");
+    assert_eq!(subscriptions[0].sequence, 0);
+    assert_eq!(subscriptions[0].messages, 1);
+
+    admin
+        .execute(Action::Unsubscribe, "btc_usd")
+        .await
+        .expect("unsubscribed market");
+
+    let error = admin.execute(Action::Unsubscribe, "btc_usd").await.err();
+    let denied = admin.execute(Action::Subscribe, "eth_usd").await.err();
+
+    assert!(admin.subscriptions().await.is_empty());
+    assert!(matches!(
+        error.map(|error| error.code),
+        Some(ErrorCode::NotFound)
+    ));
+    assert!(matches!(
+        denied.map(|error| error.code),
+        Some(ErrorCode::BadRequest)
+    ));
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use anyhow::Result;
+use connector::cache::MarketsCache;
+use connector::coordinator::Coordinator;
 use connector::http_client::HttpClient;
-use mockito::{Server, ServerGuard};
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::server::{ExchangeSim, Reply, SimServer};
 use prost::Message;
 use protocol::client::{NatsClient, NatsConfig};
 use protocol::public::market::{MarketType, MarketsMessage, MarketsRequest};
@@ -8,9 +11,10 @@ use protocol::public::types::Exchange;
 use protocol::topics::RequestTopic;
 use public_cryptocom::config::ExchangeConfig;
 use public_cryptocom::markets;
-use public_cryptocom::model::Market;
 use std::sync::Arc;
 
+mod common;
+
 const OK_BODY: &str = r#"{
   "id": -1,
   "method": "public/get-instruments",
@@ -55,46 +59,46 @@ const OK_BODY: &str = r#"{
   }
 }"#;
 
-fn nats_conf() -> NatsConfig {
-    NatsConfig {
-        host: "0.0.0.0".to_string(),
-        port: 4222,
-        max_reconnects: 0,
-    }
-}
-
-fn exchange_conf(server: &ServerGuard) -> ExchangeConfig {
-    ExchangeConfig {
-        ws_url: format!("{}/ws", server.url()),
-        markets_url: format!("{}/markets", server.url()),
-        markets: Market::new("*".to_string(), "*".to_string()),
-        max_concurrency: 2,
-        max_buffer_size: 10,
-    }
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
 }
 
 #[tokio::test]
 async fn return_all_markets() -> Result<()> {
-    let mut server: ServerGuard = Server::new_async().await;
-    let _ = server.mock("GET", "/markets").with_body(OK_BODY).create();
+    let sim: SimServer = ExchangeSim::default()
+        .rest("/markets", Reply::ok(OK_BODY))
+        .start()
+        .await?;
 
-    let nats_config: NatsConfig = nats_conf();
-    let exchange_config: ExchangeConfig = exchange_conf(&server);
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
 
     let http_client: Arc<HttpClient> = Arc::new(HttpClient::default());
     let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
 
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
     let nats: Arc<NatsClient> = nats_client.clone();
     tokio::task::spawn(async move {
-        markets::stream::run(nats.clone(), http_client.clone(), &exchange_config)
-            .await
-            .expect("running markets stream");
+        markets::stream::run(
+            nats.clone(),
+            http_client.clone(),
+            cache,
+            Coordinator::default(),
+            None,
+            &exchange_config,
+        )
+        .await
+        .expect("running markets stream");
     });
 
     let subject: RequestTopic = RequestTopic::markets(Exchange::Cryptocom);
     let request: MarketsRequest = MarketsRequest {
         symbols: vec![],
         market_type: Some(MarketType::Spot as i32),
+        base_currencies: vec![],
+        quote_currencies: vec![],
+        patterns: vec![],
     };
 
     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
@@ -105,6 +109,10 @@ async fn return_all_markets() -> Result<()> {
     assert_eq!(response.markets.len(), 2);
     assert_eq!(response.markets[0].symbol, "btc_usd");
     assert_eq!(response.markets[1].symbol, "eth_usd");
+    assert_eq!(response.markets[1].price_tick_size, "0.01");
+    assert_eq!(response.markets[1].size_tick_size, "0.0001");
+    assert_eq!(response.markets[1].base_currency, "eth");
+    assert_eq!(response.markets[1].quote_currency, "usd");
 
     Ok(())
 }
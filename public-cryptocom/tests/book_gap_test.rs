@@ -0,0 +1,75 @@
+use anyhow::Result;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use exchange_sim::cryptocom;
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::scenario::Scenario;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use protocol::client::{NatsClient, NatsConfig};
+use public_cryptocom::book;
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    ExchangeConfig {
+        preload_markets: vec!["btc_usd".to_string()],
+        preload_channels: vec!["book".to_string()],
+        ..common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+    }
+}
+
+#[tokio::test]
+async fn unsubscribe_book_on_sequence_gap() -> Result<()> {
+    let scenario: Scenario = Scenario::default()
+        .expect("subscribe")
+        .send(cryptocom::book_update(
+            "BTC_USD",
+            &[("96450.00", "1.5")],
+            &[],
+            1736286461888,
+            101,
+            100,
+        ));
+    let sim: SimServer = ExchangeSim::default().ws(scenario).start().await?;
+
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+
+    let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move {
+        ws.run().await.expect("running ws stream");
+    });
+
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    tokio::task::spawn(async move {
+        book::stream::run(
+            nats_client,
+            ws_client,
+            cache,
+            Shard::default(),
+            Leader::default(),
+            AdminHook::new("book"),
+            &exchange_config,
+        )
+        .await
+        .expect("running book stream");
+    });
+
+    let unsubscribe: String = sim
+        .wait_for("\"unsubscribe\"", Duration::from_secs(5))
+        .await?;
+
+    assert!(unsubscribe.contains("book.BTC_USD"));
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use exchange_sim::cryptocom;
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::scenario::Scenario;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use http::subscriptions::hook::SubscriptionsHook;
+use protocol::client::{NatsClient, NatsConfig};
+use public_cryptocom::book;
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    ExchangeConfig {
+        preload_markets: vec!["btc_usd".to_string()],
+        preload_channels: vec!["book".to_string()],
+        ..common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+    }
+}
+
+#[tokio::test]
+async fn apply_book_updates_after_snapshot() -> Result<()> {
+    let scenario: Scenario = Scenario::default()
+        .expect("subscribe")
+        .send(cryptocom::book_snapshot(
+            "BTC_USD",
+            &[("96450.00", "1.5"), ("96460.00", "0.25")],
+            &[("96440.00", "2.0")],
+            1736286461888,
+            100,
+        ))
+        .send(cryptocom::book_update(
+            "BTC_USD",
+            &[("96450.00", "0")],
+            &[("96445.00", "0.5")],
+            1736286461905,
+            101,
+            100,
+        ))
+        .send(cryptocom::error(1, "subscribe", 40003, "BAD_INSTRUMENT"));
+    let sim: SimServer = ExchangeSim::default().ws(scenario).start().await?;
+
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+
+    let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+    let admin: AdminHook = AdminHook::new("book");
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move {
+        ws.run().await.expect("running ws stream");
+    });
+
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    let hook: AdminHook = admin.clone();
+    tokio::task::spawn(async move {
+        book::stream::run(
+            nats_client,
+            ws_client,
+            cache,
+            Shard::default(),
+            Leader::default(),
+            hook,
+            &exchange_config,
+        )
+        .await
+        .expect("running book stream");
+    });
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let snapshot: Value = admin.snapshot("btc_usd").await.expect("book snapshot");
+
+    assert_eq!(snapshot["sequence"], 1);
+    assert_eq!(snapshot["book"]["asks"][0]["rate"], "96460.00");
+    assert_eq!(snapshot["book"]["bids"][0]["rate"], "96445.00");
+    assert_eq!(sim.connections(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use async_nats::Subscriber;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use futures::StreamExt;
+use prost::Message as ProstMessage;
+use protocol::client::{NatsClient, NatsConfig};
+use protocol::public::error::{ErrorCode, ErrorMessage};
+use protocol::public::market::Market as MarketModel;
+use protocol::public::ticker::TickerRequest;
+use protocol::public::types::Exchange;
+use protocol::topics::{StreamTopic, Topic};
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use public_cryptocom::model::Market;
+use public_cryptocom::ticker;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+
+mod common;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    ExchangeConfig {
+        preload_markets: vec!["btc_usd".to_string()],
+        preload_channels: vec!["ticker".to_string()],
+        ws_max_channels: 1,
+        ..common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+    }
+}
+
+fn listed_markets() -> Vec<MarketModel> {
+    ["btc_usd", "eth_usd"]
+        .iter()
+        .map(|symbol| MarketModel {
+            symbol: symbol.to_string(),
+            ..MarketModel::default()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn snapshot_error_when_websocket_channels_limit_reached() -> Result<()> {
+    let sim: SimServer = ExchangeSim::default().start().await?;
+
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+
+    let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move {
+        ws.run().await.expect("running ws stream");
+    });
+
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    cache.update(Ok(listed_markets()));
+
+    let nats: Arc<NatsClient> = nats_client.clone();
+    tokio::task::spawn(async move {
+        ticker::stream::run(
+            nats,
+            ws_client,
+            cache,
+            Shard::default(),
+            Leader::default(),
+            AdminHook::new("ticker"),
+            &exchange_config,
+        )
+        .await
+        .expect("running ticker stream");
+    });
+
+    let market: Market = Market::new("eth".to_string(), "usd".to_string());
+    let subject: StreamTopic = StreamTopic::ticker(Exchange::Cryptocom, &market);
+
+    let mut subscriber: Subscriber = nats_client.subscribe(subject.error()).await?;
+
+    sim.wait_for("ticker.BTC_USD", Duration::from_secs(5))
+        .await?;
+    nats_client
+        .send_message(subject.snapshot(), TickerRequest {})
+        .await?;
+
+    let error = timeout(Duration::from_secs(5), subscriber.next())
+        .await?
+        .expect("error message");
+    let response: ErrorMessage = ErrorMessage::decode(error.payload)?;
+
+    assert_eq!(response.code, ErrorCode::ExchangeError as i32);
+    assert!(response.message.contains("limit reached"));
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use public_cryptocom::config::ExchangeConfig;
+use public_cryptocom::model::Market;
+
+/// Single connection config without preloaded markets, tests override only what they need
+pub fn exchange_conf(ws_url: String, markets_url: String) -> ExchangeConfig {
+    ExchangeConfig {
+        ws_url,
+        markets_url,
+        markets: Market::new("*".to_string(), "*".to_string()),
+        allowed_markets: vec![],
+        denied_markets: vec![],
+        preload_markets: vec![],
+        preload_channels: vec![],
+        markets_refresh_interval: 60,
+        lease_ttl: 60,
+        sharding: false,
+        replica_id: "test".to_string(),
+        standby: false,
+        failover_timeout: 10,
+        ws_connections: 1,
+        ws_max_channels: 400,
+        ws_requests_per_second: 50,
+        ws_idle_timeout: 60,
+        ws_stale_timeout: 120,
+        ws_stale_channels: vec!["ticker".to_string(), "book".to_string()],
+        ws_capture_file: None,
+        ws_replay_file: None,
+        shutdown_timeout: 20,
+        max_concurrency: 2,
+        max_buffer_size: 10,
+    }
+}
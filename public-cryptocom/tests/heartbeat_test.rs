@@ -0,0 +1,36 @@
+use anyhow::Result;
+use exchange_sim::cryptocom;
+use exchange_sim::scenario::Scenario;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+}
+
+#[tokio::test]
+async fn respond_to_exchange_heartbeat() -> Result<()> {
+    let scenario: Scenario = Scenario::default()
+        .send(cryptocom::heartbeat(7))
+        .expect("public/respond-heartbeat");
+    let sim: SimServer = ExchangeSim::default().ws(scenario).start().await?;
+
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move { ws.run().await });
+
+    let response: String = sim
+        .wait_for("public/respond-heartbeat", Duration::from_secs(5))
+        .await?;
+
+    assert!(response.contains("\"id\":7"));
+
+    Ok(())
+}
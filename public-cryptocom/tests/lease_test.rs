@@ -0,0 +1,73 @@
+use anyhow::Result;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use protocol::client::{NatsClient, NatsConfig};
+use protocol::public::book::OrderBookRequest;
+use protocol::public::types::Exchange;
+use protocol::topics::{StreamTopic, Topic};
+use public_cryptocom::book;
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use public_cryptocom::model::Market;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    ExchangeConfig {
+        lease_ttl: 1,
+        ..common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+    }
+}
+
+#[tokio::test]
+async fn unsubscribe_market_without_lease() -> Result<()> {
+    let sim: SimServer = ExchangeSim::default().start().await?;
+
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+
+    let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move {
+        ws.run().await.expect("running ws stream");
+    });
+
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    let nats: Arc<NatsClient> = nats_client.clone();
+    tokio::task::spawn(async move {
+        book::stream::run(
+            nats.clone(),
+            ws_client.clone(),
+            cache,
+            Shard::default(),
+            Leader::default(),
+            AdminHook::new("book"),
+            &exchange_config,
+        )
+        .await
+        .expect("running book stream");
+    });
+
+    let market: Market = Market::new("btc".to_string(), "usd".to_string());
+    let subject: StreamTopic = StreamTopic::book(Exchange::Cryptocom, &market);
+    let request: OrderBookRequest = OrderBookRequest {};
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    nats_client
+        .send_message(subject.snapshot(), request)
+        .await?;
+
+    tokio::time::sleep(Duration::from_secs(4)).await;
+    assert_eq!(sim.count("\"unsubscribe\""), 1);
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use anyhow::Result;
+use async_nats::Subscriber;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::scenario::Scenario;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use futures::StreamExt;
+use prost::Message as ProstMessage;
+use protocol::client::{NatsClient, NatsConfig};
+use protocol::public::ticker::{TickerMessage, TickerRequest};
+use protocol::public::types::{Exchange, MessageType};
+use protocol::topics::{SnapshotTopic, StreamTopic, Topic};
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use public_cryptocom::model::Market;
+use public_cryptocom::ticker;
+use std::sync::Arc;
+
+mod common;
+
+const FIRST: &str = r#"{
+  "id": 1,
+  "method": "subscribe",
+  "code": 0,
+  "result": {
+    "instrument_name": "BTC_USD",
+    "subscription": "ticker.BTC_USD",
+    "channel": "ticker",
+    "data": [
+      {
+        "h": "102780.56",
+        "l": "96109.81",
+        "a": "96441.70",
+        "c": "-0.0526",
+        "b": "96447.99",
+        "bs": "1.68000",
+        "k": "96448.00",
+        "ks": "0.12219",
+        "i": "BTC_USD",
+        "v": "28786.2439",
+        "vv": "2836123068.86",
+        "oi": "0",
+        "t": 1736286461888
+      }
+    ]
+  }
+}"#;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    ExchangeConfig {
+        preload_markets: vec!["btc_usd".to_string()],
+        preload_channels: vec!["ticker".to_string()],
+        ..common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+    }
+}
+
+#[tokio::test]
+async fn preloaded_ticker_snapshot() -> Result<()> {
+    let scenario: Scenario = Scenario::default().expect("subscribe").send(FIRST);
+    let sim: SimServer = ExchangeSim::default().ws(scenario).start().await?;
+
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+
+    let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move {
+        ws.run().await.expect("running ws stream");
+    });
+
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    let nats: Arc<NatsClient> = nats_client.clone();
+    tokio::task::spawn(async move {
+        ticker::stream::run(
+            nats.clone(),
+            ws_client.clone(),
+            cache,
+            Shard::default(),
+            Leader::default(),
+            AdminHook::new("ticker"),
+            &exchange_config,
+        )
+        .await
+        .expect("running ticker stream");
+    });
+
+    let market: Market = Market::new("btc".to_string(), "usd".to_string());
+    let subject: StreamTopic = StreamTopic::ticker(Exchange::Cryptocom, &market);
+    let snapshot_subject: SnapshotTopic = subject.snapshot();
+    let request: TickerRequest = TickerRequest {};
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+    let mut subscriber: Subscriber = nats_client.subscribe(subject).await?;
+
+    nats_client.send_message(snapshot_subject, request).await?;
+
+    let snapshot = subscriber.next().await.expect("ticker snapshot");
+    let response: TickerMessage = TickerMessage::decode(snapshot.payload)?;
+
+    assert_eq!(response.r#type, MessageType::Snapshot as i32);
+    assert_eq!(response.sequence, 0);
+    assert_eq!(
+        response.tick.map(|tick| tick.ask_price),
+        Some("96448.00".to_string())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use exchange_sim::cryptocom;
+use exchange_sim::scenario::Scenario;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::Receiver;
+use tokio::time::timeout;
+
+mod common;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+}
+
+#[tokio::test]
+async fn reconnect_dropped_websocket() -> Result<()> {
+    let scenario: Scenario = Scenario::default()
+        .wait(Duration::from_millis(100))
+        .disconnect()
+        .reconnect()
+        .send(cryptocom::heartbeat(2))
+        .expect("public/respond-heartbeat");
+    let sim: SimServer = ExchangeSim::default().ws(scenario).start().await?;
+
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+    let mut reset: Receiver<usize> = ws_client.subscribe_reset();
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move { ws.run().await });
+
+    let connection: usize = timeout(Duration::from_secs(10), reset.recv()).await??;
+    sim.wait_for_connections(2, Duration::from_secs(10)).await?;
+    let response: String = sim
+        .wait_for("public/respond-heartbeat", Duration::from_secs(5))
+        .await?;
+
+    assert!(response.contains("\"id\":2"));
+    assert_eq!(connection, 0);
+
+    Ok(())
+}
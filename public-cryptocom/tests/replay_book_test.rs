@@ -0,0 +1,107 @@
+use anyhow::Result;
+use async_nats::subject::ToSubject;
+use async_nats::Subscriber;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use exchange_sim::nats::EmbeddedNats;
+use futures::stream::Take;
+use futures::StreamExt;
+use http::subscriptions::hook::SubscriptionsHook;
+use prost::Message as ProstMessage;
+use protocol::client::{NatsClient, NatsConfig};
+use protocol::public::book::{OrderBookMessage, OrderBookRequest};
+use protocol::public::types::{Exchange, MessageType};
+use protocol::topics::{StreamTopic, Topic};
+use public_cryptocom::book;
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use public_cryptocom::model::Market;
+use serde_json::Value;
+use std::sync::Arc;
+
+mod common;
+
+const CAPTURE: &str = "tests/resources/book_capture.jsonl";
+
+fn exchange_conf() -> ExchangeConfig {
+    ExchangeConfig {
+        ws_replay_file: Some(CAPTURE.to_string()),
+        ..common::exchange_conf(
+            "ws://localhost/ws".to_string(),
+            "http://localhost/markets".to_string(),
+        )
+    }
+}
+
+#[tokio::test]
+async fn replay_captured_book_snapshot_and_update() -> Result<()> {
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf();
+
+    let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+    let admin: AdminHook = AdminHook::new("book");
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move {
+        ws.run().await.expect("replaying ws frames");
+    });
+
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    let nats: Arc<NatsClient> = nats_client.clone();
+    let hook: AdminHook = admin.clone();
+    tokio::task::spawn(async move {
+        book::stream::run(
+            nats,
+            ws_client,
+            cache,
+            Shard::default(),
+            Leader::default(),
+            hook,
+            &exchange_config,
+        )
+        .await
+        .expect("running book stream");
+    });
+
+    let market: Market = Market::new("btc".to_string(), "usd".to_string());
+    let subject: StreamTopic = StreamTopic::book(Exchange::Cryptocom, &market);
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+
+    let mut subscriber: Take<Subscriber> =
+        nats_client.subscribe(subject.to_subject()).await?.take(2);
+    nats_client
+        .send_message(subject.snapshot(), OrderBookRequest {})
+        .await?;
+
+    if let Some(first) = subscriber.next().await {
+        let response: OrderBookMessage = OrderBookMessage::decode(first.payload)?;
+
+        assert_eq!(response.r#type, MessageType::Snapshot as i32);
+        assert_eq!(response.sequence, 0);
+        assert_eq!(response.book.map(|book| book.asks.len()), Some(2));
+    }
+
+    if let Some(second) = subscriber.next().await {
+        let response: OrderBookMessage = OrderBookMessage::decode(second.payload)?;
+
+        assert_eq!(response.r#type, MessageType::Update as i32);
+        assert_eq!(response.sequence, 1);
+        assert_eq!(
+            response.book.map(|book| book.bids[0].rate.clone()),
+            Some("96445.00".to_string())
+        );
+    }
+
+    let snapshot: Value = admin.snapshot("btc_usd").await.expect("book snapshot");
+
+    assert_eq!(snapshot["book"]["asks"][0]["rate"], "96460.00");
+    assert_eq!(snapshot["book"]["bids"][0]["rate"], "96445.00");
+    assert_eq!(snapshot["book"]["bids"][1]["rate"], "96440.00");
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use connector::shard::{Membership, Shard};
+use exchange_sim::nats::EmbeddedNats;
+use protocol::client::NatsClient;
+use protocol::public::types::Exchange;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+
+fn keys() -> Vec<String> {
+    (0..100).map(|key| format!("market{}_usd", key)).collect()
+}
+
+#[tokio::test]
+#[ignore = "requires nats-server with JetStream"]
+async fn replicas_split_markets_and_take_over_left_ones() -> Result<()> {
+    let nats_server: EmbeddedNats = EmbeddedNats::jetstream().await?;
+    let nats_client: NatsClient = NatsClient::new(&nats_server.config()).await?;
+
+    let (first, mut first_shard): (Membership, Shard) =
+        Membership::join(&nats_client, Exchange::Cryptocom, "first".to_string()).await?;
+    let (second, second_shard): (Membership, Shard) =
+        Membership::join(&nats_client, Exchange::Cryptocom, "second".to_string()).await?;
+
+    let first: Arc<Membership> = Arc::new(first);
+    let membership: Arc<Membership> = first.clone();
+    let task = tokio::spawn(async move { membership.run().await });
+
+    // the first replica learns about the second one on the next heartbeat
+    timeout(Duration::from_secs(10), first_shard.changed()).await?;
+
+    let owned: usize = keys().iter().filter(|key| first_shard.owns(key)).count();
+
+    assert!(owned > 0 && owned < keys().len());
+    assert!(keys()
+        .iter()
+        .all(|key| first_shard.owns(key) != second_shard.owns(key)));
+
+    second.leave().await?;
+    timeout(Duration::from_secs(10), first_shard.changed()).await?;
+
+    assert!(keys().iter().all(|key| first_shard.owns(key)));
+
+    task.abort();
+    Ok(())
+}
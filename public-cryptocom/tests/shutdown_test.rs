@@ -0,0 +1,45 @@
+use anyhow::Result;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use public_cryptocom::client::request::{Channel, ExchangeRequest, Method};
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use public_cryptocom::model::Market;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::timeout;
+
+mod common;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+}
+
+#[tokio::test]
+async fn unsubscribe_all_channels_and_close_on_shutdown() -> Result<()> {
+    let sim: SimServer = ExchangeSim::default().start().await?;
+
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    let running = tokio::task::spawn(async move { ws.run().await });
+
+    let mut connected: watch::Receiver<bool> = ws_client.subscribe_connection();
+    connected.wait_for(|connected| *connected).await?;
+
+    let market: Market = Market::new("btc".to_string(), "usd".to_string());
+    ws_client.send(ExchangeRequest::new(
+        &market,
+        &Channel::Ticker,
+        Method::Subscribe,
+    ))?;
+
+    ws_client.unsubscribe_all()?;
+    timeout(Duration::from_secs(5), ws_client.close()).await??;
+    timeout(Duration::from_secs(5), running).await???;
+
+    assert_eq!(sim.count("unsubscribe"), 1);
+
+    Ok(())
+}
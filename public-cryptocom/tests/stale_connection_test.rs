@@ -0,0 +1,103 @@
+use anyhow::Result;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use exchange_sim::cryptocom;
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::scenario::Scenario;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use protocol::client::{NatsClient, NatsConfig};
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use public_cryptocom::ticker;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::Receiver;
+use tokio::time::{sleep, timeout, Instant};
+
+mod common;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    ExchangeConfig {
+        preload_markets: vec!["btc_usd".to_string(), "eth_usd".to_string()],
+        preload_channels: vec!["ticker".to_string()],
+        ws_connections: 2,
+        ws_stale_timeout: 1,
+        ..common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+    }
+}
+
+/// Tickers streamed once the connection is subscribed to BTC_USD
+fn streaming(scenario: Scenario) -> Scenario {
+    (0..60).fold(scenario.expect("ticker.BTC_USD"), |scenario, offset| {
+        scenario
+            .send(cryptocom::ticker(
+                "BTC_USD",
+                ("96447.99", "1.68"),
+                ("96448.00", "0.12"),
+                1736286461888 + offset,
+            ))
+            .wait(Duration::from_millis(200))
+    })
+}
+
+/// Whichever connection is subscribed to BTC_USD streams, the other one stays quiet
+fn scenario() -> Scenario {
+    streaming(streaming(Scenario::default()).reconnect())
+}
+
+fn subscribes(sim: &SimServer, channel: &str) -> usize {
+    sim.received()
+        .iter()
+        .filter(|frame| frame.contains("\"method\":\"subscribe\"") && frame.contains(channel))
+        .count()
+}
+
+#[tokio::test]
+async fn reconnect_only_stale_websocket() -> Result<()> {
+    let sim: SimServer = ExchangeSim::default().ws(scenario()).start().await?;
+
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+
+    let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+    let mut reset: Receiver<usize> = ws_client.subscribe_reset();
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move {
+        ws.run().await.expect("running ws stream");
+    });
+
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    tokio::task::spawn(async move {
+        ticker::stream::run(
+            nats_client,
+            ws_client,
+            cache,
+            Shard::default(),
+            Leader::default(),
+            AdminHook::new("ticker"),
+            &exchange_config,
+        )
+        .await
+        .expect("running ticker stream");
+    });
+
+    let connection: usize = timeout(Duration::from_secs(10), reset.recv()).await??;
+    let deadline: Instant = Instant::now() + Duration::from_secs(5);
+
+    while subscribes(&sim, "ticker.ETH_USD") < 2 && Instant::now() < deadline {
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(connection, 1);
+    assert_eq!(sim.connections(), 3);
+    assert_eq!(subscribes(&sim, "ticker.ETH_USD"), 2);
+    assert_eq!(subscribes(&sim, "ticker.BTC_USD"), 1);
+    assert_eq!(sim.count("ticker.BTC_USD"), 1);
+
+    Ok(())
+}
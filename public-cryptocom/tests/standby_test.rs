@@ -0,0 +1,60 @@
+use anyhow::Result;
+use connector::leader::{Election, Leader};
+use exchange_sim::nats::EmbeddedNats;
+use protocol::client::NatsClient;
+use protocol::public::types::Exchange;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
+
+const FAILOVER_TIMEOUT: Duration = Duration::from_secs(3);
+
+async fn join(nats_client: &NatsClient, replica: &str) -> Result<(Arc<Election>, Leader)> {
+    let (election, leader): (Election, Leader) = Election::join(
+        nats_client,
+        Exchange::Cryptocom,
+        replica.to_string(),
+        FAILOVER_TIMEOUT,
+    )
+    .await?;
+
+    Ok((Arc::new(election), leader))
+}
+
+fn run(election: &Arc<Election>) -> JoinHandle<Result<()>> {
+    let election: Arc<Election> = election.clone();
+    tokio::spawn(async move { election.run().await })
+}
+
+#[tokio::test]
+#[ignore = "requires nats-server with JetStream"]
+async fn standby_takes_over_resigned_leader() -> Result<()> {
+    let nats_server: EmbeddedNats = EmbeddedNats::jetstream().await?;
+    let nats_client: NatsClient = NatsClient::new(&nats_server.config()).await?;
+
+    let (active, mut active_leader): (Arc<Election>, Leader) = join(&nats_client, "active").await?;
+    let (standby, mut standby_leader): (Arc<Election>, Leader) =
+        join(&nats_client, "standby").await?;
+
+    let active_task: JoinHandle<Result<()>> = run(&active);
+    timeout(Duration::from_secs(5), active_leader.changed()).await?;
+
+    let standby_task: JoinHandle<Result<()>> = run(&standby);
+    sleep(FAILOVER_TIMEOUT / 2).await;
+
+    assert!(active_leader.is_leader());
+    assert!(!standby_leader.is_leader());
+
+    let epoch: u64 = active_leader.epoch();
+    active_task.abort();
+    active.resign().await?;
+    timeout(Duration::from_secs(5), standby_leader.changed()).await?;
+
+    assert!(!active_leader.is_leader());
+    assert!(standby_leader.is_leader());
+    assert!(standby_leader.epoch() > epoch);
+
+    standby_task.abort();
+    Ok(())
+}
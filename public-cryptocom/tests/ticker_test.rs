@@ -1,20 +1,26 @@
 use anyhow::Result;
 use async_nats::Subscriber;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::scenario::Scenario;
+use exchange_sim::server::{ExchangeSim, SimServer};
 use futures::stream::Take;
 use futures::StreamExt;
 use prost::Message as ProstMessage;
 use protocol::client::{NatsClient, NatsConfig};
 use protocol::public::ticker::{TickerMessage, TickerRequest};
-use protocol::public::types::Exchange;
+use protocol::public::types::{Exchange, Latency};
 use protocol::topics::{StreamTopic, Topic};
 use public_cryptocom::client::ws_client::WsClient;
 use public_cryptocom::config::ExchangeConfig;
 use public_cryptocom::model::Market;
 use public_cryptocom::ticker;
 use std::sync::Arc;
-use tokio_tungstenite::tungstenite::Message;
-use ws_mock::matchers::Any;
-use ws_mock::ws_mock_server::{WsMock, WsMockServer};
+
+mod common;
 
 const FIRST: &str = r#"{
   "id": 1,
@@ -72,37 +78,21 @@ const SECOND: &str = r#"{
   }
 }"#;
 
-fn nats_conf() -> NatsConfig {
-    NatsConfig {
-        host: "0.0.0.0".to_string(),
-        port: 4222,
-        max_reconnects: 0,
-    }
-}
-
-fn exchange_conf(uri: String) -> ExchangeConfig {
-    ExchangeConfig {
-        ws_url: format!("{}/ws", uri),
-        markets_url: format!("{}/markets", uri),
-        markets: Market::new("*".to_string(), "*".to_string()),
-        max_concurrency: 2,
-        max_buffer_size: 10,
-    }
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
 }
 
 #[tokio::test]
 async fn stream_ticker_twice() -> Result<()> {
-    let server: WsMockServer = WsMockServer::start().await;
-
-    WsMock::new()
-        .matcher(Any::new())
-        .respond_with(Message::Text(String::from(FIRST)))
-        .respond_with(Message::Text(String::from(SECOND)))
-        .mount(&server)
-        .await;
+    let scenario: Scenario = Scenario::default()
+        .expect("subscribe")
+        .send(FIRST)
+        .send(SECOND);
+    let sim: SimServer = ExchangeSim::default().ws(scenario).start().await?;
 
-    let nats_config: NatsConfig = nats_conf();
-    let exchange_config: ExchangeConfig = exchange_conf(server.uri().await);
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
 
     let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
     let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
@@ -112,11 +102,20 @@ async fn stream_ticker_twice() -> Result<()> {
         ws.run().await.expect("running ws stream");
     });
 
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
     let nats: Arc<NatsClient> = nats_client.clone();
     tokio::task::spawn(async move {
-        ticker::stream::run(nats.clone(), ws_client.clone(), &exchange_config)
-            .await
-            .expect("running markets stream");
+        ticker::stream::run(
+            nats.clone(),
+            ws_client.clone(),
+            cache,
+            Shard::default(),
+            Leader::default(),
+            AdminHook::new("ticker"),
+            &exchange_config,
+        )
+        .await
+        .expect("running markets stream");
     });
 
     let market: Market = Market::new("btc".to_string(), "usd".to_string());
@@ -148,6 +147,11 @@ async fn stream_ticker_twice() -> Result<()> {
         assert_eq!(response.exchange, Exchange::Cryptocom as i32);
         assert!(response.tick.is_some());
 
+        let latency: Latency = response.latency.clone().expect("latency stamps");
+        assert!(latency.received_micros > 0);
+        assert!(latency.published_micros >= latency.received_micros);
+        assert_eq!(latency.instance, Shard::default().replica());
+
         if let Some(tick) = response.tick {
             assert_eq!(tick.ask_price, "96460.00");
             assert_eq!(tick.bid_price, "96449.99");
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use async_nats::Subscriber;
+use connector::cache::MarketsCache;
+use connector::leader::Leader;
+use connector::shard::Shard;
+use connector::stream::admin::AdminHook;
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use futures::StreamExt;
+use prost::Message as ProstMessage;
+use protocol::client::{NatsClient, NatsConfig};
+use protocol::public::book::OrderBookRequest;
+use protocol::public::error::{ErrorCode, ErrorMessage};
+use protocol::public::market::Market as MarketModel;
+use protocol::public::types::Exchange;
+use protocol::topics::{StreamTopic, Topic};
+use public_cryptocom::book;
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use public_cryptocom::model::Market;
+use std::sync::Arc;
+
+mod common;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+}
+
+fn listed_markets() -> Vec<MarketModel> {
+    vec![MarketModel {
+        symbol: "btc_usd".to_string(),
+        ..MarketModel::default()
+    }]
+}
+
+#[tokio::test]
+async fn snapshot_for_unknown_market() -> Result<()> {
+    let sim: SimServer = ExchangeSim::default().start().await?;
+
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+
+    let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+
+    let cache: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    cache.update(Ok(listed_markets()));
+
+    let nats: Arc<NatsClient> = nats_client.clone();
+    tokio::task::spawn(async move {
+        book::stream::run(
+            nats.clone(),
+            ws_client.clone(),
+            cache,
+            Shard::default(),
+            Leader::default(),
+            AdminHook::new("book"),
+            &exchange_config,
+        )
+        .await
+        .expect("running book stream");
+    });
+
+    let market: Market = Market::new("xyz".to_string(), "usd".to_string());
+    let subject: StreamTopic = StreamTopic::book(Exchange::Cryptocom, &market);
+    let request: OrderBookRequest = OrderBookRequest {};
+
+    let mut subscriber: Subscriber = nats_client.subscribe(subject.error()).await?;
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+    nats_client
+        .send_message(subject.snapshot(), request)
+        .await?;
+
+    let error = subscriber.next().await.expect("error message");
+    let response: ErrorMessage = ErrorMessage::decode(error.payload)?;
+
+    assert_eq!(response.code, ErrorCode::MarketNotFound as i32);
+    assert_eq!(response.message, "Market xyz_usd not found!");
+
+    Ok(())
+}
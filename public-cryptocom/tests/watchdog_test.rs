@@ -0,0 +1,39 @@
+use anyhow::Result;
+use exchange_sim::server::{ExchangeSim, SimServer};
+use public_cryptocom::client::ws_client::WsClient;
+use public_cryptocom::config::ExchangeConfig;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::watch;
+use tokio::time::timeout;
+
+mod common;
+
+fn exchange_conf(sim: &SimServer) -> ExchangeConfig {
+    ExchangeConfig {
+        ws_idle_timeout: 1,
+        ..common::exchange_conf(sim.ws_url(), sim.rest_url("/markets"))
+    }
+}
+
+#[tokio::test]
+async fn reconnect_idle_websocket() -> Result<()> {
+    let sim: SimServer = ExchangeSim::default().start().await?;
+
+    let exchange_config: ExchangeConfig = exchange_conf(&sim);
+    let ws_client: Arc<WsClient> = Arc::new(WsClient::new(&exchange_config)?);
+    let mut reset: Receiver<usize> = ws_client.subscribe_reset();
+
+    let ws: Arc<WsClient> = ws_client.clone();
+    tokio::task::spawn(async move { ws.run().await });
+
+    let mut connected: watch::Receiver<bool> = ws_client.subscribe_connection();
+    connected.wait_for(|connected| *connected).await?;
+
+    let connection: usize = timeout(Duration::from_secs(10), reset.recv()).await??;
+
+    assert_eq!(connection, 0);
+
+    Ok(())
+}
@@ -7,6 +7,12 @@ pub struct ExchangeResponse<T> {
     pub result: T,
 }
 
+/// Kraken Futures API response
+#[derive(Deserialize, Debug)]
+pub struct FuturesResponse<T> {
+    pub instruments: Vec<T>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ExchangeError {
     pub error: String,
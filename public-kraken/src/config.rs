@@ -12,6 +12,8 @@ const DEFAULT_PATH: &str = "kraken-cryptocom/resources";
 #[derive(Debug, Clone, Deserialize)]
 pub struct ExchangeConfig {
     pub markets_url: String,
+    pub futures_markets_url: String,
+    pub markets_refresh_interval: u64,
     pub max_concurrency: usize,
 }
 
@@ -0,0 +1,5 @@
+pub mod client;
+pub mod config;
+pub mod markets;
+pub mod model;
+pub mod topics;
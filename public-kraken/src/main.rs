@@ -1,20 +1,23 @@
-use crate::config::{load_config, AppConfig};
 use anyhow::Context;
+use connector::cache::{MarketsCache, MarketsHealthCheck};
 use connector::http_client::HttpClient;
-use connector::utils::check::nats_healthcheck;
+use connector::supervisor::Supervisor;
+use connector::utils::check::{nats_healthcheck, ExchangeHealthCheck};
 use connector::utils::tracing;
 use http::healthcheck::service::HealthcheckService;
 use http::server::{base_router, HttpConfig};
+use http::subscriptions::service::SubscriptionsService;
 use protocol::client::NatsClient;
+use public_kraken::config::{load_config, AppConfig};
+use public_kraken::markets;
+use reqwest::Url;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::select;
 
-mod client;
-mod config;
-mod markets;
-mod model;
-mod topics;
+const EXCHANGE_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const EXCHANGE_PROBE_INTERVAL: Duration = Duration::from_secs(60);
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -25,20 +28,50 @@ async fn main() -> anyhow::Result<()> {
     let http_client: Arc<HttpClient> = Arc::new(HttpClient::default());
     let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&config.nats).await?);
 
-    let healthcheck: HealthcheckService = nats_healthcheck(nats_client.clone());
+    let markets_url: Url = Url::parse(&config.exchange.markets_url)?;
+    let markets_age: Duration = Duration::from_secs(config.exchange.markets_refresh_interval * 3);
+    let spot: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    let futures: Arc<MarketsCache> = Arc::new(MarketsCache::default());
+    let supervisor: Supervisor = Supervisor::default();
+
+    let markets_stream_task = supervisor.supervise("markets", || {
+        markets::stream::run(
+            nats_client.clone(),
+            http_client.clone(),
+            spot.clone(),
+            futures.clone(),
+            &config.exchange,
+        )
+    });
+    let server_task = supervisor.supervise("http", || {
+        let mut healthcheck: HealthcheckService = nats_healthcheck(nats_client.clone());
+        healthcheck.add(Box::new(
+            MarketsHealthCheck::new(spot.clone(), markets_age).named("markets-spot"),
+        ));
+        healthcheck.add(Box::new(
+            MarketsHealthCheck::new(futures.clone(), markets_age).named("markets-futures"),
+        ));
+        healthcheck.add(Box::new(ExchangeHealthCheck::new(
+            http_client.clone(),
+            markets_url.clone(),
+            EXCHANGE_PROBE_TIMEOUT,
+            EXCHANGE_PROBE_INTERVAL,
+        )));
+        healthcheck.add(Box::new(supervisor.clone()));
+        run_server(&config.http, healthcheck)
+    });
 
-    let markets_stream_task =
-        markets::stream::run(nats_client.clone(), http_client.clone(), &config.exchange);
     select! {
         task = markets_stream_task => task?,
-        task = run_server(&config.http, healthcheck) => task?,
+        task = server_task => task?,
     }
 
+    tracing::shutdown();
     Ok(())
 }
 
 async fn run_server(config: &HttpConfig, service: HealthcheckService) -> anyhow::Result<()> {
-    let router = base_router(service);
+    let router = base_router(service, SubscriptionsService::default());
 
     let listener: TcpListener = TcpListener::bind(config.address())
         .await
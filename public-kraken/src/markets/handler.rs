@@ -1,38 +1,58 @@
-use crate::client::response::{ExchangeError, ExchangeResponse};
+use crate::client::response::{ExchangeError, ExchangeResponse, FuturesResponse};
 use crate::config::ExchangeConfig;
-use crate::markets::models::AssetPair;
+use crate::markets::models::{AssetPair, FuturesInstrument};
+use crate::topics;
 use anyhow::{anyhow, Result};
 use async_nats::Subject;
 use chrono::Utc;
+use connector::cache::{CachedMarkets, MarketsCache};
 use connector::decoder::NatsEvent;
+use connector::filter::filter;
 use connector::http_client::HttpClient;
-use log::info;
+use log::{info, warn};
 use protocol::client::NatsClient;
 use protocol::public::error::ErrorMessage;
-use protocol::public::market::{Market, MarketsMessage, MarketsRequest};
+use protocol::public::market::{
+    Market, MarketChange, MarketType, MarketsChangesMessage, MarketsMessage, MarketsRequest,
+};
 use protocol::public::types::Exchange;
+use protocol::topics::ChangesTopic;
 use reqwest::Url;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::join;
 
 type MarketsResponse = ExchangeResponse<HashMap<String, AssetPair>>;
+type FuturesMarketsResponse = FuturesResponse<FuturesInstrument>;
+type MarketsResult = Result<Vec<Market>, ErrorMessage>;
 
 pub struct RequestHandler {
     http_client: Arc<HttpClient>,
     nats_client: Arc<NatsClient>,
+    spot: Arc<MarketsCache>,
+    futures: Arc<MarketsCache>,
     markets_url: Url,
+    futures_markets_url: Url,
+    refresh_interval: Duration,
 }
 
 impl RequestHandler {
     pub fn new(
         http_client: Arc<HttpClient>,
         nats_client: Arc<NatsClient>,
+        spot: Arc<MarketsCache>,
+        futures: Arc<MarketsCache>,
         config: &ExchangeConfig,
     ) -> Result<Self> {
         Ok(RequestHandler {
             http_client,
             nats_client,
+            spot,
+            futures,
             markets_url: Url::parse(&config.markets_url)?,
+            futures_markets_url: Url::parse(&config.futures_markets_url)?,
+            refresh_interval: Duration::from_secs(config.markets_refresh_interval),
         })
     }
 
@@ -45,8 +65,56 @@ impl RequestHandler {
         }
     }
 
+    /// Reloads markets from the exchange and publishes listing changes.
+    /// Spot and futures are served by different APIs and cached separately,
+    /// failure of one keeps the other fresh. Used by the periodic refresh task.
+    pub async fn refresh(&self) {
+        join!(self.refresh_spot(), self.refresh_futures());
+    }
+
+    async fn refresh_spot(&self) {
+        let changes: Vec<MarketChange> = self.spot.update(self.spot_markets().await);
+
+        if !changes.is_empty() {
+            self.publish_changes(changes).await
+        }
+    }
+
+    async fn refresh_futures(&self) {
+        let changes: Vec<MarketChange> = self.futures.update(self.futures_markets().await);
+
+        if !changes.is_empty() {
+            self.publish_changes(changes).await
+        }
+    }
+
+    async fn publish_changes(&self, changes: Vec<MarketChange>) {
+        info!("Publishing {} markets changes", changes.len());
+
+        let message: MarketsChangesMessage = MarketsChangesMessage {
+            timestamp: Utc::now().timestamp_millis(),
+            exchange: Exchange::Kraken as i32,
+            changes,
+        };
+
+        let topic: ChangesTopic = topics::markets().changes();
+
+        if let Err(error) = self.nats_client.send_message(topic, message).await {
+            warn!("Cannot publish markets changes: {}", error)
+        }
+    }
+
     async fn get_markets(&self, request: MarketsRequest, reply_topic: Subject) -> Result<()> {
-        let response: Result<MarketsMessage, ErrorMessage> = self.call_api(request).await;
+        join!(
+            self.spot
+                .load(self.refresh_interval, || self.refresh_spot()),
+            self.futures
+                .load(self.refresh_interval, || self.refresh_futures())
+        );
+
+        let response: Result<MarketsMessage, ErrorMessage> = self
+            .cached(&request)
+            .map(|cached| to_message(cached, request));
 
         match response {
             Ok(markets_message) => self
@@ -62,37 +130,127 @@ impl RequestHandler {
         }
     }
 
-    async fn call_api(&self, request: MarketsRequest) -> Result<MarketsMessage, ErrorMessage> {
+    /// Only caches of requested market types decide about the response staleness
+    fn cached(&self, request: &MarketsRequest) -> Result<CachedMarkets, ErrorMessage> {
+        let spot: i32 = MarketType::Spot as i32;
+
+        match request.market_type {
+            Some(market_type) if market_type == spot => self.spot.get(),
+            Some(_) => self.futures.get(),
+            None => merge(self.spot.get(), self.futures.get()),
+        }
+    }
+
+    async fn spot_markets(&self) -> MarketsResult {
         self.http_client
             .get::<MarketsResponse, ExchangeError>(&self.markets_url)
             .await
-            .map(|response| response.result.values().cloned().collect())
-            .map(|pairs| filter(pairs, request))
-            .map(to_message)
+            .map(|response| listed(response.result.values()))
+    }
+
+    async fn futures_markets(&self) -> MarketsResult {
+        self.http_client
+            .get::<FuturesMarketsResponse, ExchangeError>(&self.futures_markets_url)
+            .await
+            .map(|response| {
+                listed(
+                    response
+                        .instruments
+                        .iter()
+                        .filter(|instrument| instrument.is_contract()),
+                )
+            })
     }
 }
 
-fn to_message(markets: Vec<Market>) -> MarketsMessage {
+/// Instruments with values out of range are logged and skipped instead of failing the refresh
+fn listed<'a, T: 'a>(instruments: impl Iterator<Item = &'a T>) -> Vec<Market>
+where
+    Market: TryFrom<&'a T, Error = anyhow::Error>,
+{
+    instruments
+        .filter_map(|instrument| match Market::try_from(instrument) {
+            Ok(market) => Some(market),
+            Err(error) => {
+                warn!("Skipping market: {}", error);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Markets of the loaded cache are served as stale when the other one is not loaded
+fn merge(
+    spot: Result<CachedMarkets, ErrorMessage>,
+    futures: Result<CachedMarkets, ErrorMessage>,
+) -> Result<CachedMarkets, ErrorMessage> {
+    match (spot, futures) {
+        (Ok(spot), Ok(futures)) => Ok(CachedMarkets {
+            markets: spot.markets.into_iter().chain(futures.markets).collect(),
+            age: spot.age.max(futures.age),
+            stale: spot.stale || futures.stale,
+        }),
+        (Ok(cached), Err(_)) | (Err(_), Ok(cached)) => Ok(CachedMarkets {
+            stale: true,
+            ..cached
+        }),
+        (Err(error), Err(_)) => Err(error),
+    }
+}
+
+fn to_message(cached: CachedMarkets, request: MarketsRequest) -> MarketsMessage {
     MarketsMessage {
         timestamp: Utc::now().timestamp_millis(),
         exchange: Exchange::Kraken as i32,
-        markets,
+        markets: filter(cached.markets, &request),
+        stale: cached.stale,
+        cache_age: cached.age,
     }
 }
 
-fn filter(pairs: Vec<AssetPair>, request: MarketsRequest) -> Vec<Market> {
-    if request.symbols.is_empty() {
-        pairs.iter().map(Market::from).collect()
-    } else {
-        pairs
-            .iter()
-            .map(Market::from)
-            .filter(|market| request.symbols.contains(&market.symbol))
-            .filter(|market| check(request.market_type, market.market_type))
-            .collect()
+#[cfg(test)]
+mod tests {
+    use crate::markets::handler::merge;
+    use connector::cache::CachedMarkets;
+    use protocol::public::error::{ErrorCode, ErrorMessage};
+    use protocol::public::market::Market;
+
+    fn cached(symbol: &str, age: i64, stale: bool) -> Result<CachedMarkets, ErrorMessage> {
+        Ok(CachedMarkets {
+            markets: vec![Market {
+                symbol: symbol.to_string(),
+                ..Market::default()
+            }],
+            age,
+            stale,
+        })
+    }
+
+    fn error() -> Result<CachedMarkets, ErrorMessage> {
+        Err(ErrorMessage {
+            code: ErrorCode::UnderMaintenance as i32,
+            message: "futures api down".to_string(),
+            ..ErrorMessage::default()
+        })
     }
-}
 
-fn check(opt: Option<i32>, market_type: i32) -> bool {
-    opt.map_or(true, |t| t == market_type)
+    #[test]
+    fn merge_should_join_spot_and_futures() {
+        let merged: CachedMarkets =
+            merge(cached("btc_usd", 10, false), cached("eth_usd", 20, true))
+                .expect("merged markets");
+
+        assert_eq!(merged.markets.len(), 2);
+        assert_eq!(merged.age, 20);
+        assert!(merged.stale);
+    }
+
+    #[test]
+    fn merge_should_serve_loaded_markets_as_stale() {
+        let merged: CachedMarkets = merge(cached("btc_usd", 10, false), error()).expect("spot");
+
+        assert_eq!(merged.markets.len(), 1);
+        assert!(merged.stale);
+        assert!(merge(error(), error()).is_err());
+    }
 }
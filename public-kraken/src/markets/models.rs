@@ -1,31 +1,418 @@
 use crate::model::Market;
+use anyhow::{anyhow, Error, Result};
+use chrono::{DateTime, Utc};
 use protocol::model::Symbol;
-use protocol::public::market::MarketType;
+use protocol::public::market::{MarketStatus, MarketType};
 use rust_decimal::Decimal;
 use serde::Deserialize;
 
+const QUOTE_LENGTH: usize = 3;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PairStatus {
+    Online,
+    CancelOnly,
+    PostOnly,
+    LimitOnly,
+    ReduceOnly,
+    #[serde(other)]
+    Unknown,
+}
+
+impl From<&PairStatus> for MarketStatus {
+    fn from(value: &PairStatus) -> Self {
+        match value {
+            PairStatus::Online => MarketStatus::Online,
+            PairStatus::CancelOnly => MarketStatus::CancelOnly,
+            PairStatus::PostOnly => MarketStatus::PostOnly,
+            PairStatus::LimitOnly => MarketStatus::LimitOnly,
+            PairStatus::ReduceOnly => MarketStatus::ReduceOnly,
+            PairStatus::Unknown => MarketStatus::Unspecified,
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct AssetPair {
+    altname: String,
     wsname: Market,
     pair_decimals: i32,
     lot_decimals: i32,
     ordermin: Decimal,
     costmin: Decimal,
+    tick_size: Decimal,
+    status: PairStatus,
+}
+
+impl AssetPair {
+    /// Fails for lot decimals out of decimal scale range
+    fn size_tick_size(&self) -> Result<Decimal> {
+        u32::try_from(self.lot_decimals)
+            .ok()
+            .and_then(|scale| Decimal::try_new(1, scale).ok())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Invalid lot decimals {} of {}",
+                    self.lot_decimals,
+                    self.altname
+                )
+            })
+    }
 }
 
-impl From<&AssetPair> for protocol::public::market::Market {
-    fn from(pair: &AssetPair) -> Self {
-        protocol::public::market::Market {
+/// Spot pairs from AssetPairs endpoint. Kraken does not publish max order size and price limits.
+impl TryFrom<&AssetPair> for protocol::public::market::Market {
+    type Error = Error;
+
+    fn try_from(pair: &AssetPair) -> Result<Self> {
+        Ok(protocol::public::market::Market {
             symbol: pair.wsname.nats_format(),
             price_precision: pair.pair_decimals,
             rate_precision: pair.pair_decimals,
             size_precision: pair.lot_decimals,
             min_size: pair.ordermin.to_string(),
-            max_size: i64::MAX.to_string(),
-            min_price: pair.costmin.to_string(),
-            max_price: i64::MAX.to_string(),
+            max_size: String::new(),
+            min_price: String::new(),
+            max_price: String::new(),
             market_type: MarketType::Spot as i32,
             expiry_timestamp: None,
+            price_tick_size: pair.tick_size.to_string(),
+            size_tick_size: pair.size_tick_size()?.to_string(),
+            min_notional: Some(pair.costmin.to_string()),
+            base_currency: pair.wsname.from().to_string(),
+            quote_currency: pair.wsname.to().to_string(),
+            contract_size: None,
+            settlement_currency: None,
+            status: MarketStatus::from(&pair.status) as i32,
+            instrument_id: pair.altname.clone(),
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstrumentType {
+    FuturesInverse,
+    FuturesVanilla,
+    FlexibleFutures,
+    #[serde(other)]
+    Other,
+}
+
+/// Kraken Futures instrument e.g. PI_XBTUSD, FI_XBTUSD_250328, PF_XBTUSD
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesInstrument {
+    symbol: String,
+    #[serde(rename = "type")]
+    instrument_type: InstrumentType,
+    #[serde(default)]
+    tradeable: bool,
+    #[serde(default)]
+    post_only: bool,
+    tick_size: Option<Decimal>,
+    contract_size: Option<Decimal>,
+    #[serde(default)]
+    contract_value_trade_precision: i32,
+    last_trading_time: Option<DateTime<Utc>>,
+    base: Option<String>,
+    quote: Option<String>,
+}
+
+impl FuturesInstrument {
+    /// Index instruments are also returned by the endpoint and cannot be traded
+    pub fn is_contract(&self) -> bool {
+        self.instrument_type != InstrumentType::Other
+    }
+
+    /// Flexible futures contain base and quote.
+    /// For other contracts the pair is taken from symbol e.g. FI_XBTUSD_250328
+    fn market(&self) -> Market {
+        match (&self.base, &self.quote) {
+            (Some(base), Some(quote)) => Market::new(base.clone(), quote.clone()),
+            _ => {
+                let pair: &str = self.symbol.split('_').nth(1).unwrap_or_default();
+                let (base, quote) = pair.split_at(pair.len().saturating_sub(QUOTE_LENGTH));
+                Market::new(base.to_string(), quote.to_string())
+            }
+        }
+    }
+
+    fn market_type(&self) -> MarketType {
+        if self.last_trading_time.is_some() {
+            MarketType::Future
+        } else {
+            MarketType::Perpetual
         }
     }
+
+    /// Negative precision means that size has to be multiple of tens.
+    /// Fails for precision out of decimal range.
+    fn size_tick_size(&self) -> Result<Decimal> {
+        let precision: i32 = self.contract_value_trade_precision;
+
+        let size_tick_size: Option<Decimal> = if precision >= 0 {
+            Decimal::try_new(1, precision.unsigned_abs()).ok()
+        } else {
+            10_i64
+                .checked_pow(precision.unsigned_abs())
+                .map(Decimal::from)
+        };
+
+        size_tick_size.ok_or_else(|| {
+            anyhow!(
+                "Invalid contract value trade precision {} of {}",
+                precision,
+                self.symbol
+            )
+        })
+    }
+
+    fn rate_precision(&self) -> i32 {
+        self.tick_size
+            .map(|tick_size| tick_size.normalize().scale() as i32)
+            .unwrap_or_default()
+    }
+
+    /// Inverse contracts are settled in base currency
+    fn settlement_currency(&self, market: &Market) -> String {
+        if self.instrument_type == InstrumentType::FuturesInverse {
+            market.from().to_string()
+        } else {
+            market.to().to_string()
+        }
+    }
+
+    fn status(&self) -> MarketStatus {
+        if !self.tradeable {
+            MarketStatus::Offline
+        } else if self.post_only {
+            MarketStatus::PostOnly
+        } else {
+            MarketStatus::Online
+        }
+    }
+}
+
+impl TryFrom<&FuturesInstrument> for protocol::public::market::Market {
+    type Error = Error;
+
+    fn try_from(instrument: &FuturesInstrument) -> Result<Self> {
+        let market: Market = instrument.market();
+        let size_tick_size: Decimal = instrument.size_tick_size()?;
+
+        Ok(protocol::public::market::Market {
+            symbol: market.nats_format(),
+            price_precision: instrument.rate_precision(),
+            rate_precision: instrument.rate_precision(),
+            size_precision: instrument.contract_value_trade_precision.max(0),
+            min_size: size_tick_size.to_string(),
+            max_size: String::new(),
+            min_price: String::new(),
+            max_price: String::new(),
+            market_type: instrument.market_type() as i32,
+            expiry_timestamp: instrument
+                .last_trading_time
+                .map(|time| time.timestamp_millis()),
+            price_tick_size: instrument.tick_size.unwrap_or_default().to_string(),
+            size_tick_size: size_tick_size.to_string(),
+            min_notional: None,
+            base_currency: market.from().to_string(),
+            quote_currency: market.to().to_string(),
+            contract_size: instrument.contract_size.map(|size| size.to_string()),
+            settlement_currency: Some(instrument.settlement_currency(&market)),
+            status: instrument.status() as i32,
+            instrument_id: instrument.symbol.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::markets::models::{AssetPair, FuturesInstrument, PairStatus};
+    use protocol::public::market::{Market, MarketStatus, MarketType};
+    use serde_json::from_str;
+
+    const PAIR: &str = r#"{
+        "altname": "XBTUSD",
+        "wsname": "XBT/USD",
+        "aclass_base": "currency",
+        "base": "XXBT",
+        "aclass_quote": "currency",
+        "quote": "ZUSD",
+        "lot": "unit",
+        "cost_decimals": 5,
+        "pair_decimals": 1,
+        "lot_decimals": 8,
+        "lot_multiplier": 1,
+        "leverage_buy": [2, 3, 4, 5],
+        "leverage_sell": [2, 3, 4, 5],
+        "fees": [[0, 0.4]],
+        "fees_maker": [[0, 0.25]],
+        "fee_volume_currency": "ZUSD",
+        "margin_call": 80,
+        "margin_stop": 40,
+        "ordermin": "0.0001",
+        "costmin": "0.5",
+        "tick_size": "0.1",
+        "status": "online"
+    }"#;
+
+    const PERPETUAL: &str = r#"{
+        "symbol": "PI_XBTUSD",
+        "type": "futures_inverse",
+        "underlying": "rr_xbtusd",
+        "tickSize": 0.5,
+        "contractSize": 1,
+        "tradeable": true,
+        "impactMidSize": 1,
+        "maxPositionSize": 1000000,
+        "openingDate": "2022-01-01T00:00:00.000Z",
+        "fundingRateCoefficient": 8,
+        "maxRelativeFundingRate": 0.001,
+        "contractValueTradePrecision": 0,
+        "postOnly": false
+    }"#;
+
+    const FUTURE: &str = r#"{
+        "symbol": "FI_ETHUSD_250328",
+        "type": "futures_inverse",
+        "underlying": "rr_ethusd",
+        "tickSize": 0.05,
+        "contractSize": 1,
+        "tradeable": true,
+        "lastTradingTime": "2025-03-28T16:00:00.000Z",
+        "contractValueTradePrecision": 0,
+        "postOnly": true
+    }"#;
+
+    const FLEXIBLE: &str = r#"{
+        "symbol": "PF_XBTUSD",
+        "type": "flexible_futures",
+        "tickSize": 1,
+        "contractSize": 1,
+        "tradeable": true,
+        "contractValueTradePrecision": 4,
+        "postOnly": false,
+        "base": "BTC",
+        "quote": "USD",
+        "pair": "BTC:USD"
+    }"#;
+
+    const INDEX: &str = r#"{
+        "symbol": "in_xbtusd",
+        "type": "spot index",
+        "tradeable": false
+    }"#;
+
+    #[test]
+    fn deserialize_should_return_pair_status() {
+        let online = from_str(r#""online""#);
+        let cancel_only = from_str(r#""cancel_only""#);
+        let reduce_only = from_str(r#""reduce_only""#);
+        let unknown = from_str(r#""delisted""#);
+
+        assert_eq!(online.ok(), Some(PairStatus::Online));
+        assert_eq!(cancel_only.ok(), Some(PairStatus::CancelOnly));
+        assert_eq!(reduce_only.ok(), Some(PairStatus::ReduceOnly));
+        assert_eq!(unknown.ok(), Some(PairStatus::Unknown));
+    }
+
+    #[test]
+    fn from_pair_status_should_return_unspecified_for_unknown_status() {
+        assert_eq!(
+            MarketStatus::from(&PairStatus::Unknown),
+            MarketStatus::Unspecified
+        );
+    }
+
+    #[test]
+    fn from_asset_pair_should_return_spot_market() {
+        let pair: AssetPair = from_str(PAIR).expect("asset pair");
+
+        let market: Market = Market::try_from(&pair).expect("spot market");
+
+        assert_eq!(market.symbol, "btc_usd");
+        assert_eq!(market.instrument_id, "XBTUSD");
+        assert_eq!(market.market_type, MarketType::Spot as i32);
+        assert_eq!(market.price_precision, 1);
+        assert_eq!(market.rate_precision, 1);
+        assert_eq!(market.size_precision, 8);
+        assert_eq!(market.min_size, "0.0001");
+        assert_eq!(market.min_price, "");
+        assert_eq!(market.min_notional, Some("0.5".to_string()));
+        assert_eq!(market.price_tick_size, "0.1");
+        assert_eq!(market.size_tick_size, "0.00000001");
+        assert_eq!(market.base_currency, "btc");
+        assert_eq!(market.quote_currency, "usd");
+        assert_eq!(market.status, MarketStatus::Online as i32);
+    }
+
+    #[test]
+    fn from_futures_instrument_should_return_inverse_perpetual() {
+        let instrument: FuturesInstrument = from_str(PERPETUAL).expect("perpetual");
+
+        let market: Market = Market::try_from(&instrument).expect("futures market");
+
+        assert!(instrument.is_contract());
+        assert_eq!(market.symbol, "btc_usd");
+        assert_eq!(market.instrument_id, "PI_XBTUSD");
+        assert_eq!(market.market_type, MarketType::Perpetual as i32);
+        assert_eq!(market.expiry_timestamp, None);
+        assert_eq!(market.price_tick_size, "0.5");
+        assert_eq!(market.rate_precision, 1);
+        assert_eq!(market.size_tick_size, "1");
+        assert_eq!(market.contract_size, Some("1".to_string()));
+        assert_eq!(market.settlement_currency, Some("btc".to_string()));
+        assert_eq!(market.status, MarketStatus::Online as i32);
+    }
+
+    #[test]
+    fn from_futures_instrument_should_return_future_with_expiry() {
+        let instrument: FuturesInstrument = from_str(FUTURE).expect("future");
+
+        let market: Market = Market::try_from(&instrument).expect("futures market");
+
+        assert_eq!(market.symbol, "eth_usd");
+        assert_eq!(market.market_type, MarketType::Future as i32);
+        assert_eq!(market.expiry_timestamp, Some(1743177600000));
+        assert_eq!(market.rate_precision, 2);
+        assert_eq!(market.status, MarketStatus::PostOnly as i32);
+    }
+
+    #[test]
+    fn from_futures_instrument_should_return_flexible_perpetual() {
+        let instrument: FuturesInstrument = from_str(FLEXIBLE).expect("flexible");
+
+        let market: Market = Market::try_from(&instrument).expect("futures market");
+
+        assert_eq!(market.symbol, "btc_usd");
+        assert_eq!(market.instrument_id, "PF_XBTUSD");
+        assert_eq!(market.market_type, MarketType::Perpetual as i32);
+        assert_eq!(market.size_precision, 4);
+        assert_eq!(market.size_tick_size, "0.0001");
+        assert_eq!(market.settlement_currency, Some("usd".to_string()));
+    }
+
+    #[test]
+    fn try_from_should_fail_for_precision_out_of_range() {
+        let pair: AssetPair =
+            from_str(&PAIR.replace(r#""lot_decimals": 8"#, r#""lot_decimals": 29"#)).expect("pair");
+        let instrument: FuturesInstrument = from_str(&FLEXIBLE.replace(
+            r#""contractValueTradePrecision": 4"#,
+            r#""contractValueTradePrecision": -19"#,
+        ))
+        .expect("flexible");
+
+        assert!(Market::try_from(&pair).is_err());
+        assert!(Market::try_from(&instrument).is_err());
+    }
+
+    #[test]
+    fn futures_instrument_should_skip_index() {
+        let instrument: FuturesInstrument = from_str(INDEX).expect("index");
+
+        assert!(!instrument.is_contract());
+    }
 }
@@ -5,6 +5,8 @@ use std::sync::Arc;
 use crate::config::ExchangeConfig;
 use crate::topics;
 use anyhow::Result;
+use connector::cache;
+use connector::cache::MarketsCache;
 use connector::decoder::NatsEvent;
 use connector::http_client::HttpClient;
 use connector::subscription::NatsSubscription;
@@ -12,23 +14,46 @@ use log::{debug, info, warn};
 use protocol::client::NatsClient;
 use protocol::public::market::MarketsRequest;
 use protocol::topics::RequestTopic;
+use std::time::Duration;
+use tokio::select;
 use tokio::sync::{OwnedSemaphorePermit as Permit, Semaphore};
+use tracing::{Instrument, Span};
 
-const QUEUE: &str = "cryptocom.markets";
+const QUEUE: &str = "kraken.markets";
 
 pub async fn run(
     nats_client: Arc<NatsClient>,
     http_client: Arc<HttpClient>,
+    spot: Arc<MarketsCache>,
+    futures: Arc<MarketsCache>,
     config: &ExchangeConfig,
 ) -> Result<()> {
     let topic: RequestTopic = topics::markets();
 
     info!("Starting markets request processing");
 
-    let mut nats_subscription: NatsSubscription<MarketsRequest> =
+    let nats_subscription: NatsSubscription<MarketsRequest> =
         NatsSubscription::new(&nats_client, topic, QUEUE).await?;
-    let request_handler: Arc<RequestHandler> =
-        Arc::new(RequestHandler::new(http_client, nats_client, config)?);
+    let request_handler: Arc<RequestHandler> = Arc::new(RequestHandler::new(
+        http_client,
+        nats_client,
+        spot,
+        futures,
+        config,
+    )?);
+    let interval: Duration = Duration::from_secs(config.markets_refresh_interval);
+
+    select! {
+        result = handle_nats_subscription(request_handler.clone(), nats_subscription, config) => result,
+        result = cache::refresh(interval, || request_handler.refresh()) => result,
+    }
+}
+
+async fn handle_nats_subscription(
+    request_handler: Arc<RequestHandler>,
+    mut nats_subscription: NatsSubscription<MarketsRequest>,
+    config: &ExchangeConfig,
+) -> Result<()> {
     let limiter: Arc<Semaphore> = Arc::new(Semaphore::new(config.max_concurrency));
 
     while let Some(result) = nats_subscription.next().await {
@@ -44,7 +69,9 @@ pub async fn run(
 }
 
 async fn process(handler: Arc<RequestHandler>, event: NatsEvent<MarketsRequest>, permit: Permit) {
-    if let Err(error) = handler.process(event).await {
+    let span: Span = event.span();
+
+    if let Err(error) = handler.process(event).instrument(span).await {
         warn!("Cannot send markets response: {}", error)
     } else {
         debug!("Markets response sent")
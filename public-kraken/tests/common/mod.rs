@@ -0,0 +1,11 @@
+use public_kraken::config::ExchangeConfig;
+
+/// Spot and futures REST endpoints of the exchange simulator
+pub fn exchange_conf(markets_url: String, futures_markets_url: String) -> ExchangeConfig {
+    ExchangeConfig {
+        markets_url,
+        futures_markets_url,
+        markets_refresh_interval: 60,
+        max_concurrency: 2,
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use connector::cache::MarketsCache;
+use connector::http_client::HttpClient;
+use exchange_sim::kraken::{asset_pairs, rest_error};
+use exchange_sim::nats::EmbeddedNats;
+use exchange_sim::server::{ExchangeSim, Reply, SimServer};
+use prost::Message;
+use protocol::client::{NatsClient, NatsConfig};
+use protocol::public::market::{MarketType, MarketsMessage, MarketsRequest};
+use protocol::public::types::Exchange;
+use protocol::topics::RequestTopic;
+use public_kraken::config::ExchangeConfig;
+use public_kraken::markets;
+use std::sync::Arc;
+
+mod common;
+
+#[tokio::test]
+async fn return_stale_spot_markets_when_futures_fail() -> Result<()> {
+    let sim: SimServer = ExchangeSim::default()
+        .rest(
+            "/0/public/AssetPairs",
+            Reply::ok(asset_pairs(&[("XBT", "USD")])),
+        )
+        .rest(
+            "/derivatives/api/v3/instruments",
+            Reply::status(503, rest_error("EService:Unavailable")),
+        )
+        .start()
+        .await?;
+
+    let nats_server: EmbeddedNats = EmbeddedNats::start().await?;
+    let nats_config: NatsConfig = nats_server.config();
+    let exchange_config: ExchangeConfig = common::exchange_conf(
+        sim.rest_url("/0/public/AssetPairs"),
+        sim.rest_url("/derivatives/api/v3/instruments"),
+    );
+
+    let http_client: Arc<HttpClient> = Arc::new(HttpClient::default());
+    let nats_client: Arc<NatsClient> = Arc::new(NatsClient::new(&nats_config).await?);
+
+    let nats: Arc<NatsClient> = nats_client.clone();
+    tokio::task::spawn(async move {
+        markets::stream::run(
+            nats,
+            http_client,
+            Arc::new(MarketsCache::default()),
+            Arc::new(MarketsCache::default()),
+            &exchange_config,
+        )
+        .await
+        .expect("running markets stream");
+    });
+
+    let subject: RequestTopic = RequestTopic::markets(Exchange::Kraken);
+    let request: MarketsRequest = MarketsRequest {
+        symbols: vec![],
+        market_type: None,
+        base_currencies: vec![],
+        quote_currencies: vec![],
+        patterns: vec![],
+    };
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+    let message = nats_client.send_request(subject, request).await?;
+    let response = MarketsMessage::decode(message.payload)?;
+
+    assert!(response.stale);
+    assert_eq!(response.markets.len(), 1);
+    assert_eq!(response.markets[0].symbol, "btc_usd");
+    assert_eq!(response.markets[0].market_type, MarketType::Spot as i32);
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use async_nats::Subscriber;
+use chrono::Utc;
+use connector::shutdown;
+use connector::utils::tracing;
+use futures::stream::{select_all, SelectAll};
+use futures::StreamExt;
+use log::info;
+use protocol::client::{NatsClient, NatsConfig};
+use recorder::config::{load_recorder_config, RecorderConfig};
+use recorder::frame::Frame;
+use recorder::writer::RotatingWriter;
+use std::path::Path;
+use std::time::Duration;
+use tokio::select;
+use tokio::time::{interval, Interval};
+
+const ROTATE_CHECK: Duration = Duration::from_secs(1);
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing::init()?;
+
+    let (nats_config, config): (NatsConfig, RecorderConfig) = load_recorder_config()?;
+    let nats_client: NatsClient = NatsClient::new(&nats_config).await?;
+
+    let mut subscribers: Vec<Subscriber> = Vec::new();
+    for subject in &config.subjects {
+        info!("Recording nats subject {}", subject);
+        subscribers.push(nats_client.subscribe(subject.clone()).await?);
+    }
+
+    let mut messages: SelectAll<Subscriber> = select_all(subscribers);
+    let mut writer: RotatingWriter = RotatingWriter::new(
+        Path::new(&config.directory),
+        Duration::from_secs(config.rotate_interval),
+        config.rotate_size,
+    )?;
+
+    let mut rotate: Interval = interval(ROTATE_CHECK);
+    let signal = shutdown::signal();
+    tokio::pin!(signal);
+
+    loop {
+        select! {
+            message = messages.next() => match message {
+                Some(message) => {
+                    let frame: Frame = Frame::new(message, Utc::now().timestamp_micros());
+                    writer.write(&frame)?;
+                }
+                None => break,
+            },
+            _ = rotate.tick() => writer.rotate()?,
+            result = &mut signal => {
+                result?;
+                break;
+            }
+        }
+    }
+
+    writer.finish()?;
+    info!("Recorder stopped");
+    tracing::shutdown();
+    Ok(())
+}
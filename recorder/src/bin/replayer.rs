@@ -0,0 +1,29 @@
+use anyhow::Result;
+use connector::utils::tracing;
+use log::info;
+use protocol::client::{NatsClient, NatsConfig};
+use recorder::config::{load_replayer_config, ReplayerConfig};
+use recorder::reader::{files, FrameReader};
+use recorder::replay::Replay;
+use std::path::Path;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing::init()?;
+
+    let (nats_config, config): (NatsConfig, ReplayerConfig) = load_replayer_config()?;
+    let nats_client: NatsClient = NatsClient::new(&nats_config).await?;
+    let mut replay: Replay = Replay::new(nats_client, config.speed, config.subject_prefix);
+
+    for path in files(Path::new(&config.directory))? {
+        info!("Replaying {} at {}x speed", path.display(), config.speed);
+
+        for frame in FrameReader::open(&path)? {
+            replay.publish(frame?).await?;
+        }
+    }
+
+    replay.finish().await?;
+    tracing::shutdown();
+    Ok(())
+}
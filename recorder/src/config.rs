@@ -0,0 +1,51 @@
+use anyhow::Result;
+use connector::config::load_file;
+use log::info;
+use protocol::client::NatsConfig;
+use serde::Deserialize;
+use std::env;
+
+const ENV_PATH: &str = "CONFIGURATION_PATH";
+const DEFAULT_PATH: &str = "recorder/resources";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecorderConfig {
+    /// Nats subjects with wildcards e.g. cryptocom.ticker.*.*
+    pub subjects: Vec<String>,
+    pub directory: String,
+    /// Max age of the file in seconds
+    pub rotate_interval: u64,
+    /// Max size of the uncompressed frames in bytes
+    pub rotate_size: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplayerConfig {
+    pub directory: String,
+    /// Multiplier of the original speed, 0 publishes without delays
+    pub speed: f64,
+    /// Prepended to recorded subjects to keep replay apart from live data
+    pub subject_prefix: String,
+}
+
+pub fn load_recorder_config() -> Result<(NatsConfig, RecorderConfig)> {
+    let path: String = env::var(ENV_PATH).unwrap_or(DEFAULT_PATH.to_string());
+
+    let nats: NatsConfig = load_file(&path, "nats")?;
+    let recorder: RecorderConfig = load_file(&path, "recorder")?;
+
+    info!("Recorder config loaded successfully!");
+
+    Ok((nats, recorder))
+}
+
+pub fn load_replayer_config() -> Result<(NatsConfig, ReplayerConfig)> {
+    let path: String = env::var(ENV_PATH).unwrap_or(DEFAULT_PATH.to_string());
+
+    let nats: NatsConfig = load_file(&path, "nats")?;
+    let replayer: ReplayerConfig = load_file(&path, "replayer")?;
+
+    info!("Replayer config loaded successfully!");
+
+    Ok((nats, replayer))
+}
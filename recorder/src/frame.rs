@@ -0,0 +1,149 @@
+use anyhow::{anyhow, Result};
+use async_nats::{HeaderMap, Message as NatsMessage};
+use prost::bytes::Bytes;
+use prost::Message;
+use std::io::{ErrorKind, Read, Write};
+
+/// Varint length prefix takes at most 10 bytes
+const MAX_DELIMITER_LENGTH: usize = 10;
+
+/// Nats message as received by the recorder, payload kept as raw protobuf
+#[derive(Clone, PartialEq, Message)]
+pub struct Frame {
+    /// Receive time in micros since epoch
+    #[prost(int64, tag = "1")]
+    pub received_micros: i64,
+    #[prost(string, tag = "2")]
+    pub subject: String,
+    #[prost(message, repeated, tag = "3")]
+    pub headers: Vec<Header>,
+    #[prost(bytes = "bytes", tag = "4")]
+    pub payload: Bytes,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Header {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub value: String,
+}
+
+impl Frame {
+    pub fn new(message: NatsMessage, received_micros: i64) -> Self {
+        let headers: Vec<Header> = message
+            .headers
+            .iter()
+            .flat_map(|headers| headers.iter())
+            .flat_map(|(name, values)| {
+                values.iter().map(move |value| Header {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+            })
+            .collect();
+
+        Frame {
+            received_micros,
+            subject: message.subject.to_string(),
+            headers,
+            payload: message.payload,
+        }
+    }
+
+    pub fn header_map(&self) -> HeaderMap {
+        let mut headers: HeaderMap = HeaderMap::new();
+
+        for header in &self.headers {
+            headers.append(header.name.as_str(), header.value.as_str());
+        }
+
+        headers
+    }
+
+    /// Writes frame prefixed with its varint encoded length, returns written bytes
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<usize> {
+        let buffer: Vec<u8> = self.encode_length_delimited_to_vec();
+        writer.write_all(&buffer)?;
+        Ok(buffer.len())
+    }
+
+    /// Reads next length delimited frame, None at the end of the stream
+    pub fn read<R: Read>(reader: &mut R) -> Result<Option<Frame>> {
+        let length: usize = match read_delimiter(reader)? {
+            Some(length) => length,
+            None => return Ok(None),
+        };
+
+        let mut buffer: Vec<u8> = vec![0; length];
+        reader.read_exact(&mut buffer)?;
+
+        Ok(Some(Frame::decode(buffer.as_slice())?))
+    }
+}
+
+fn read_delimiter<R: Read>(reader: &mut R) -> Result<Option<usize>> {
+    let mut length: u64 = 0;
+
+    for index in 0..MAX_DELIMITER_LENGTH {
+        let mut byte: [u8; 1] = [0];
+
+        match reader.read_exact(&mut byte) {
+            Ok(()) => {}
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof && index == 0 => {
+                return Ok(None)
+            }
+            Err(error) => return Err(error.into()),
+        }
+
+        length |= u64::from(byte[0] & 0x7f) << (7 * index);
+
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(length as usize));
+        }
+    }
+
+    Err(anyhow!("Invalid frame length delimiter"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::{Frame, Header};
+    use prost::bytes::Bytes;
+    use std::io::Cursor;
+
+    fn frame(received_micros: i64, payload: &'static [u8]) -> Frame {
+        Frame {
+            received_micros,
+            subject: "cryptocom.ticker.btc.usd".to_string(),
+            headers: vec![Header {
+                name: "status".to_string(),
+                value: "ok".to_string(),
+            }],
+            payload: Bytes::from_static(payload),
+        }
+    }
+
+    #[test]
+    fn read_should_return_written_frames() {
+        let mut buffer: Vec<u8> = Vec::new();
+        frame(1, b"first").write(&mut buffer).unwrap();
+        frame(2, &[0; 300]).write(&mut buffer).unwrap();
+
+        let mut reader: Cursor<Vec<u8>> = Cursor::new(buffer);
+
+        assert_eq!(Frame::read(&mut reader).unwrap(), Some(frame(1, b"first")));
+        assert_eq!(Frame::read(&mut reader).unwrap(), Some(frame(2, &[0; 300])));
+        assert_eq!(Frame::read(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn header_map_should_restore_recorded_headers() {
+        let headers = frame(1, b"first").header_map();
+
+        assert_eq!(
+            headers.get("status").map(|value| value.as_str()),
+            Some("ok")
+        );
+    }
+}
@@ -0,0 +1,5 @@
+pub mod config;
+pub mod frame;
+pub mod reader;
+pub mod replay;
+pub mod writer;
@@ -0,0 +1,109 @@
+use crate::frame::Frame;
+use crate::writer::FILE_EXTENSION;
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use std::fs;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// Finished recordings of the directory in recording order, unfinished .part files are skipped
+pub fn files(directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(directory)
+        .with_context(|| format!("Cannot read directory {}", directory.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(FILE_EXTENSION))
+        })
+        .collect();
+
+    files.sort();
+    Ok(files)
+}
+
+pub struct FrameReader {
+    reader: BufReader<GzDecoder<File>>,
+}
+
+impl FrameReader {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file: File =
+            File::open(path).with_context(|| format!("Cannot open file {}", path.display()))?;
+
+        Ok(FrameReader {
+            reader: BufReader::new(GzDecoder::new(file)),
+        })
+    }
+}
+
+impl Iterator for FrameReader {
+    type Item = Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Frame::read(&mut self.reader).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::Frame;
+    use crate::reader::{files, FrameReader};
+    use crate::writer::RotatingWriter;
+    use prost::bytes::Bytes;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn frame(received_micros: i64) -> Frame {
+        Frame {
+            received_micros,
+            subject: "cryptocom.trades.btc.usd".to_string(),
+            headers: vec![],
+            payload: Bytes::from_static(b"trade"),
+        }
+    }
+
+    #[test]
+    fn files_should_return_rotated_recordings_in_order() {
+        let directory: PathBuf =
+            std::env::temp_dir().join(format!("recorder-{}", std::process::id()));
+        let mut writer: RotatingWriter =
+            RotatingWriter::new(&directory, Duration::from_secs(60), 100).unwrap();
+
+        for received in 0..4 {
+            writer.write(&frame(received)).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let recordings: Vec<PathBuf> = files(&directory).unwrap();
+        let frames: Vec<Frame> = recordings
+            .iter()
+            .flat_map(|path| FrameReader::open(path).unwrap())
+            .map(|frame| frame.unwrap())
+            .collect();
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(recordings.len(), 2);
+        assert_eq!(frames, (0..4).map(frame).collect::<Vec<Frame>>());
+    }
+
+    #[test]
+    fn rotate_should_finish_expired_recording_without_writes() {
+        let directory: PathBuf =
+            std::env::temp_dir().join(format!("recorder-rotate-{}", std::process::id()));
+        let mut writer: RotatingWriter =
+            RotatingWriter::new(&directory, Duration::ZERO, 100).unwrap();
+
+        writer.write(&frame(0)).unwrap();
+        writer.rotate().unwrap();
+
+        let recordings: Vec<PathBuf> = files(&directory).unwrap();
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(recordings.len(), 1);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::frame::Frame;
+use anyhow::Result;
+use async_nats::Subject;
+use log::info;
+use protocol::client::NatsClient;
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
+
+/// Republishes frames keeping the recorded gaps between them divided by speed
+pub struct Replay {
+    nats_client: NatsClient,
+    speed: f64,
+    subject_prefix: String,
+    started: Option<(i64, Instant)>,
+    published: u64,
+}
+
+impl Replay {
+    pub fn new(nats_client: NatsClient, speed: f64, subject_prefix: String) -> Self {
+        Replay {
+            nats_client,
+            speed,
+            subject_prefix,
+            started: None,
+            published: 0,
+        }
+    }
+
+    pub async fn publish(&mut self, frame: Frame) -> Result<()> {
+        let (first, start): (i64, Instant) = *self
+            .started
+            .get_or_insert((frame.received_micros, Instant::now()));
+
+        if let Some(delay) = delay(first, frame.received_micros, self.speed) {
+            sleep_until(start + delay).await;
+        }
+
+        let subject: Subject = Subject::from(format!("{}{}", self.subject_prefix, frame.subject));
+
+        self.nats_client
+            .publish(subject, frame.header_map(), frame.payload)
+            .await?;
+        self.published += 1;
+
+        Ok(())
+    }
+
+    pub async fn finish(self) -> Result<()> {
+        self.nats_client.flush().await?;
+        info!("Replayed {} frames", self.published);
+        Ok(())
+    }
+}
+
+/// Offset of the frame from the replay start, None when replaying without delays
+fn delay(first_micros: i64, received_micros: i64, speed: f64) -> Option<Duration> {
+    if speed <= 0.0 {
+        return None;
+    }
+
+    let elapsed: i64 = (received_micros - first_micros).max(0);
+    Some(Duration::from_micros((elapsed as f64 / speed) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::replay::delay;
+    use std::time::Duration;
+
+    #[test]
+    fn delay_should_scale_recorded_gap_by_speed() {
+        assert_eq!(delay(1_000, 3_000, 1.0), Some(Duration::from_micros(2_000)));
+        assert_eq!(delay(1_000, 3_000, 4.0), Some(Duration::from_micros(500)));
+        assert_eq!(delay(1_000, 500, 1.0), Some(Duration::ZERO));
+        assert_eq!(delay(1_000, 3_000, 0.0), None);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::frame::Frame;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::info;
+use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub const FILE_EXTENSION: &str = "frames.gz";
+const PART_EXTENSION: &str = "part";
+
+/// Gzip compressed file being written, renamed from .part when finished
+struct Segment {
+    path: PathBuf,
+    encoder: GzEncoder<BufWriter<File>>,
+    opened: Instant,
+    written: u64,
+}
+
+/// Writes frames into compressed files rotated by age or uncompressed size.
+/// Files are named by creation time so sorting by name restores the recording order.
+pub struct RotatingWriter {
+    directory: PathBuf,
+    max_age: Duration,
+    max_size: u64,
+    segment: Option<Segment>,
+}
+
+impl RotatingWriter {
+    pub fn new(directory: &Path, max_age: Duration, max_size: u64) -> Result<Self> {
+        fs::create_dir_all(directory)
+            .with_context(|| format!("Cannot create directory {}", directory.display()))?;
+
+        Ok(RotatingWriter {
+            directory: directory.to_path_buf(),
+            max_age,
+            max_size,
+            segment: None,
+        })
+    }
+
+    pub fn write(&mut self, frame: &Frame) -> Result<()> {
+        self.rotate()?;
+
+        let segment: &mut Segment = match self.segment {
+            Some(ref mut segment) => segment,
+            None => self.segment.insert(open(&self.directory)?),
+        };
+
+        segment.written += frame.write(&mut segment.encoder)? as u64;
+
+        Ok(())
+    }
+
+    /// Completes the current file once expired. Called periodically as well,
+    /// so recording of idle subjects is not left unfinished.
+    pub fn rotate(&mut self) -> Result<()> {
+        if self
+            .segment
+            .as_ref()
+            .is_some_and(|segment| self.expired(segment))
+        {
+            self.finish()?;
+        }
+
+        Ok(())
+    }
+
+    /// Completes the current file, the next write opens a new one
+    pub fn finish(&mut self) -> Result<()> {
+        if let Some(segment) = self.segment.take() {
+            segment.encoder.finish()?.flush()?;
+
+            let path: PathBuf = segment.path.with_extension("");
+            fs::rename(&segment.path, &path)?;
+
+            info!("Recorded {} bytes into {}", segment.written, path.display());
+        }
+
+        Ok(())
+    }
+
+    fn expired(&self, segment: &Segment) -> bool {
+        segment.written >= self.max_size || segment.opened.elapsed() >= self.max_age
+    }
+}
+
+fn open(directory: &Path) -> Result<Segment> {
+    let name: String = format!(
+        "{}.{}.{}",
+        Utc::now().format("%Y%m%d-%H%M%S%.6f"),
+        FILE_EXTENSION,
+        PART_EXTENSION
+    );
+    let path: PathBuf = directory.join(name);
+    let file: File =
+        File::create(&path).with_context(|| format!("Cannot create file {}", path.display()))?;
+
+    Ok(Segment {
+        path,
+        encoder: GzEncoder::new(BufWriter::new(file), Compression::default()),
+        opened: Instant::now(),
+        written: 0,
+    })
+}
@@ -0,0 +1,21 @@
+use protocol::model::{Currency, Symbol};
+
+/// Market symbol shared by the examples
+pub struct Market {
+    pub from: String,
+    pub to: String,
+}
+
+impl Symbol for Market {
+    fn from(&self) -> Currency {
+        Currency::new(self.from.clone())
+    }
+
+    fn to(&self) -> Currency {
+        Currency::new(self.to.clone())
+    }
+
+    fn exchange_format(&self) -> String {
+        format!("{}-{}", self.from(), self.to()).to_uppercase()
+    }
+}
@@ -1,9 +1,11 @@
+use crate::common::Market;
 use connectors_sdk::connector::PublicConnector;
 use protocol::client::{NatsClient, NatsConfig};
-use protocol::model::{Currency, Symbol};
 use protocol::public::market::{MarketType, MarketsMessage};
 use protocol::public::types::Exchange;
 
+mod common;
+
 #[tokio::main]
 async fn main() {
     let exchange: Exchange = Exchange::Cryptocom;
@@ -34,22 +36,3 @@ async fn main() {
 
     println!("{:?}", response);
 }
-
-pub struct Market {
-    pub from: String,
-    pub to: String,
-}
-
-impl Symbol for Market {
-    fn from(&self) -> Currency {
-        Currency::new(self.from.clone())
-    }
-
-    fn to(&self) -> Currency {
-        Currency::new(self.to.clone())
-    }
-
-    fn exchange_format(&self) -> String {
-        format!("{}-{}", self.from(), self.to()).to_uppercase()
-    }
-}
@@ -1,5 +1,6 @@
-use crate::markets_example::Market;
+use crate::common::Market;
 use connectors_sdk::connector::PublicConnector;
+use connectors_sdk::latency::LatencyBreakdown;
 use connectors_sdk::subscription::NatsStream;
 use futures::stream::Take;
 use futures::StreamExt;
@@ -7,7 +8,7 @@ use protocol::client::{NatsClient, NatsConfig};
 use protocol::public::ticker::TickerMessage;
 use protocol::public::types::Exchange;
 
-mod markets_example;
+mod common;
 
 #[tokio::main]
 async fn main() {
@@ -34,5 +35,6 @@ async fn main() {
 
     while let Some(message) = subscription.next().await {
         println!("{:?}", message);
+        println!("{:?}", LatencyBreakdown::now(&message));
     }
 }
@@ -1,4 +1,4 @@
-use crate::markets_example::Market;
+use crate::common::Market;
 use connectors_sdk::connector::PublicConnector;
 use connectors_sdk::subscription::NatsStream;
 use futures::stream::Take;
@@ -7,7 +7,7 @@ use protocol::client::{NatsClient, NatsConfig};
 use protocol::public::trade::TradesMessage;
 use protocol::public::types::Exchange;
 
-mod markets_example;
+mod common;
 
 #[tokio::main]
 async fn main() {
@@ -1,22 +1,33 @@
 use crate::decoder::{decode_message, parse_publish_error, parse_request_error};
-use crate::subscription::NatsStream;
+use crate::subscription::{NatsStream, DEFAULT_LEASE_INTERVAL};
 use protocol::client::NatsClient;
 use protocol::model::Symbol;
 use protocol::public::book::{OrderBookMessage, OrderBookRequest};
 use protocol::public::error::ErrorMessage;
-use protocol::public::market::{MarketType, MarketsMessage, MarketsRequest};
+use protocol::public::market::{MarketType, MarketsChangesMessage, MarketsMessage, MarketsRequest};
 use protocol::public::ticker::{TickerMessage, TickerRequest};
 use protocol::public::trade::{TradesMessage, TradesRequest};
 use protocol::public::types::Exchange;
-use protocol::topics::{RequestTopic, StreamTopic, Topic};
+use protocol::topics::{LeaseTopic, RequestTopic, StreamTopic, Topic};
+use std::time::Duration;
 
 pub struct PublicConnector {
     client: NatsClient,
+    lease_interval: Duration,
 }
 
 impl PublicConnector {
     pub fn new(client: NatsClient) -> Self {
-        PublicConnector { client }
+        PublicConnector {
+            client,
+            lease_interval: DEFAULT_LEASE_INTERVAL,
+        }
+    }
+
+    /// Lease renewal period of streams, has to be shorter than `lease_ttl` of connectors
+    pub fn lease_interval(mut self, lease_interval: Duration) -> Self {
+        self.lease_interval = lease_interval;
+        self
     }
 
     pub async fn markets<S: Symbol>(
@@ -32,6 +43,9 @@ impl PublicConnector {
         let request: MarketsRequest = MarketsRequest {
             symbols,
             market_type,
+            base_currencies: vec![],
+            quote_currencies: vec![],
+            patterns: vec![],
         };
 
         self.client
@@ -41,6 +55,30 @@ impl PublicConnector {
             .and_then(decode_message)
     }
 
+    /// Markets filtered by symbols, currencies and glob patterns like `*_usdt`
+    pub async fn filter_markets(
+        &self,
+        exchange: Exchange,
+        request: MarketsRequest,
+    ) -> Result<MarketsMessage, ErrorMessage> {
+        let topic: RequestTopic = RequestTopic::markets(exchange);
+
+        self.client
+            .send_request(topic, request)
+            .await
+            .map_err(parse_request_error)
+            .and_then(decode_message)
+    }
+
+    pub async fn markets_changes(
+        &self,
+        exchange: Exchange,
+    ) -> Result<NatsStream<MarketsChangesMessage>, ErrorMessage> {
+        let topic: RequestTopic = RequestTopic::markets(exchange);
+
+        NatsStream::new(&self.client, topic.changes()).await
+    }
+
     pub async fn ticker<S: Symbol>(
         &self,
         exchange: Exchange,
@@ -54,7 +92,8 @@ impl PublicConnector {
             .await
             .map_err(parse_publish_error)?;
 
-        NatsStream::new(&self.client, topic).await
+        let lease: LeaseTopic = topic.lease();
+        NatsStream::leased(&self.client, topic, lease, self.lease_interval).await
     }
 
     pub async fn trades<S: Symbol>(
@@ -70,7 +109,8 @@ impl PublicConnector {
             .await
             .map_err(parse_publish_error)?;
 
-        NatsStream::new(&self.client, topic).await
+        let lease: LeaseTopic = topic.lease();
+        NatsStream::leased(&self.client, topic, lease, self.lease_interval).await
     }
 
     pub async fn order_book<S: Symbol>(
@@ -79,13 +119,26 @@ impl PublicConnector {
         symbol: S,
     ) -> Result<NatsStream<OrderBookMessage>, ErrorMessage> {
         let topic: StreamTopic = StreamTopic::book(exchange, &symbol);
+
+        self.order_book_snapshot(exchange, symbol).await?;
+
+        let lease: LeaseTopic = topic.lease();
+        NatsStream::leased(&self.client, topic, lease, self.lease_interval).await
+    }
+
+    /// Requests order book snapshot published on the already subscribed stream,
+    /// e.g. to rebuild the book after a sequence gap
+    pub async fn order_book_snapshot<S: Symbol>(
+        &self,
+        exchange: Exchange,
+        symbol: S,
+    ) -> Result<(), ErrorMessage> {
+        let topic: StreamTopic = StreamTopic::book(exchange, &symbol);
         let snapshot: OrderBookRequest = OrderBookRequest {};
 
         self.client
             .send_message(topic.snapshot(), snapshot)
             .await
-            .map_err(parse_publish_error)?;
-
-        NatsStream::new(&self.client, topic).await
+            .map_err(parse_publish_error)
     }
 }
@@ -0,0 +1,88 @@
+use chrono::Utc;
+use protocol::latency::Stamped;
+use protocol::public::types::Latency;
+use std::time::Duration;
+
+/// Where the time between the exchange event and the delivery to the client was spent.
+/// Components crossing hosts depend on clock sync and are zero for negative skew.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyBreakdown {
+    /// Exchange timestamp to connector receive, None without exchange timestamp
+    pub exchange: Option<Duration>,
+    /// Connector receive to NATS publish
+    pub connector: Duration,
+    /// NATS publish to client delivery
+    pub nats: Duration,
+    /// Replica id of the publishing connector
+    pub instance: String,
+}
+
+impl LatencyBreakdown {
+    /// Breakdown of the message delivered at the given time in micros,
+    /// None for messages without latency stamps
+    pub fn new<M: Stamped>(message: &M, delivered_micros: i64) -> Option<Self> {
+        let latency: &Latency = message.latency()?;
+
+        Some(LatencyBreakdown {
+            exchange: message
+                .exchange_timestamp()
+                .map(|timestamp| between(timestamp * 1000, latency.received_micros)),
+            connector: between(latency.received_micros, latency.published_micros),
+            nats: between(latency.published_micros, delivered_micros),
+            instance: latency.instance.clone(),
+        })
+    }
+
+    /// Breakdown of the message delivered now, should be called as soon as message is received
+    pub fn now<M: Stamped>(message: &M) -> Option<Self> {
+        LatencyBreakdown::new(message, Utc::now().timestamp_micros())
+    }
+
+    pub fn total(&self) -> Duration {
+        self.exchange.unwrap_or_default() + self.connector + self.nats
+    }
+}
+
+fn between(from_micros: i64, to_micros: i64) -> Duration {
+    Duration::from_micros((to_micros - from_micros).max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::latency::LatencyBreakdown;
+    use protocol::public::ticker::{Tick, TickerMessage};
+    use protocol::public::types::Latency;
+    use std::time::Duration;
+
+    #[test]
+    fn new_should_split_latency_between_exchange_connector_and_nats() {
+        let message: TickerMessage = TickerMessage {
+            tick: Some(Tick {
+                timestamp: 1700000000000,
+                ..Tick::default()
+            }),
+            latency: Some(Latency {
+                received_micros: 1700000000020000,
+                published_micros: 1700000000020500,
+                instance: "replica-1".to_string(),
+            }),
+            ..TickerMessage::default()
+        };
+
+        let result: LatencyBreakdown = LatencyBreakdown::new(&message, 1700000000021500).unwrap();
+
+        assert_eq!(result.exchange, Some(Duration::from_millis(20)));
+        assert_eq!(result.connector, Duration::from_micros(500));
+        assert_eq!(result.nats, Duration::from_millis(1));
+        assert_eq!(result.total(), Duration::from_micros(21500));
+        assert_eq!(result.instance, "replica-1");
+    }
+
+    #[test]
+    fn new_should_return_none_without_stamps() {
+        assert_eq!(
+            LatencyBreakdown::new(&TickerMessage::default(), 1700000000021500),
+            None
+        );
+    }
+}
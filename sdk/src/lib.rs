@@ -1,5 +1,6 @@
 pub mod connector;
 mod decoder;
+pub mod latency;
 pub mod subscription;
 
 pub fn add(left: u64, right: u64) -> u64 {
@@ -1,18 +1,32 @@
 use crate::decoder::parse_subscribe_error;
 use async_nats::subject::ToSubject;
+use async_nats::Subject;
 use async_nats::Subscriber;
 use futures::{Stream, StreamExt};
-use log::{error, info};
+use log::{error, info, warn};
 use prost::Message as ProtoMessage;
 use protocol::client::NatsClient;
 use protocol::public::error::ErrorMessage;
+use protocol::public::types::LeaseRequest;
+use protocol::trace;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::mpsc::{Receiver, Sender, WeakSender};
+use tokio::time::Interval;
+use tracing::Span;
 
+/// Renewal period of leases, shorter than the default lease ttl of connectors (60s)
+pub const DEFAULT_LEASE_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Decoded message with the remote span of its publisher
+type Received<E> = (E, Span);
+
+/// Messages are received with the remote span of their publisher, see `span`
 pub struct NatsStream<E> {
-    receiver: Receiver<E>,
+    receiver: Receiver<Received<E>>,
+    span: Span,
 }
 
 impl<E: ProtoMessage + Default + 'static> NatsStream<E> {
@@ -20,17 +34,31 @@ impl<E: ProtoMessage + Default + 'static> NatsStream<E> {
         nats_client: &NatsClient,
         topic: T,
     ) -> Result<Self, ErrorMessage> {
+        NatsStream::subscribe(nats_client, topic)
+            .await
+            .map(|(stream, _)| stream)
+    }
+
+    /// Returns stream with weak sender to check if the stream is still consumed
+    async fn subscribe<T: ToSubject>(
+        nats_client: &NatsClient,
+        topic: T,
+    ) -> Result<(Self, WeakSender<Received<E>>), ErrorMessage> {
         info!("Subscribe to nats topic {}", topic.to_subject());
 
-        let (sender, receiver): (Sender<E>, Receiver<E>) = mpsc::channel::<E>(100);
+        let (sender, receiver): (Sender<Received<E>>, Receiver<Received<E>>) =
+            mpsc::channel::<Received<E>>(100);
 
         let mut subscriber: Subscriber = nats_client
             .subscribe(topic)
             .await
             .map_err(parse_subscribe_error)?;
 
+        let weak: WeakSender<Received<E>> = sender.downgrade();
+
         tokio::spawn(async move {
             while let Some(message) = subscriber.next().await {
+                let span: Span = trace::remote_span(&message.subject, message.headers.as_ref());
                 let event: E = match E::decode(message.payload) {
                     Ok(event) => event,
                     Err(error) => {
@@ -40,7 +68,7 @@ impl<E: ProtoMessage + Default + 'static> NatsStream<E> {
                     }
                 };
 
-                match sender.send(event).await {
+                match sender.send((event, span)).await {
                     Ok(_) => {}
                     Err(error) => {
                         error!("Cannot publish message {}", error);
@@ -51,14 +79,71 @@ impl<E: ProtoMessage + Default + 'static> NatsStream<E> {
             }
         });
 
-        Ok(NatsStream { receiver })
+        let stream: NatsStream<E> = NatsStream {
+            receiver,
+            span: Span::none(),
+        };
+
+        Ok((stream, weak))
     }
+
+    /// Stream which renews consumer interest on the lease topic every interval until it is dropped.
+    /// Connectors unsubscribe markets without active leases, interval has to be shorter than
+    /// their lease ttl.
+    pub async fn leased<T: ToSubject, L: ToSubject>(
+        nats_client: &NatsClient,
+        topic: T,
+        lease: L,
+        interval: Duration,
+    ) -> Result<Self, ErrorMessage> {
+        let (stream, sender): (NatsStream<E>, WeakSender<Received<E>>) =
+            NatsStream::subscribe(nats_client, topic).await?;
+        let client: NatsClient = nats_client.clone();
+        let lease: Subject = lease.to_subject();
+
+        tokio::spawn(async move {
+            let mut interval: Interval = tokio::time::interval(interval);
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                if !is_open(&sender) {
+                    break;
+                }
+
+                if let Err(error) = client.send_message(lease.clone(), LeaseRequest {}).await {
+                    warn!("Cannot renew lease {}: {}", lease, error);
+                }
+            }
+        });
+
+        Ok(stream)
+    }
+}
+
+impl<E> NatsStream<E> {
+    /// Span continuing the trace context of the last received message, consumers enter it or
+    /// set it as parent to join the connector trace
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+/// False when the stream is dropped or the nats subscription is finished
+fn is_open<E>(sender: &WeakSender<E>) -> bool {
+    sender.upgrade().is_some_and(|sender| !sender.is_closed())
 }
 
 impl<T> Stream for NatsStream<T> {
     type Item = T;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.receiver.poll_recv(cx)
+        self.receiver.poll_recv(cx).map(|received| {
+            received.map(|(message, span)| {
+                self.span = span;
+                message
+            })
+        })
     }
 }